@@ -0,0 +1,119 @@
+//! Cooperative cancellation of model calls.
+
+use std::future::Future;
+
+use futures::future::{self, Either};
+use tokio_util::sync::CancellationToken;
+
+use ai_error::{AiError, Result};
+
+/// A cloneable signal used to cancel in-flight work.
+///
+/// All clones share the same state: calling [`AbortSignal::abort`] on any of
+/// them cancels every operation observing the signal.
+#[derive(Debug, Clone, Default)]
+pub struct AbortSignal {
+    token: CancellationToken,
+}
+
+impl AbortSignal {
+    /// Creates a new, untriggered signal.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Triggers the signal.
+    pub fn abort(&self) {
+        self.token.cancel();
+    }
+
+    /// Returns `true` once the signal has been triggered.
+    pub fn is_aborted(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Completes when the signal is triggered.
+    pub async fn aborted(&self) {
+        self.token.cancelled().await
+    }
+
+    /// Runs `future` until it completes or the signal is triggered.
+    ///
+    /// On abort the future is dropped, which cancels any HTTP request it was
+    /// awaiting. Used by [`crate::generate_text`] and, through
+    /// [`crate::ToolExecutionContext::run`], by tool executions.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AiError::Cancelled`] if the signal fires first, including
+    /// when it was already triggered.
+    pub async fn run_until_aborted<F: Future>(&self, future: F) -> Result<F::Output> {
+        if self.is_aborted() {
+            return Err(AiError::Cancelled);
+        }
+        let aborted = std::pin::pin!(self.aborted());
+        let future = std::pin::pin!(future);
+        match future::select(future, aborted).await {
+            Either::Left((output, _)) => Ok(output),
+            Either::Right(_) => Err(AiError::Cancelled),
+        }
+    }
+
+    /// Creates a signal that is triggered together with this one but can also
+    /// be triggered on its own without affecting the parent.
+    pub fn child(&self) -> Self {
+        Self {
+            token: self.token.child_token(),
+        }
+    }
+}
+
+impl From<CancellationToken> for AbortSignal {
+    fn from(token: CancellationToken) -> Self {
+        Self { token }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_abort_is_shared_between_clones() {
+        let signal = AbortSignal::new();
+        let observer = signal.clone();
+        assert!(!observer.is_aborted());
+
+        signal.abort();
+        observer.aborted().await;
+        assert!(observer.is_aborted());
+    }
+
+    #[tokio::test]
+    async fn test_run_until_aborted() {
+        let signal = AbortSignal::new();
+        assert_eq!(signal.run_until_aborted(async { 7 }).await.unwrap(), 7);
+
+        let trigger = signal.clone();
+        let result = signal
+            .run_until_aborted(async move {
+                trigger.abort();
+                std::future::pending::<()>().await
+            })
+            .await;
+        assert!(matches!(result, Err(AiError::Cancelled)));
+    }
+
+    #[test]
+    fn test_child_does_not_abort_parent() {
+        let parent = AbortSignal::new();
+        let child = parent.child();
+
+        child.abort();
+        assert!(!parent.is_aborted());
+
+        let child = parent.child();
+        parent.abort();
+        assert!(child.is_aborted());
+    }
+}
@@ -0,0 +1,170 @@
+//! Model capability metadata and request validation.
+
+use serde::{Deserialize, Serialize};
+
+use crate::message::MessagePart;
+use crate::model::{CallOptions, LanguageModel};
+use ai_error::{AiError, Result};
+
+/// Features a model supports, as declared by its provider.
+///
+/// Returned by [`LanguageModel::capabilities`] and used by
+/// [`crate::generate_text`] and [`crate::stream_text`] to reject requests the
+/// model cannot serve before anything is sent to the vendor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelCapabilities {
+    /// Accepts tool definitions and emits tool calls.
+    pub tool_calling: bool,
+    /// Can be constrained to a JSON schema.
+    pub structured_outputs: bool,
+    /// Accepts image parts (vision input).
+    pub image_input: bool,
+    /// Accepts file parts such as PDFs.
+    pub file_input: bool,
+    /// Emits reasoning content.
+    pub reasoning: bool,
+    /// Supports [`LanguageModel::do_stream`].
+    pub streaming: bool,
+    /// Size of the context window in tokens, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_context_tokens: Option<u32>,
+}
+
+impl ModelCapabilities {
+    /// Checks that `options` only uses features this model supports.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AiError::Validation`] describing the first unsupported
+    /// feature found.
+    pub fn validate(&self, options: &CallOptions) -> Result<()> {
+        if !options.tools.is_empty() && !self.tool_calling {
+            return Err(unsupported("tool calling"));
+        }
+
+        for part in options.messages.iter().flat_map(|m| &m.parts) {
+            match part {
+                MessagePart::Image(_) if !self.image_input => {
+                    return Err(unsupported("image input"));
+                }
+                MessagePart::File(_) if !self.file_input => {
+                    return Err(unsupported("file input"));
+                }
+                _ => {}
+            }
+        }
+
+        if let (Some(max_output), Some(context)) =
+            (options.settings.max_output_tokens, self.max_context_tokens)
+        {
+            if max_output > context {
+                return Err(AiError::Validation(format!(
+                    "max_output_tokens ({max_output}) exceeds the model context window ({context})"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Validates a call against the model's capabilities, naming the model in
+/// the error.
+pub(crate) fn validate_call(
+    model: &dyn LanguageModel,
+    options: &CallOptions,
+    streaming: bool,
+) -> Result<()> {
+    let capabilities = model.capabilities();
+    let result = if streaming && !capabilities.streaming {
+        Err(unsupported("streaming"))
+    } else {
+        capabilities.validate(options)
+    };
+
+    result.map_err(|error| match error {
+        AiError::Validation(message) => AiError::Validation(format!(
+            "{}:{}: {}",
+            model.provider(),
+            model.model_id(),
+            message
+        )),
+        other => other,
+    })
+}
+
+fn unsupported(feature: &str) -> AiError {
+    AiError::Validation(format!("model does not support {feature}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::{DataContent, Message, MessageRole};
+    use crate::settings::CallSettings;
+    use crate::tool::ToolDefinition;
+
+    fn options_with(part: MessagePart) -> CallOptions {
+        CallOptions {
+            messages: vec![Message::new(MessageRole::User, vec![part])],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_rejects_image_for_text_only_model() {
+        let text_only = ModelCapabilities::default();
+        let options = options_with(MessagePart::image(
+            DataContent::Url("https://example.com/a.png".into()),
+            None,
+        ));
+
+        assert!(matches!(
+            text_only.validate(&options),
+            Err(AiError::Validation(_))
+        ));
+
+        let vision = ModelCapabilities {
+            image_input: true,
+            ..Default::default()
+        };
+        assert!(vision.validate(&options).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_tools_without_tool_calling() {
+        let options = CallOptions {
+            tools: vec![ToolDefinition::new(
+                "weather",
+                "Current weather",
+                serde_json::json!({ "type": "object" }),
+            )],
+            ..options_with(MessagePart::text("hi"))
+        };
+
+        assert!(ModelCapabilities::default().validate(&options).is_err());
+        let tool_model = ModelCapabilities {
+            tool_calling: true,
+            ..Default::default()
+        };
+        assert!(tool_model.validate(&options).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_output_larger_than_context() {
+        let capabilities = ModelCapabilities {
+            max_context_tokens: Some(8_192),
+            ..Default::default()
+        };
+        let options = CallOptions {
+            settings: CallSettings {
+                max_output_tokens: Some(10_000),
+                ..Default::default()
+            },
+            ..options_with(MessagePart::text("hi"))
+        };
+
+        assert!(capabilities.validate(&options).is_err());
+    }
+}
@@ -0,0 +1,358 @@
+//! Text embeddings.
+//!
+//! Provider crates implement [`EmbeddingModel`]; applications call [`embed`]
+//! for a single value or [`embed_many`] for bulk jobs. `embed_many` splits
+//! the input into batches no larger than
+//! [`EmbeddingModel::max_embeddings_per_call`] and runs them concurrently,
+//! returning embeddings in input order.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::stream::{self, StreamExt, TryStreamExt};
+
+use crate::pricing::{CostEstimate, PricingTable};
+use crate::settings::ProviderOptions;
+use crate::usage::Usage;
+use ai_error::{AiError, Result};
+
+/// A dense vector representation of a value.
+pub type Embedding = Vec<f32>;
+
+/// Number of batches [`embed_many`] runs concurrently unless configured.
+pub const DEFAULT_MAX_PARALLEL_CALLS: usize = 4;
+
+/// A model that turns text into embeddings.
+#[async_trait]
+pub trait EmbeddingModel: Send + Sync {
+    /// Name of the provider serving this model (e.g. `"openai"`).
+    fn provider(&self) -> &str;
+
+    /// Provider-specific model identifier (e.g. `"text-embedding-3-small"`).
+    fn model_id(&self) -> &str;
+
+    /// Largest number of values accepted by a single [`do_embed`](Self::do_embed)
+    /// call, or `None` if unlimited.
+    fn max_embeddings_per_call(&self) -> Option<usize>;
+
+    /// Embeds one batch of values. The response must contain exactly one
+    /// embedding per input value, in input order.
+    async fn do_embed(&self, options: EmbedOptions) -> Result<EmbeddingResponse>;
+}
+
+/// Normalized input handed to [`EmbeddingModel::do_embed`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EmbedOptions {
+    /// Values to embed.
+    pub values: Vec<String>,
+    /// Additional HTTP headers to send with the request.
+    pub headers: HashMap<String, String>,
+    /// Vendor-specific options keyed by provider name.
+    pub provider_options: ProviderOptions,
+}
+
+/// Result of a single [`EmbeddingModel::do_embed`] call.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EmbeddingResponse {
+    /// One embedding per input value.
+    pub embeddings: Vec<Embedding>,
+    /// Tokens consumed by the call.
+    pub usage: Usage,
+}
+
+/// Input for [`embed`].
+pub struct EmbedRequest {
+    /// Model used for the call.
+    pub model: Box<dyn EmbeddingModel>,
+    /// Value to embed.
+    pub value: String,
+    /// Additional HTTP headers to send with the request.
+    pub headers: HashMap<String, String>,
+    /// Vendor-specific options keyed by provider name.
+    pub provider_options: ProviderOptions,
+    /// Prices used to attach a [`CostEstimate`] to the response.
+    pub pricing: Option<Arc<dyn PricingTable>>,
+}
+
+impl EmbedRequest {
+    /// Creates a request embedding `value` with `model`.
+    pub fn new(model: impl EmbeddingModel + 'static, value: impl Into<String>) -> Self {
+        Self {
+            model: Box::new(model),
+            value: value.into(),
+            headers: HashMap::new(),
+            provider_options: ProviderOptions::new(),
+            pricing: None,
+        }
+    }
+
+    /// Sets the prices used to estimate the cost of the call.
+    pub fn pricing(mut self, pricing: Arc<dyn PricingTable>) -> Self {
+        self.pricing = Some(pricing);
+        self
+    }
+}
+
+/// Output of [`embed`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EmbedResponse {
+    /// Embedding of the input value.
+    pub embedding: Embedding,
+    /// Tokens consumed by the call.
+    pub usage: Usage,
+    /// Estimated cost, if the request carried a pricing table that prices
+    /// the model.
+    pub cost: Option<CostEstimate>,
+}
+
+/// Input for [`embed_many`].
+pub struct EmbedManyRequest {
+    /// Model used for the calls.
+    pub model: Box<dyn EmbeddingModel>,
+    /// Values to embed.
+    pub values: Vec<String>,
+    /// Maximum number of batches in flight at once.
+    pub max_parallel_calls: usize,
+    /// Additional HTTP headers to send with every request.
+    pub headers: HashMap<String, String>,
+    /// Vendor-specific options keyed by provider name.
+    pub provider_options: ProviderOptions,
+    /// Prices used to attach a [`CostEstimate`] to the response.
+    pub pricing: Option<Arc<dyn PricingTable>>,
+}
+
+impl EmbedManyRequest {
+    /// Creates a request embedding `values` with `model`.
+    pub fn new(model: impl EmbeddingModel + 'static, values: Vec<String>) -> Self {
+        Self {
+            model: Box::new(model),
+            values,
+            max_parallel_calls: DEFAULT_MAX_PARALLEL_CALLS,
+            headers: HashMap::new(),
+            provider_options: ProviderOptions::new(),
+            pricing: None,
+        }
+    }
+
+    /// Sets the prices used to estimate the cost of the job.
+    pub fn pricing(mut self, pricing: Arc<dyn PricingTable>) -> Self {
+        self.pricing = Some(pricing);
+        self
+    }
+
+    /// Sets the maximum number of batches in flight at once.
+    pub fn max_parallel_calls(mut self, max_parallel_calls: usize) -> Self {
+        self.max_parallel_calls = max_parallel_calls;
+        self
+    }
+}
+
+/// Output of [`embed_many`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EmbedManyResponse {
+    /// One embedding per input value, in input order.
+    pub embeddings: Vec<Embedding>,
+    /// Tokens consumed across all batches.
+    pub usage: Usage,
+    /// Estimated cost of all batches, if the request carried a pricing table
+    /// that prices the model.
+    pub cost: Option<CostEstimate>,
+}
+
+/// Embeds a single value.
+///
+/// # Errors
+///
+/// Propagates any error reported by the model, and returns
+/// [`AiError::Provider`] if the model does not return exactly one embedding.
+pub async fn embed(request: EmbedRequest) -> Result<EmbedResponse> {
+    let model = request.model.as_ref();
+    let response = embed_batch(
+        model,
+        EmbedOptions {
+            values: vec![request.value],
+            headers: request.headers,
+            provider_options: request.provider_options,
+        },
+    )
+    .await?;
+
+    Ok(EmbedResponse {
+        embedding: response.embeddings.into_iter().next().unwrap_or_default(),
+        usage: response.usage,
+        cost: estimate(request.pricing.as_deref(), model, &response.usage),
+    })
+}
+
+/// Embeds many values, batching and parallelizing calls to the model.
+///
+/// # Errors
+///
+/// Returns [`AiError::Validation`] if `max_parallel_calls` is zero, and
+/// fails with the first error reported by any batch.
+pub async fn embed_many(request: EmbedManyRequest) -> Result<EmbedManyResponse> {
+    if request.max_parallel_calls == 0 {
+        return Err(AiError::Validation(
+            "max_parallel_calls must be at least 1".into(),
+        ));
+    }
+
+    let model = request.model.as_ref();
+    let batch_size = match model.max_embeddings_per_call() {
+        Some(0) | None => request.values.len().max(1),
+        Some(limit) => limit,
+    };
+
+    let batches = request
+        .values
+        .chunks(batch_size)
+        .map(|values| EmbedOptions {
+            values: values.to_vec(),
+            headers: request.headers.clone(),
+            provider_options: request.provider_options.clone(),
+        });
+
+    // `buffered` preserves batch order while keeping up to
+    // `max_parallel_calls` requests in flight.
+    let responses: Vec<EmbeddingResponse> = stream::iter(batches)
+        .map(|options| embed_batch(model, options))
+        .buffered(request.max_parallel_calls)
+        .try_collect()
+        .await?;
+
+    let mut result = EmbedManyResponse {
+        embeddings: Vec::with_capacity(request.values.len()),
+        ..Default::default()
+    };
+    for response in responses {
+        result.embeddings.extend(response.embeddings);
+        result.usage += response.usage;
+    }
+    result.cost = estimate(request.pricing.as_deref(), model, &result.usage);
+    Ok(result)
+}
+
+fn estimate(
+    pricing: Option<&dyn PricingTable>,
+    model: &dyn EmbeddingModel,
+    usage: &Usage,
+) -> Option<CostEstimate> {
+    pricing?.estimate(model.provider(), model.model_id(), usage)
+}
+
+async fn embed_batch(
+    model: &dyn EmbeddingModel,
+    options: EmbedOptions,
+) -> Result<EmbeddingResponse> {
+    let expected = options.values.len();
+    let response = model.do_embed(options).await?;
+
+    if response.embeddings.len() != expected {
+        return Err(AiError::Provider {
+            provider: model.provider().to_string(),
+            message: format!(
+                "expected {} embeddings but received {}",
+                expected,
+                response.embeddings.len()
+            ),
+            code: None,
+            details: None,
+        });
+    }
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pricing::{ModelPricing, StaticPricingTable};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    /// Embeds each value as `[len]` and records batch sizes and concurrency.
+    #[derive(Default)]
+    struct LengthModel {
+        batches: Arc<std::sync::Mutex<Vec<usize>>>,
+        in_flight: Arc<AtomicUsize>,
+        peak: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl EmbeddingModel for LengthModel {
+        fn provider(&self) -> &str {
+            "test"
+        }
+
+        fn model_id(&self) -> &str {
+            "length"
+        }
+
+        fn max_embeddings_per_call(&self) -> Option<usize> {
+            Some(3)
+        }
+
+        async fn do_embed(&self, options: EmbedOptions) -> Result<EmbeddingResponse> {
+            let current = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(current, Ordering::SeqCst);
+            self.batches.lock().unwrap().push(options.values.len());
+
+            // Later batches finish first to prove ordering is preserved.
+            let delay = 10 * (4 - options.values[0].len().min(4)) as u64;
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+            Ok(EmbeddingResponse {
+                embeddings: options
+                    .values
+                    .iter()
+                    .map(|value| vec![value.len() as f32])
+                    .collect(),
+                usage: Usage::new(options.values.len() as u64, 0),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_embed_many_batches_in_order() {
+        let model = LengthModel::default();
+        let batches = Arc::clone(&model.batches);
+        let peak = Arc::clone(&model.peak);
+        let values: Vec<String> = (1..=8).map(|n| "x".repeat(n)).collect();
+
+        let response = embed_many(EmbedManyRequest::new(model, values).max_parallel_calls(2))
+            .await
+            .unwrap();
+
+        let lengths: Vec<f32> = response.embeddings.iter().map(|e| e[0]).collect();
+        assert_eq!(lengths, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0]);
+        assert_eq!(response.usage.input_tokens, 8);
+        assert_eq!(response.cost, None);
+
+        let mut sizes = batches.lock().unwrap().clone();
+        sizes.sort_unstable();
+        assert_eq!(sizes, vec![2, 3, 3]);
+        assert!(peak.load(Ordering::SeqCst) <= 2);
+    }
+
+    #[tokio::test]
+    async fn test_embed_single_value() {
+        let pricing = StaticPricingTable::new().with("test", "length", ModelPricing::new(0.5, 0.0));
+        let response =
+            embed(EmbedRequest::new(LengthModel::default(), "abcd").pricing(Arc::new(pricing)))
+                .await
+                .unwrap();
+        assert_eq!(response.embedding, vec![4.0]);
+        assert_eq!(response.cost.unwrap().total_cost(), 0.5 / 1_000_000.0);
+    }
+
+    #[tokio::test]
+    async fn test_embed_many_rejects_zero_parallelism() {
+        let request =
+            EmbedManyRequest::new(LengthModel::default(), vec!["a".into()]).max_parallel_calls(0);
+        assert!(matches!(
+            embed_many(request).await,
+            Err(AiError::Validation(_))
+        ));
+    }
+}
@@ -0,0 +1,617 @@
+//! Non-streaming text generation.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::abort::AbortSignal;
+use crate::capabilities::validate_call;
+use crate::message::{Message, MessagePart, MessageRole, ToolCallPart};
+use crate::model::{LanguageModel, ModelResponse, UnsetModel};
+use crate::pricing::{CostEstimate, PricingTable};
+use crate::settings::{CallSettings, ProviderOptions};
+use crate::tool::{ToolChoice, ToolDefinition};
+use crate::types::{CallWarning, FinishReason, ProviderMetadata, Source};
+use crate::usage::Usage;
+use ai_error::{AiError, Result};
+
+/// Input for [`generate_text`].
+///
+/// Provide either `prompt` (a single user turn) or `messages` (a full
+/// conversation), not both.
+///
+/// The [`Default`] implementation leaves `model` unset; calling
+/// [`generate_text`] without replacing it fails with [`AiError::Config`].
+pub struct GenerateTextRequest {
+    /// Model used for the call.
+    pub model: Box<dyn LanguageModel>,
+    /// Optional system instruction.
+    pub system: Option<String>,
+    /// User prompt.
+    pub prompt: String,
+    /// Conversation history.
+    pub messages: Vec<Message>,
+    /// Sampling and length settings.
+    pub settings: CallSettings,
+    /// Additional HTTP headers to send with the request.
+    pub headers: HashMap<String, String>,
+    /// Tools the model may call.
+    pub tools: Vec<ToolDefinition>,
+    /// How the model should choose among `tools`.
+    pub tool_choice: Option<ToolChoice>,
+    /// Vendor-specific options keyed by provider name.
+    pub provider_options: ProviderOptions,
+    /// Signal that cancels the call when triggered.
+    pub abort_signal: Option<AbortSignal>,
+    /// Prices used to attach a [`CostEstimate`] to the response.
+    pub pricing: Option<Arc<dyn PricingTable>>,
+}
+
+/// Implements the builders and option normalization shared by
+/// [`GenerateTextRequest`] and [`crate::StreamTextRequest`].
+macro_rules! text_request_methods {
+    ($request:ty) => {
+        impl $request {
+            /// Creates a request for `model` with the given prompt.
+            pub fn new(
+                model: impl $crate::LanguageModel + 'static,
+                prompt: impl Into<String>,
+            ) -> Self {
+                Self {
+                    model: Box::new(model),
+                    prompt: prompt.into(),
+                    ..Default::default()
+                }
+            }
+
+            /// Creates a request for `model` continuing the given conversation.
+            pub fn from_messages(
+                model: impl $crate::LanguageModel + 'static,
+                messages: Vec<$crate::Message>,
+            ) -> Self {
+                Self {
+                    model: Box::new(model),
+                    messages,
+                    ..Default::default()
+                }
+            }
+
+            /// Sets the system instruction.
+            pub fn system(mut self, system: impl Into<String>) -> Self {
+                self.system = Some(system.into());
+                self
+            }
+
+            /// Replaces all sampling and length settings.
+            pub fn settings(mut self, settings: $crate::CallSettings) -> Self {
+                self.settings = settings;
+                self
+            }
+
+            /// Sets the sampling temperature.
+            pub fn temperature(mut self, temperature: f32) -> Self {
+                self.settings.temperature = Some(temperature);
+                self
+            }
+
+            /// Sets the nucleus sampling probability mass.
+            pub fn top_p(mut self, top_p: f32) -> Self {
+                self.settings.top_p = Some(top_p);
+                self
+            }
+
+            /// Only samples from the top K options for each token.
+            pub fn top_k(mut self, top_k: u32) -> Self {
+                self.settings.top_k = Some(top_k);
+                self
+            }
+
+            /// Sets the maximum number of tokens to generate.
+            pub fn max_output_tokens(mut self, max_output_tokens: u32) -> Self {
+                self.settings.max_output_tokens = Some(max_output_tokens);
+                self
+            }
+
+            /// Adds a sequence that stops generation when produced.
+            pub fn stop_sequence(mut self, stop_sequence: impl Into<String>) -> Self {
+                self.settings.stop_sequences.push(stop_sequence.into());
+                self
+            }
+
+            /// Sets the seed for deterministic sampling.
+            pub fn seed(mut self, seed: u64) -> Self {
+                self.settings.seed = Some(seed);
+                self
+            }
+
+            /// Sets the penalty for tokens that already appeared in the text.
+            pub fn presence_penalty(mut self, presence_penalty: f32) -> Self {
+                self.settings.presence_penalty = Some(presence_penalty);
+                self
+            }
+
+            /// Sets the penalty proportional to how often a token already appeared.
+            pub fn frequency_penalty(mut self, frequency_penalty: f32) -> Self {
+                self.settings.frequency_penalty = Some(frequency_penalty);
+                self
+            }
+
+            /// Adds an HTTP header sent with the request.
+            pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+                self.headers.insert(name.into(), value.into());
+                self
+            }
+
+            /// Offers a tool to the model.
+            pub fn tool(mut self, tool: $crate::ToolDefinition) -> Self {
+                self.tools.push(tool);
+                self
+            }
+
+            /// Sets how the model should choose among the offered tools.
+            pub fn tool_choice(mut self, tool_choice: $crate::ToolChoice) -> Self {
+                self.tool_choice = Some(tool_choice);
+                self
+            }
+
+            /// Sets the options passed through to `provider`.
+            pub fn provider_options(
+                mut self,
+                provider: impl Into<String>,
+                options: serde_json::Value,
+            ) -> Self {
+                self.provider_options.insert(provider.into(), options);
+                self
+            }
+
+            /// Sets the signal that cancels the call.
+            pub fn abort_signal(mut self, signal: $crate::AbortSignal) -> Self {
+                self.abort_signal = Some(signal);
+                self
+            }
+
+            /// Sets the prices used to estimate the cost of the call.
+            pub fn pricing(mut self, pricing: std::sync::Arc<dyn $crate::PricingTable>) -> Self {
+                self.pricing = Some(pricing);
+                self
+            }
+
+            fn call_options(&self) -> $crate::Result<$crate::CallOptions> {
+                self.settings.validate()?;
+                $crate::tool::validate_tools(&self.tools, self.tool_choice.as_ref())?;
+
+                Ok($crate::CallOptions {
+                    messages: $crate::generate::prompt_messages(
+                        &self.system,
+                        &self.prompt,
+                        &self.messages,
+                    )?,
+                    settings: self.settings.clone(),
+                    tools: self.tools.clone(),
+                    tool_choice: self.tool_choice.clone(),
+                    headers: self.headers.clone(),
+                    provider_options: self.provider_options.clone(),
+                    abort_signal: self.abort_signal.clone(),
+                })
+            }
+        }
+    };
+}
+pub(crate) use text_request_methods;
+
+text_request_methods!(GenerateTextRequest);
+
+/// Normalizes the prompt fields shared by all text requests into messages.
+pub(crate) fn prompt_messages(
+    system: &Option<String>,
+    prompt: &str,
+    messages: &[Message],
+) -> Result<Vec<Message>> {
+    let has_prompt = !prompt.trim().is_empty();
+    if has_prompt && !messages.is_empty() {
+        return Err(AiError::Validation(
+            "prompt and messages cannot both be set".into(),
+        ));
+    }
+    if !has_prompt && messages.is_empty() {
+        return Err(AiError::Validation(
+            "either prompt or messages must be set".into(),
+        ));
+    }
+
+    let mut normalized = Vec::with_capacity(messages.len() + 2);
+    if let Some(system) = system {
+        normalized.push(Message::system(system.clone()));
+    }
+    if has_prompt {
+        normalized.push(Message::user(prompt));
+    }
+    normalized.extend_from_slice(messages);
+
+    Ok(normalized)
+}
+
+impl Default for GenerateTextRequest {
+    fn default() -> Self {
+        Self {
+            model: Box::new(UnsetModel),
+            system: None,
+            prompt: String::new(),
+            messages: Vec::new(),
+            settings: CallSettings::default(),
+            headers: HashMap::new(),
+            tools: Vec::new(),
+            tool_choice: None,
+            provider_options: ProviderOptions::new(),
+            abort_signal: None,
+            pricing: None,
+        }
+    }
+}
+
+/// Output of [`generate_text`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GenerateTextResponse {
+    /// Generated text.
+    pub text: String,
+    /// All generated content, including reasoning and tool calls.
+    pub content: Vec<MessagePart>,
+    /// Why the model stopped generating.
+    pub finish_reason: FinishReason,
+    /// Tokens consumed by the call.
+    pub usage: Usage,
+    /// Sources the model used to ground its answer.
+    pub sources: Vec<Source>,
+    /// Warnings raised by the provider while handling the call.
+    pub warnings: Vec<CallWarning>,
+    /// Estimated cost, if the request carried a pricing table that prices
+    /// the model.
+    pub cost: Option<CostEstimate>,
+    /// Provider-specific data about the response as a whole.
+    pub provider_metadata: Option<ProviderMetadata>,
+}
+
+impl GenerateTextResponse {
+    /// Returns the tool calls requested by the model.
+    pub fn tool_calls(&self) -> Vec<&ToolCallPart> {
+        self.content
+            .iter()
+            .filter_map(|part| match part {
+                MessagePart::ToolCall(call) => Some(call),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the generated content as an assistant message, ready to be
+    /// appended to the conversation.
+    pub fn to_message(&self) -> Message {
+        Message {
+            provider_metadata: self.provider_metadata.clone(),
+            ..Message::new(MessageRole::Assistant, self.content.clone())
+        }
+    }
+}
+
+impl From<ModelResponse> for GenerateTextResponse {
+    fn from(response: ModelResponse) -> Self {
+        Self {
+            text: response.text(),
+            content: response.content,
+            finish_reason: response.finish_reason,
+            usage: response.usage,
+            sources: response.sources,
+            warnings: response.warnings,
+            cost: None,
+            provider_metadata: response.provider_metadata,
+        }
+    }
+}
+
+/// Generates text for a prompt using a language model.
+///
+/// # Errors
+///
+/// Returns [`AiError::Validation`] for malformed requests or requests that use
+/// features the model does not support, [`AiError::Cancelled`] if the abort
+/// signal fires before the model responds, and propagates any error reported
+/// by the model.
+pub async fn generate_text(request: GenerateTextRequest) -> Result<GenerateTextResponse> {
+    let options = request.call_options()?;
+    validate_call(request.model.as_ref(), &options, false)?;
+
+    let response = match options.abort_signal.clone() {
+        Some(signal) => {
+            signal
+                .run_until_aborted(request.model.do_generate(options))
+                .await??
+        }
+        None => request.model.do_generate(options).await?,
+    };
+
+    let mut response = GenerateTextResponse::from(response);
+    if let Some(pricing) = &request.pricing {
+        let (provider, model_id) = request.model.served_by(response.provider_metadata.as_ref());
+        response.cost = pricing.estimate(provider, model_id, &response.usage);
+    }
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capabilities::ModelCapabilities;
+    use crate::model::{CallOptions, ModelStream};
+    use crate::pricing::{ModelPricing, StaticPricingTable};
+    use async_trait::async_trait;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    struct EchoModel;
+
+    #[async_trait]
+    impl LanguageModel for EchoModel {
+        fn provider(&self) -> &str {
+            "test"
+        }
+
+        fn model_id(&self) -> &str {
+            "echo"
+        }
+
+        fn capabilities(&self) -> ModelCapabilities {
+            ModelCapabilities {
+                streaming: true,
+                ..Default::default()
+            }
+        }
+
+        async fn do_generate(&self, options: CallOptions) -> Result<ModelResponse> {
+            let roles: Vec<_> = options
+                .messages
+                .iter()
+                .map(|m| format!("{:?}:{}", m.role, m.text()))
+                .collect();
+            Ok(ModelResponse {
+                content: vec![
+                    MessagePart::reasoning("hmm"),
+                    MessagePart::text(roles.join("|")),
+                    MessagePart::ToolCall(ToolCallPart {
+                        tool_call_id: "call_1".into(),
+                        tool_name: "lookup".into(),
+                        input: json!({}),
+                        provider_metadata: None,
+                    }),
+                ],
+                finish_reason: FinishReason::Stop,
+                usage: Usage::new(3, 5),
+                sources: Vec::new(),
+                warnings: vec![CallWarning::other("echo")],
+                provider_metadata: None,
+            })
+        }
+
+        async fn do_stream(&self, _options: CallOptions) -> Result<ModelStream> {
+            Err(AiError::Internal("not used in this test".into()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_generate_text() {
+        let response = generate_text(GenerateTextRequest::new(EchoModel, "hi").system("sys"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.text, "System:sys|User:hi");
+        assert_eq!(response.tool_calls().len(), 1);
+        assert_eq!(response.finish_reason, FinishReason::Stop);
+        assert_eq!(response.usage.total_tokens(), 8);
+        assert_eq!(response.warnings, vec![CallWarning::other("echo")]);
+        assert_eq!(response.to_message().role, MessageRole::Assistant);
+        assert_eq!(response.cost, None);
+    }
+
+    #[tokio::test]
+    async fn test_generate_text_estimates_cost() {
+        let pricing = StaticPricingTable::new().with("test", "echo", ModelPricing::new(1.0, 2.0));
+        let response =
+            generate_text(GenerateTextRequest::new(EchoModel, "hi").pricing(Arc::new(pricing)))
+                .await
+                .unwrap();
+
+        let cost = response.cost.unwrap();
+        assert_eq!(cost.input_cost, 3.0 / 1_000_000.0);
+        assert_eq!(cost.output_cost, 10.0 / 1_000_000.0);
+    }
+
+    #[tokio::test]
+    async fn test_generate_text_from_messages() {
+        let response = generate_text(GenerateTextRequest::from_messages(
+            EchoModel,
+            vec![
+                Message::user("a"),
+                Message::assistant("b"),
+                Message::user("c"),
+            ],
+        ))
+        .await
+        .unwrap();
+
+        assert_eq!(response.text, "User:a|Assistant:b|User:c");
+    }
+
+    #[tokio::test]
+    async fn test_generate_text_validates_prompt() {
+        let error = generate_text(GenerateTextRequest::new(EchoModel, "  "))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, AiError::Validation(_)));
+
+        let mut request = GenerateTextRequest::new(EchoModel, "hi");
+        request.messages.push(Message::user("again"));
+        let error = generate_text(request).await.unwrap_err();
+        assert!(matches!(error, AiError::Validation(_)));
+    }
+
+    struct SettingsModel;
+
+    #[async_trait]
+    impl LanguageModel for SettingsModel {
+        fn provider(&self) -> &str {
+            "test"
+        }
+
+        fn model_id(&self) -> &str {
+            "settings"
+        }
+
+        fn capabilities(&self) -> ModelCapabilities {
+            ModelCapabilities {
+                streaming: true,
+                ..Default::default()
+            }
+        }
+
+        async fn do_generate(&self, options: CallOptions) -> Result<ModelResponse> {
+            #[derive(Default, serde::Deserialize)]
+            struct TestOptions {
+                mode: Option<String>,
+            }
+
+            let test_options: TestOptions = options.provider_options("test")?;
+            Ok(ModelResponse {
+                content: vec![MessagePart::text(format!(
+                    "{:?}/{}/{}",
+                    options.settings.temperature,
+                    options.headers["x-trace"],
+                    test_options.mode.unwrap_or_default()
+                ))],
+                warnings: options.settings.unsupported_warnings(&["temperature"]),
+                ..Default::default()
+            })
+        }
+
+        async fn do_stream(&self, _options: CallOptions) -> Result<ModelStream> {
+            Err(AiError::Internal("not used in this test".into()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_generate_text_forwards_settings() {
+        let response = generate_text(GenerateTextRequest {
+            model: Box::new(SettingsModel),
+            prompt: "hi".into(),
+            settings: CallSettings {
+                temperature: Some(0.5),
+                seed: Some(1),
+                ..Default::default()
+            },
+            headers: HashMap::from([("x-trace".into(), "abc".into())]),
+            provider_options: ProviderOptions::from([("test".into(), json!({ "mode": "fast" }))]),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(response.text, "Some(0.5)/abc/fast");
+        assert_eq!(
+            response.warnings,
+            vec![CallWarning::unsupported_setting("seed")]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_generate_text_rejects_invalid_settings() {
+        let error = generate_text(GenerateTextRequest::new(SettingsModel, "hi").temperature(-1.0))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, AiError::Validation(_)));
+    }
+
+    #[tokio::test]
+    async fn test_generate_text_checks_capabilities() {
+        let error = generate_text(GenerateTextRequest::from_messages(
+            EchoModel,
+            vec![Message::new(
+                MessageRole::User,
+                vec![MessagePart::image(vec![1, 2, 3], Some("image/png".into()))],
+            )],
+        ))
+        .await
+        .unwrap_err();
+
+        match error {
+            AiError::Validation(message) => assert!(message.starts_with("test:echo:")),
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    /// Never responds; records when its in-flight call is dropped.
+    struct HangingModel {
+        dropped: Arc<AtomicBool>,
+    }
+
+    struct DropFlag(Arc<AtomicBool>);
+
+    impl Drop for DropFlag {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    #[async_trait]
+    impl LanguageModel for HangingModel {
+        fn provider(&self) -> &str {
+            "test"
+        }
+
+        fn model_id(&self) -> &str {
+            "hanging"
+        }
+
+        fn capabilities(&self) -> ModelCapabilities {
+            ModelCapabilities::default()
+        }
+
+        async fn do_generate(&self, _options: CallOptions) -> Result<ModelResponse> {
+            let _flag = DropFlag(Arc::clone(&self.dropped));
+            std::future::pending().await
+        }
+
+        async fn do_stream(&self, _options: CallOptions) -> Result<ModelStream> {
+            Err(AiError::Internal("not used in this test".into()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_generate_text_cancels_in_flight_call() {
+        let dropped = Arc::new(AtomicBool::new(false));
+        let signal = AbortSignal::new();
+        let trigger = signal.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            trigger.abort();
+        });
+
+        let model = HangingModel {
+            dropped: Arc::clone(&dropped),
+        };
+        let error = generate_text(GenerateTextRequest::new(model, "hi").abort_signal(signal))
+            .await
+            .unwrap_err();
+
+        assert!(matches!(error, AiError::Cancelled));
+        assert!(dropped.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_generate_text_requires_model() {
+        let error = generate_text(GenerateTextRequest {
+            prompt: "hi".into(),
+            ..Default::default()
+        })
+        .await
+        .unwrap_err();
+        assert!(matches!(error, AiError::Config(_)));
+    }
+}
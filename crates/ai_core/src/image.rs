@@ -0,0 +1,348 @@
+//! Image generation.
+//!
+//! Provider crates implement [`ImageModel`]; applications call
+//! [`generate_image`]. Requests for more images than the model produces per
+//! call are split into several calls that run concurrently.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use futures::stream::{self, StreamExt, TryStreamExt};
+
+use crate::embedding::DEFAULT_MAX_PARALLEL_CALLS;
+use crate::settings::ProviderOptions;
+use crate::types::CallWarning;
+use ai_error::{AiError, Result};
+
+/// A model that generates images from a text prompt.
+#[async_trait]
+pub trait ImageModel: Send + Sync {
+    /// Name of the provider serving this model (e.g. `"openai"`).
+    fn provider(&self) -> &str;
+
+    /// Provider-specific model identifier (e.g. `"dall-e-3"`).
+    fn model_id(&self) -> &str;
+
+    /// Largest number of images a single [`do_generate`](Self::do_generate)
+    /// call can return, or `None` if unlimited.
+    fn max_images_per_call(&self) -> Option<u32>;
+
+    /// Generates `options.n` images.
+    async fn do_generate(&self, options: ImageOptions) -> Result<ImageModelResponse>;
+}
+
+/// Normalized input handed to [`ImageModel::do_generate`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImageOptions {
+    /// Description of the image to generate.
+    pub prompt: String,
+    /// Number of images to generate in this call.
+    pub n: u32,
+    /// Size as `"{width}x{height}"`, e.g. `"1024x1024"`.
+    pub size: Option<String>,
+    /// Aspect ratio as `"{width}:{height}"`, e.g. `"16:9"`.
+    pub aspect_ratio: Option<String>,
+    /// Seed for deterministic generation.
+    pub seed: Option<u64>,
+    /// Additional HTTP headers to send with the request.
+    pub headers: HashMap<String, String>,
+    /// Vendor-specific options keyed by provider name.
+    pub provider_options: ProviderOptions,
+}
+
+/// An image produced by an [`ImageModel`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeneratedImage {
+    /// Raw image bytes.
+    pub data: Vec<u8>,
+    /// IANA media type of `data`, e.g. `"image/png"`.
+    pub media_type: String,
+}
+
+impl GeneratedImage {
+    /// Creates an image from raw bytes.
+    pub fn new(data: Vec<u8>, media_type: impl Into<String>) -> Self {
+        Self {
+            data,
+            media_type: media_type.into(),
+        }
+    }
+
+    /// Returns the image bytes encoded as standard base64.
+    pub fn to_base64(&self) -> String {
+        STANDARD.encode(&self.data)
+    }
+}
+
+/// Result of a single [`ImageModel::do_generate`] call.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImageModelResponse {
+    /// Generated images.
+    pub images: Vec<GeneratedImage>,
+    /// Non-fatal issues reported by the provider.
+    pub warnings: Vec<CallWarning>,
+}
+
+/// Input for [`generate_image`].
+pub struct GenerateImageRequest {
+    /// Model used for the calls.
+    pub model: Box<dyn ImageModel>,
+    /// Description of the image to generate.
+    pub prompt: String,
+    /// Total number of images to generate.
+    pub n: u32,
+    /// Size as `"{width}x{height}"`, e.g. `"1024x1024"`.
+    pub size: Option<String>,
+    /// Aspect ratio as `"{width}:{height}"`, e.g. `"16:9"`.
+    pub aspect_ratio: Option<String>,
+    /// Seed for deterministic generation.
+    ///
+    /// When the request is split into several calls, the call for batch `i`
+    /// uses `seed + i`, so batches do not return the same images.
+    pub seed: Option<u64>,
+    /// Maximum number of calls in flight at once.
+    pub max_parallel_calls: usize,
+    /// Additional HTTP headers to send with every request.
+    pub headers: HashMap<String, String>,
+    /// Vendor-specific options keyed by provider name.
+    pub provider_options: ProviderOptions,
+}
+
+impl GenerateImageRequest {
+    /// Creates a request for a single image of `prompt`.
+    pub fn new(model: impl ImageModel + 'static, prompt: impl Into<String>) -> Self {
+        Self {
+            model: Box::new(model),
+            prompt: prompt.into(),
+            n: 1,
+            size: None,
+            aspect_ratio: None,
+            seed: None,
+            max_parallel_calls: DEFAULT_MAX_PARALLEL_CALLS,
+            headers: HashMap::new(),
+            provider_options: ProviderOptions::new(),
+        }
+    }
+
+    /// Sets the total number of images to generate.
+    pub fn n(mut self, n: u32) -> Self {
+        self.n = n;
+        self
+    }
+
+    /// Sets the image size, e.g. `"1024x1024"`.
+    pub fn size(mut self, size: impl Into<String>) -> Self {
+        self.size = Some(size.into());
+        self
+    }
+
+    /// Sets the aspect ratio, e.g. `"16:9"`.
+    pub fn aspect_ratio(mut self, aspect_ratio: impl Into<String>) -> Self {
+        self.aspect_ratio = Some(aspect_ratio.into());
+        self
+    }
+
+    /// Sets the seed.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Sets the options for `provider`, replacing any previous entry.
+    pub fn provider_options(
+        mut self,
+        provider: impl Into<String>,
+        options: serde_json::Value,
+    ) -> Self {
+        self.provider_options.insert(provider.into(), options);
+        self
+    }
+}
+
+/// Output of [`generate_image`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GenerateImageResponse {
+    /// Generated images, in call order.
+    pub images: Vec<GeneratedImage>,
+    /// Warnings from all calls.
+    pub warnings: Vec<CallWarning>,
+}
+
+impl GenerateImageResponse {
+    /// Returns the first generated image.
+    pub fn image(&self) -> Option<&GeneratedImage> {
+        self.images.first()
+    }
+}
+
+/// Generates images, splitting the request into several model calls when
+/// `n` exceeds [`ImageModel::max_images_per_call`].
+///
+/// # Errors
+///
+/// Returns [`AiError::Validation`] for a zero `n` or `max_parallel_calls`, or
+/// a malformed size or aspect ratio, and fails with the first error reported
+/// by any call.
+pub async fn generate_image(request: GenerateImageRequest) -> Result<GenerateImageResponse> {
+    if request.n == 0 {
+        return Err(AiError::Validation("n must be at least 1".into()));
+    }
+    if request.max_parallel_calls == 0 {
+        return Err(AiError::Validation(
+            "max_parallel_calls must be at least 1".into(),
+        ));
+    }
+    if let Some(size) = &request.size {
+        parse_dimensions(size, 'x', "size")?;
+    }
+    if let Some(aspect_ratio) = &request.aspect_ratio {
+        parse_dimensions(aspect_ratio, ':', "aspect_ratio")?;
+    }
+
+    let model = request.model.as_ref();
+    let per_call = match model.max_images_per_call() {
+        Some(0) | None => request.n,
+        Some(limit) => limit,
+    };
+    let counts = (0..request.n)
+        .step_by(per_call as usize)
+        .map(|start| per_call.min(request.n - start));
+
+    let calls = counts.enumerate().map(|(batch, n)| ImageOptions {
+        prompt: request.prompt.clone(),
+        n,
+        size: request.size.clone(),
+        aspect_ratio: request.aspect_ratio.clone(),
+        seed: request.seed.map(|seed| seed.wrapping_add(batch as u64)),
+        headers: request.headers.clone(),
+        provider_options: request.provider_options.clone(),
+    });
+
+    let responses: Vec<ImageModelResponse> = stream::iter(calls)
+        .map(|options| model.do_generate(options))
+        .buffered(request.max_parallel_calls)
+        .try_collect()
+        .await?;
+
+    let mut result = GenerateImageResponse::default();
+    for response in responses {
+        result.images.extend(response.images);
+        for warning in response.warnings {
+            if !result.warnings.contains(&warning) {
+                result.warnings.push(warning);
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Parses `"{a}{separator}{b}"` into two positive integers.
+fn parse_dimensions(value: &str, separator: char, setting: &str) -> Result<(u32, u32)> {
+    value
+        .split_once(separator)
+        .and_then(|(a, b)| Some((a.trim().parse().ok()?, b.trim().parse().ok()?)))
+        .filter(|&(a, b): &(u32, u32)| a > 0 && b > 0)
+        .ok_or_else(|| {
+            AiError::Validation(format!(
+                "{setting} must look like \"{{width}}{separator}{{height}}\", got {value:?}"
+            ))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// Returns one single-byte image per requested image and records call sizes.
+    #[derive(Default)]
+    struct CountingModel {
+        calls: Arc<Mutex<Vec<u32>>>,
+        seeds: Arc<Mutex<Vec<Option<u64>>>>,
+    }
+
+    #[async_trait]
+    impl ImageModel for CountingModel {
+        fn provider(&self) -> &str {
+            "test"
+        }
+
+        fn model_id(&self) -> &str {
+            "counting"
+        }
+
+        fn max_images_per_call(&self) -> Option<u32> {
+            Some(2)
+        }
+
+        async fn do_generate(&self, options: ImageOptions) -> Result<ImageModelResponse> {
+            let mut calls = self.calls.lock().unwrap();
+            let offset = calls.iter().sum::<u32>();
+            calls.push(options.n);
+            self.seeds.lock().unwrap().push(options.seed);
+
+            let warnings = match options.seed {
+                Some(_) => vec![CallWarning::unsupported_setting("seed")],
+                None => Vec::new(),
+            };
+            Ok(ImageModelResponse {
+                images: (0..options.n)
+                    .map(|i| GeneratedImage::new(vec![(offset + i) as u8], "image/png"))
+                    .collect(),
+                warnings,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_generate_image_batches_by_model_limit() {
+        let model = CountingModel::default();
+        let calls = Arc::clone(&model.calls);
+
+        let response = generate_image(GenerateImageRequest::new(model, "a fox").n(5).seed(1))
+            .await
+            .unwrap();
+
+        assert_eq!(*calls.lock().unwrap(), vec![2, 2, 1]);
+        assert_eq!(response.images.len(), 5);
+        assert_eq!(response.image().unwrap().to_base64(), "AA==");
+        assert_eq!(
+            response.warnings,
+            vec![CallWarning::unsupported_setting("seed")]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_generate_image_varies_seed_per_batch() {
+        let model = CountingModel::default();
+        let seeds = Arc::clone(&model.seeds);
+
+        generate_image(GenerateImageRequest::new(model, "a fox").n(5).seed(7))
+            .await
+            .unwrap();
+
+        let mut seeds = seeds.lock().unwrap().clone();
+        seeds.sort();
+        assert_eq!(seeds, vec![Some(7), Some(8), Some(9)]);
+    }
+
+    #[tokio::test]
+    async fn test_generate_image_validates_input() {
+        let bad_size = GenerateImageRequest::new(CountingModel::default(), "a fox").size("large");
+        assert!(matches!(
+            generate_image(bad_size).await,
+            Err(AiError::Validation(_))
+        ));
+
+        let bad_ratio =
+            GenerateImageRequest::new(CountingModel::default(), "a fox").aspect_ratio("16x9");
+        assert!(generate_image(bad_ratio).await.is_err());
+
+        let ok = GenerateImageRequest::new(CountingModel::default(), "a fox")
+            .size("1024x1024")
+            .aspect_ratio("16:9");
+        assert!(generate_image(ok).await.is_ok());
+    }
+}
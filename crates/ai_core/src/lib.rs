@@ -1,14 +1,82 @@
-pub fn add(left: usize, right: usize) -> usize {
-    left + right
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
-    }
-}
+//! Core traits and types for the AI SDK.
+//!
+//! This crate defines the provider-agnostic contract that every model backend
+//! implements ([`LanguageModel`], [`EmbeddingModel`]) together with the high-level entry points
+//! applications call, such as [`generate_text`], [`stream_text`] and
+//! [`embed_many`].
+//!
+//! # Features
+//!
+//! - `image` — image generation via `ImageModel` and `generate_image`.
+//! - `speech` — text-to-speech via `SpeechModel` and `generate_speech`, and
+//!   speech-to-text via `TranscriptionModel` and `transcribe`.
+//!
+//! ```no_run
+//! use ai_core::{generate_text, GenerateTextRequest, LanguageModel};
+//!
+//! # async fn run(model: Box<dyn LanguageModel>) -> ai_error::Result<()> {
+//! let response = generate_text(GenerateTextRequest {
+//!     model,
+//!     prompt: "Explain Rust's ownership system".into(),
+//!     ..Default::default()
+//! })
+//! .await?;
+//!
+//! println!("{}", response.text);
+//! # Ok(())
+//! # }
+//! ```
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs, rust_2018_idioms)]
+
+pub mod abort;
+pub mod capabilities;
+pub mod embedding;
+pub mod generate;
+#[cfg(feature = "image")]
+pub mod image;
+pub mod message;
+pub mod model;
+pub mod pricing;
+pub mod provider;
+pub mod settings;
+#[cfg(feature = "speech")]
+pub mod speech;
+pub mod stream;
+pub mod tool;
+pub mod types;
+pub mod usage;
+
+pub use abort::AbortSignal;
+pub use capabilities::ModelCapabilities;
+pub use embedding::{
+    embed, embed_many, EmbedManyRequest, EmbedManyResponse, EmbedOptions, EmbedRequest,
+    EmbedResponse, Embedding, EmbeddingModel, EmbeddingResponse,
+};
+pub use generate::{generate_text, GenerateTextRequest, GenerateTextResponse};
+#[cfg(feature = "image")]
+pub use image::{
+    generate_image, GenerateImageRequest, GenerateImageResponse, GeneratedImage, ImageModel,
+    ImageModelResponse, ImageOptions,
+};
+pub use message::{
+    CacheControl, DataContent, FilePart, ImagePart, Message, MessagePart, MessageRole,
+    ReasoningPart, TextPart, ToolCallPart, ToolResultPart,
+};
+pub use model::{CallOptions, LanguageModel, ModelResponse, ModelStream};
+pub use pricing::{CostEstimate, ModelPricing, PricingTable, StaticPricingTable};
+pub use provider::ModelProvider;
+pub use settings::{CallSettings, ProviderOptions};
+#[cfg(feature = "speech")]
+pub use speech::{
+    generate_speech, transcribe, GenerateSpeechRequest, GeneratedAudio, SpeechModel, SpeechOptions,
+    SpeechResponse, TranscribeRequest, TranscriptionModel, TranscriptionOptions,
+    TranscriptionResponse, TranscriptionSegment,
+};
+pub use stream::{stream_text, StreamPart, StreamTextHandle, StreamTextRequest, StreamTextResult};
+pub use tool::{ToolChoice, ToolDefinition, ToolExecutionContext};
+pub use types::{CallWarning, FinishReason, ProviderMetadata, Source};
+pub use usage::Usage;
+
+pub use ai_error::{AiError, Result};
@@ -0,0 +1,501 @@
+//! Multimodal conversation messages.
+//!
+//! A [`Message`] is a role plus an ordered list of [`MessagePart`]s. All types
+//! serialize losslessly to JSON so conversations can be persisted and
+//! restored; binary payloads are stored as base64 strings.
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+use crate::types::ProviderMetadata;
+
+/// Author of a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageRole {
+    /// Instructions that steer the model.
+    System,
+    /// End-user input.
+    User,
+    /// Model output.
+    Assistant,
+    /// Results of tool calls requested by the assistant.
+    Tool,
+}
+
+/// A single message in a conversation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Message {
+    /// Author of the message.
+    pub role: MessageRole,
+    /// Ordered message content.
+    pub parts: Vec<MessagePart>,
+    /// Provider-specific metadata attached to the whole message.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider_metadata: Option<ProviderMetadata>,
+}
+
+impl Message {
+    /// Creates a message from a role and parts.
+    pub fn new(role: MessageRole, parts: Vec<MessagePart>) -> Self {
+        Self {
+            role,
+            parts,
+            provider_metadata: None,
+        }
+    }
+
+    /// Creates a system message containing `text`.
+    pub fn system(text: impl Into<String>) -> Self {
+        Self::new(MessageRole::System, vec![MessagePart::text(text)])
+    }
+
+    /// Creates a user message containing `text`.
+    pub fn user(text: impl Into<String>) -> Self {
+        Self::new(MessageRole::User, vec![MessagePart::text(text)])
+    }
+
+    /// Creates an assistant message containing `text`.
+    pub fn assistant(text: impl Into<String>) -> Self {
+        Self::new(MessageRole::Assistant, vec![MessagePart::text(text)])
+    }
+
+    /// Creates a tool message carrying tool results.
+    pub fn tool(results: impl IntoIterator<Item = ToolResultPart>) -> Self {
+        Self::new(
+            MessageRole::Tool,
+            results.into_iter().map(MessagePart::ToolResult).collect(),
+        )
+    }
+
+    /// Returns the concatenation of all text parts.
+    pub fn text(&self) -> String {
+        self.parts.iter().filter_map(MessagePart::as_text).collect()
+    }
+
+    /// Marks the end of this message as a prompt-cache breakpoint by setting
+    /// `cache_control` on its last part that can carry one.
+    pub fn with_cache_control(mut self, cache_control: CacheControl) -> Self {
+        if let Some(part) = self
+            .parts
+            .iter_mut()
+            .rev()
+            .find(|part| !matches!(part, MessagePart::ToolCall(_) | MessagePart::Reasoning(_)))
+        {
+            part.set_cache_control(Some(cache_control));
+        }
+        self
+    }
+}
+
+/// A piece of message content.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum MessagePart {
+    /// Plain text.
+    Text(TextPart),
+    /// An image.
+    Image(ImagePart),
+    /// A file such as a PDF or audio clip.
+    File(FilePart),
+    /// A tool call requested by the assistant.
+    ToolCall(ToolCallPart),
+    /// The result of executing a tool call.
+    ToolResult(ToolResultPart),
+    /// Model reasoning that preceded the answer.
+    Reasoning(ReasoningPart),
+}
+
+impl MessagePart {
+    /// Creates a text part.
+    pub fn text(text: impl Into<String>) -> Self {
+        MessagePart::Text(TextPart {
+            text: text.into(),
+            cache_control: None,
+            provider_metadata: None,
+        })
+    }
+
+    /// Creates an image part from any supported data source.
+    pub fn image(image: impl Into<DataContent>, media_type: Option<String>) -> Self {
+        MessagePart::Image(ImagePart {
+            image: image.into(),
+            media_type,
+            cache_control: None,
+            provider_metadata: None,
+        })
+    }
+
+    /// Creates a file part.
+    pub fn file(data: impl Into<DataContent>, media_type: impl Into<String>) -> Self {
+        MessagePart::File(FilePart {
+            data: data.into(),
+            media_type: media_type.into(),
+            filename: None,
+            cache_control: None,
+            provider_metadata: None,
+        })
+    }
+
+    /// Creates a reasoning part.
+    pub fn reasoning(text: impl Into<String>) -> Self {
+        MessagePart::Reasoning(ReasoningPart {
+            text: text.into(),
+            provider_metadata: None,
+        })
+    }
+
+    /// Returns the text of a [`MessagePart::Text`] part.
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            MessagePart::Text(part) => Some(&part.text),
+            _ => None,
+        }
+    }
+
+    /// Returns the prompt-caching hint attached to this part.
+    pub fn cache_control(&self) -> Option<CacheControl> {
+        match self {
+            MessagePart::Text(part) => part.cache_control,
+            MessagePart::Image(part) => part.cache_control,
+            MessagePart::File(part) => part.cache_control,
+            MessagePart::ToolResult(part) => part.cache_control,
+            MessagePart::ToolCall(_) | MessagePart::Reasoning(_) => None,
+        }
+    }
+
+    /// Sets the prompt-caching hint of this part.
+    ///
+    /// Tool calls and reasoning are model output and cannot carry a hint;
+    /// they are returned unchanged.
+    pub fn with_cache_control(mut self, cache_control: CacheControl) -> Self {
+        self.set_cache_control(Some(cache_control));
+        self
+    }
+
+    fn set_cache_control(&mut self, cache_control: Option<CacheControl>) {
+        match self {
+            MessagePart::Text(part) => part.cache_control = cache_control,
+            MessagePart::Image(part) => part.cache_control = cache_control,
+            MessagePart::File(part) => part.cache_control = cache_control,
+            MessagePart::ToolResult(part) => part.cache_control = cache_control,
+            MessagePart::ToolCall(_) | MessagePart::Reasoning(_) => {}
+        }
+    }
+
+    /// Returns the provider metadata attached to this part.
+    pub fn provider_metadata(&self) -> Option<&ProviderMetadata> {
+        match self {
+            MessagePart::Text(part) => part.provider_metadata.as_ref(),
+            MessagePart::Image(part) => part.provider_metadata.as_ref(),
+            MessagePart::File(part) => part.provider_metadata.as_ref(),
+            MessagePart::ToolCall(part) => part.provider_metadata.as_ref(),
+            MessagePart::ToolResult(part) => part.provider_metadata.as_ref(),
+            MessagePart::Reasoning(part) => part.provider_metadata.as_ref(),
+        }
+    }
+}
+
+/// A prompt-caching hint.
+///
+/// Marks a cache breakpoint: providers with explicit prompt caching (such as
+/// Anthropic) cache the prompt up to and including the marked content, so
+/// later calls sharing that prefix are cheaper. Providers without explicit
+/// caching ignore the hint and return a warning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CacheControl {
+    /// Cache for the provider's default lifetime (five minutes on Anthropic).
+    #[default]
+    Ephemeral,
+    /// Cache for one hour, where supported.
+    OneHour,
+}
+
+/// Plain text content.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextPart {
+    /// The text.
+    pub text: String,
+    /// Prompt-caching hint; see [`CacheControl`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_control: Option<CacheControl>,
+    /// Provider-specific metadata.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider_metadata: Option<ProviderMetadata>,
+}
+
+/// Image content.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImagePart {
+    /// Image data or location.
+    pub image: DataContent,
+    /// IANA media type (e.g. `"image/png"`), if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub media_type: Option<String>,
+    /// Prompt-caching hint; see [`CacheControl`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_control: Option<CacheControl>,
+    /// Provider-specific metadata.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider_metadata: Option<ProviderMetadata>,
+}
+
+/// File content.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilePart {
+    /// File data or location.
+    pub data: DataContent,
+    /// IANA media type (e.g. `"application/pdf"`).
+    pub media_type: String,
+    /// Optional original file name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filename: Option<String>,
+    /// Prompt-caching hint; see [`CacheControl`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_control: Option<CacheControl>,
+    /// Provider-specific metadata.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider_metadata: Option<ProviderMetadata>,
+}
+
+/// A tool call requested by the assistant.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolCallPart {
+    /// Identifier used to match the call with its result.
+    pub tool_call_id: String,
+    /// Name of the tool to call.
+    pub tool_name: String,
+    /// JSON input for the tool.
+    pub input: Value,
+    /// Provider-specific metadata.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider_metadata: Option<ProviderMetadata>,
+}
+
+/// The result of a tool call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolResultPart {
+    /// Identifier of the call this result answers.
+    pub tool_call_id: String,
+    /// Name of the tool that was called.
+    pub tool_name: String,
+    /// JSON output of the tool.
+    pub output: Value,
+    /// Whether `output` describes a failure.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_error: bool,
+    /// Prompt-caching hint; see [`CacheControl`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_control: Option<CacheControl>,
+    /// Provider-specific metadata.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider_metadata: Option<ProviderMetadata>,
+}
+
+/// Model reasoning content.
+///
+/// Providers that sign or encrypt reasoning (e.g. Anthropic signatures) keep
+/// the opaque payload in `provider_metadata` so it can be sent back verbatim.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReasoningPart {
+    /// Reasoning text, possibly a summary.
+    pub text: String,
+    /// Provider-specific metadata.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider_metadata: Option<ProviderMetadata>,
+}
+
+/// Binary content or a reference to it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DataContent {
+    /// A URL (`https:` or `data:`) pointing at the content.
+    Url(String),
+    /// Base64-encoded content.
+    Base64(String),
+    /// Raw bytes, serialized as a base64 string.
+    Bytes(
+        #[serde(
+            serialize_with = "serialize_bytes",
+            deserialize_with = "deserialize_bytes"
+        )]
+        Vec<u8>,
+    ),
+}
+
+impl DataContent {
+    /// Returns the content as base64, or `None` for URLs.
+    pub fn to_base64(&self) -> Option<String> {
+        match self {
+            DataContent::Url(_) => None,
+            DataContent::Base64(data) => Some(data.clone()),
+            DataContent::Bytes(bytes) => Some(STANDARD.encode(bytes)),
+        }
+    }
+
+    /// Returns the content as a URL, encoding inline data as a `data:` URL.
+    pub fn to_url(&self, media_type: &str) -> String {
+        match self {
+            DataContent::Url(url) => url.clone(),
+            _ => format!(
+                "data:{};base64,{}",
+                media_type,
+                self.to_base64().unwrap_or_default()
+            ),
+        }
+    }
+}
+
+impl From<Vec<u8>> for DataContent {
+    fn from(bytes: Vec<u8>) -> Self {
+        DataContent::Bytes(bytes)
+    }
+}
+
+fn serialize_bytes<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&STANDARD.encode(bytes))
+}
+
+fn deserialize_bytes<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    let encoded = String::deserialize(deserializer)?;
+    STANDARD.decode(encoded).map_err(serde::de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_message_json_roundtrip() {
+        let mut metadata = ProviderMetadata::new();
+        metadata.insert("anthropic".into(), json!({ "signature": "sig" }));
+
+        let messages = vec![
+            Message::system("Be brief."),
+            Message::new(
+                MessageRole::User,
+                vec![
+                    MessagePart::text("What is in these?"),
+                    MessagePart::image(
+                        DataContent::Url("https://example.com/cat.png".into()),
+                        None,
+                    ),
+                    MessagePart::image(vec![0u8, 159, 146, 150], Some("image/png".into())),
+                    MessagePart::file(DataContent::Base64("JVBERi0=".into()), "application/pdf"),
+                ],
+            ),
+            Message::new(
+                MessageRole::Assistant,
+                vec![
+                    MessagePart::Reasoning(ReasoningPart {
+                        text: "Look closely".into(),
+                        provider_metadata: Some(metadata),
+                    }),
+                    MessagePart::ToolCall(ToolCallPart {
+                        tool_call_id: "call_1".into(),
+                        tool_name: "describe".into(),
+                        input: json!({ "detail": "high" }),
+                        provider_metadata: None,
+                    }),
+                ],
+            ),
+            Message::tool([ToolResultPart {
+                tool_call_id: "call_1".into(),
+                tool_name: "describe".into(),
+                output: json!("a cat"),
+                is_error: false,
+                cache_control: Some(CacheControl::OneHour),
+                provider_metadata: None,
+            }]),
+        ];
+
+        let encoded = serde_json::to_string(&messages).unwrap();
+        let decoded: Vec<Message> = serde_json::from_str(&encoded).unwrap();
+        assert_eq!(decoded, messages);
+    }
+
+    #[test]
+    fn test_part_wire_shape() {
+        let part = MessagePart::ToolResult(ToolResultPart {
+            tool_call_id: "call_1".into(),
+            tool_name: "weather".into(),
+            output: json!({ "temp": 21 }),
+            is_error: true,
+            cache_control: None,
+            provider_metadata: None,
+        });
+
+        assert_eq!(
+            serde_json::to_value(&part).unwrap(),
+            json!({
+                "type": "tool-result",
+                "toolCallId": "call_1",
+                "toolName": "weather",
+                "output": { "temp": 21 },
+                "isError": true
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(DataContent::Bytes(b"hi".to_vec())).unwrap(),
+            json!({ "bytes": "aGk=" })
+        );
+    }
+
+    #[test]
+    fn test_data_content_to_url() {
+        assert_eq!(
+            DataContent::Bytes(b"hi".to_vec()).to_url("text/plain"),
+            "data:text/plain;base64,aGk="
+        );
+        assert_eq!(
+            DataContent::Url("https://x".into()).to_url("image/png"),
+            "https://x"
+        );
+    }
+
+    #[test]
+    fn test_message_text() {
+        let message = Message::new(
+            MessageRole::Assistant,
+            vec![
+                MessagePart::text("a"),
+                MessagePart::reasoning("skip"),
+                MessagePart::text("b"),
+            ],
+        );
+        assert_eq!(message.text(), "ab");
+    }
+
+    #[test]
+    fn test_message_cache_control_marks_last_part() {
+        let message = Message::new(
+            MessageRole::User,
+            vec![
+                MessagePart::text("long context"),
+                MessagePart::text("question"),
+            ],
+        )
+        .with_cache_control(CacheControl::Ephemeral);
+
+        assert_eq!(message.parts[0].cache_control(), None);
+        assert_eq!(
+            message.parts[1].cache_control(),
+            Some(CacheControl::Ephemeral)
+        );
+        assert_eq!(
+            serde_json::to_value(&message.parts[1]).unwrap(),
+            json!({ "type": "text", "text": "question", "cacheControl": "ephemeral" })
+        );
+    }
+}
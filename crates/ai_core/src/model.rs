@@ -0,0 +1,210 @@
+//! The provider-facing language model contract.
+//!
+//! Provider crates implement [`LanguageModel`]; applications normally call the
+//! higher level functions in [`crate::generate`] instead of invoking the trait
+//! methods directly.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+
+use crate::abort::AbortSignal;
+use crate::capabilities::ModelCapabilities;
+use crate::message::{Message, MessagePart, ReasoningPart, ToolCallPart};
+use crate::settings::{parse_provider_options, CallSettings, ProviderOptions};
+use crate::stream::StreamPart;
+use crate::tool::{ToolChoice, ToolDefinition};
+use crate::types::{CallWarning, FinishReason, ProviderMetadata, Source};
+use crate::usage::Usage;
+use ai_error::{AiError, Result};
+
+/// A text generation model exposed by a provider.
+///
+/// The trait is object safe so models can be stored as
+/// `Box<dyn LanguageModel>` and selected at runtime.
+#[async_trait]
+pub trait LanguageModel: Send + Sync {
+    /// Name of the provider serving this model (e.g. `"openai"`).
+    fn provider(&self) -> &str;
+
+    /// Provider-specific model identifier (e.g. `"gpt-4.1-mini"`).
+    fn model_id(&self) -> &str;
+
+    /// Features supported by this model.
+    fn capabilities(&self) -> ModelCapabilities;
+
+    /// Provider and model id of the model that served a call, given the
+    /// provider metadata of its response.
+    ///
+    /// Models that route calls to other models override this so usage is
+    /// priced against the model that actually answered.
+    fn served_by<'a>(
+        &'a self,
+        provider_metadata: Option<&'a ProviderMetadata>,
+    ) -> (&'a str, &'a str) {
+        let _ = provider_metadata;
+        (self.provider(), self.model_id())
+    }
+
+    /// Performs a single, non-streaming generation call.
+    async fn do_generate(&self, options: CallOptions) -> Result<ModelResponse>;
+
+    /// Starts a streaming generation call.
+    ///
+    /// The returned future resolves once the provider has accepted the
+    /// request; generated content is then delivered through
+    /// [`ModelStream::stream`].
+    async fn do_stream(&self, options: CallOptions) -> Result<ModelStream>;
+}
+
+/// Normalized input handed to [`LanguageModel::do_generate`].
+#[derive(Debug, Clone, Default)]
+pub struct CallOptions {
+    /// Conversation to continue, with any system instruction first.
+    pub messages: Vec<Message>,
+    /// Sampling and length settings.
+    pub settings: CallSettings,
+    /// Tools the model may call.
+    pub tools: Vec<ToolDefinition>,
+    /// How the model should choose among `tools`.
+    pub tool_choice: Option<ToolChoice>,
+    /// Additional HTTP headers to send with the request.
+    pub headers: HashMap<String, String>,
+    /// Vendor-specific options keyed by provider name.
+    pub provider_options: ProviderOptions,
+    /// Signal that cancels the call when triggered.
+    pub abort_signal: Option<AbortSignal>,
+}
+
+impl CallOptions {
+    /// Deserializes this call's options for `provider` into a typed struct.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AiError::Validation`] if the options do not match `T`.
+    pub fn provider_options<T>(&self, provider: &str) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned + Default,
+    {
+        parse_provider_options(&self.provider_options, provider)
+    }
+
+    /// Returns a warning if any message part or tool carries a
+    /// [`CacheControl`](crate::CacheControl) hint.
+    ///
+    /// Providers without explicit prompt caching add this to their warnings
+    /// so ignored hints are reported instead of dropped silently.
+    pub fn cache_control_warning(&self) -> Option<CallWarning> {
+        let has_hint = self
+            .messages
+            .iter()
+            .flat_map(|message| &message.parts)
+            .any(|part| part.cache_control().is_some())
+            || self.tools.iter().any(|tool| tool.cache_control.is_some());
+
+        has_hint.then(|| CallWarning::UnsupportedSetting {
+            setting: "cache_control".into(),
+            details: Some("prompt caching hints are not supported by this provider".into()),
+        })
+    }
+}
+
+/// Result of a single [`LanguageModel::do_generate`] call.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModelResponse {
+    /// Generated content in output order.
+    pub content: Vec<MessagePart>,
+    /// Why the model stopped generating.
+    pub finish_reason: FinishReason,
+    /// Tokens consumed by the call.
+    pub usage: Usage,
+    /// Sources the model used to ground its answer.
+    pub sources: Vec<Source>,
+    /// Warnings raised by the provider while handling the call.
+    pub warnings: Vec<CallWarning>,
+    /// Provider-specific data about the response as a whole, such as a
+    /// response id that a later call can refer to.
+    pub provider_metadata: Option<ProviderMetadata>,
+}
+
+impl ModelResponse {
+    /// Returns the concatenation of all generated text parts.
+    pub fn text(&self) -> String {
+        self.content
+            .iter()
+            .filter_map(MessagePart::as_text)
+            .collect()
+    }
+
+    /// Returns the concatenation of all reasoning parts.
+    pub fn reasoning_text(&self) -> String {
+        self.content
+            .iter()
+            .filter_map(|part| match part {
+                MessagePart::Reasoning(ReasoningPart { text, .. }) => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the tool calls requested by the model.
+    pub fn tool_calls(&self) -> Vec<&ToolCallPart> {
+        self.content
+            .iter()
+            .filter_map(|part| match part {
+                MessagePart::ToolCall(call) => Some(call),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Result of a [`LanguageModel::do_stream`] call.
+pub struct ModelStream {
+    /// Stream of generated parts. An `Err` item ends the stream.
+    pub stream: BoxStream<'static, Result<StreamPart>>,
+    /// Warnings raised by the provider while preparing the call.
+    pub warnings: Vec<CallWarning>,
+}
+
+/// Placeholder model used by request `Default` implementations.
+///
+/// It reports every capability so requests get past validation and fail with
+/// a configuration error naming the actual problem.
+pub(crate) struct UnsetModel;
+
+#[async_trait]
+impl LanguageModel for UnsetModel {
+    fn provider(&self) -> &str {
+        "unset"
+    }
+
+    fn model_id(&self) -> &str {
+        "unset"
+    }
+
+    fn capabilities(&self) -> ModelCapabilities {
+        ModelCapabilities {
+            tool_calling: true,
+            structured_outputs: true,
+            image_input: true,
+            file_input: true,
+            reasoning: true,
+            streaming: true,
+            max_context_tokens: None,
+        }
+    }
+
+    async fn do_generate(&self, _options: CallOptions) -> Result<ModelResponse> {
+        Err(unset_model_error())
+    }
+
+    async fn do_stream(&self, _options: CallOptions) -> Result<ModelStream> {
+        Err(unset_model_error())
+    }
+}
+
+fn unset_model_error() -> AiError {
+    AiError::Config("no model set on request".into())
+}
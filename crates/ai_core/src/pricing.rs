@@ -0,0 +1,257 @@
+//! Cost estimation from token usage.
+//!
+//! Prices change often and differ per contract, so the SDK ships no built-in
+//! rates. Implement [`PricingTable`] over your own price source, or fill a
+//! [`StaticPricingTable`], and attach it to a request to get a
+//! [`CostEstimate`] on the response.
+
+use std::collections::HashMap;
+use std::ops::{Add, AddAssign};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::LanguageModel;
+use crate::types::ProviderMetadata;
+use crate::usage::Usage;
+
+/// Source of per-model prices.
+pub trait PricingTable: Send + Sync {
+    /// Returns the prices for a model, or `None` if it is not priced.
+    fn pricing(&self, provider: &str, model_id: &str) -> Option<ModelPricing>;
+
+    /// Estimates the cost of `usage` on a model.
+    fn estimate(&self, provider: &str, model_id: &str, usage: &Usage) -> Option<CostEstimate> {
+        self.pricing(provider, model_id)
+            .map(|pricing| pricing.estimate(usage))
+    }
+}
+
+/// Prices for one model in US dollars per million tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelPricing {
+    /// Price of uncached input tokens.
+    pub input_per_million: f64,
+    /// Price of output tokens, including reasoning tokens.
+    pub output_per_million: f64,
+    /// Price of input tokens served from cache; defaults to the input price.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cached_input_per_million: Option<f64>,
+    /// Price of input tokens written to cache; defaults to the input price.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_creation_input_per_million: Option<f64>,
+}
+
+impl ModelPricing {
+    /// Creates pricing from input and output prices per million tokens.
+    pub fn new(input_per_million: f64, output_per_million: f64) -> Self {
+        Self {
+            input_per_million,
+            output_per_million,
+            cached_input_per_million: None,
+            cache_creation_input_per_million: None,
+        }
+    }
+
+    /// Sets the price of cached input tokens per million.
+    pub fn with_cached_input(mut self, cached_input_per_million: f64) -> Self {
+        self.cached_input_per_million = Some(cached_input_per_million);
+        self
+    }
+
+    /// Sets the price of cache creation (cache write) input tokens per
+    /// million.
+    pub fn with_cache_creation_input(mut self, cache_creation_input_per_million: f64) -> Self {
+        self.cache_creation_input_per_million = Some(cache_creation_input_per_million);
+        self
+    }
+
+    /// Computes the cost of `usage` at these prices.
+    pub fn estimate(&self, usage: &Usage) -> CostEstimate {
+        let cached = usage.cached_input_tokens.min(usage.input_tokens);
+        let cache_creation = usage
+            .cache_creation_input_tokens
+            .min(usage.input_tokens - cached);
+        let uncached = usage.input_tokens - cached - cache_creation;
+        let cached_rate = self
+            .cached_input_per_million
+            .unwrap_or(self.input_per_million);
+        let cache_creation_rate = self
+            .cache_creation_input_per_million
+            .unwrap_or(self.input_per_million);
+
+        CostEstimate {
+            input_cost: per_million(uncached, self.input_per_million),
+            cached_input_cost: per_million(cached, cached_rate),
+            cache_creation_input_cost: per_million(cache_creation, cache_creation_rate),
+            output_cost: per_million(usage.output_tokens, self.output_per_million),
+        }
+    }
+}
+
+fn per_million(tokens: u64, price: f64) -> f64 {
+    tokens as f64 * price / 1_000_000.0
+}
+
+/// Estimated cost of a call in US dollars.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CostEstimate {
+    /// Cost of uncached input tokens.
+    pub input_cost: f64,
+    /// Cost of input tokens served from cache.
+    pub cached_input_cost: f64,
+    /// Cost of input tokens written to cache.
+    #[serde(default)]
+    pub cache_creation_input_cost: f64,
+    /// Cost of output tokens.
+    pub output_cost: f64,
+}
+
+impl CostEstimate {
+    /// Returns the total cost.
+    pub fn total_cost(&self) -> f64 {
+        self.input_cost + self.cached_input_cost + self.cache_creation_input_cost + self.output_cost
+    }
+}
+
+impl Add for CostEstimate {
+    type Output = CostEstimate;
+
+    fn add(mut self, other: CostEstimate) -> CostEstimate {
+        self += other;
+        self
+    }
+}
+
+impl AddAssign for CostEstimate {
+    fn add_assign(&mut self, other: CostEstimate) {
+        self.input_cost += other.input_cost;
+        self.cached_input_cost += other.cached_input_cost;
+        self.cache_creation_input_cost += other.cache_creation_input_cost;
+        self.output_cost += other.output_cost;
+    }
+}
+
+/// A [`PricingTable`] backed by an in-memory map.
+///
+/// Entries are looked up by provider and model id first, then by model id
+/// alone, so one entry can price a model served by several providers.
+#[derive(Debug, Clone, Default)]
+pub struct StaticPricingTable {
+    entries: HashMap<String, ModelPricing>,
+}
+
+impl StaticPricingTable {
+    /// Creates an empty table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builder form of [`insert`](Self::insert).
+    pub fn with(mut self, provider: &str, model_id: &str, pricing: ModelPricing) -> Self {
+        self.insert(provider, model_id, pricing);
+        self
+    }
+
+    /// Adds prices for `model_id` served by `provider`.
+    pub fn insert(&mut self, provider: &str, model_id: &str, pricing: ModelPricing) {
+        self.entries
+            .insert(format!("{provider}:{model_id}"), pricing);
+    }
+
+    /// Adds prices for `model_id` regardless of provider.
+    pub fn insert_model(&mut self, model_id: &str, pricing: ModelPricing) {
+        self.entries.insert(model_id.to_string(), pricing);
+    }
+}
+
+impl PricingTable for StaticPricingTable {
+    fn pricing(&self, provider: &str, model_id: &str) -> Option<ModelPricing> {
+        self.entries
+            .get(&format!("{provider}:{model_id}"))
+            .or_else(|| self.entries.get(model_id))
+            .copied()
+    }
+}
+
+/// A pricing table bound to the model a request is sent to.
+#[derive(Clone)]
+pub(crate) struct PricedModel {
+    table: Arc<dyn PricingTable>,
+    model: Arc<dyn LanguageModel>,
+}
+
+impl PricedModel {
+    pub(crate) fn new(table: Arc<dyn PricingTable>, model: Arc<dyn LanguageModel>) -> Self {
+        Self { table, model }
+    }
+
+    /// Prices `usage` on the model that served the call, as reported by
+    /// [`LanguageModel::served_by`].
+    pub(crate) fn estimate(
+        &self,
+        usage: &Usage,
+        provider_metadata: Option<&ProviderMetadata>,
+    ) -> Option<CostEstimate> {
+        let (provider, model_id) = self.model.served_by(provider_metadata);
+        self.table.estimate(provider, model_id, usage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_prices_cached_input_separately() {
+        let pricing = ModelPricing::new(2.5, 10.0).with_cached_input(1.25);
+        let usage = Usage {
+            cached_input_tokens: 200_000,
+            ..Usage::new(1_000_000, 100_000)
+        };
+
+        let cost = pricing.estimate(&usage);
+        assert_eq!(cost.input_cost, 2.0);
+        assert_eq!(cost.cached_input_cost, 0.25);
+        assert_eq!(cost.output_cost, 1.0);
+        assert_eq!(cost.total_cost(), 3.25);
+    }
+
+    #[test]
+    fn test_estimate_prices_cache_creation_separately() {
+        let pricing = ModelPricing::new(3.0, 15.0)
+            .with_cached_input(0.3)
+            .with_cache_creation_input(3.75);
+        let usage = Usage {
+            cached_input_tokens: 400_000,
+            cache_creation_input_tokens: 200_000,
+            ..Usage::new(1_000_000, 0)
+        };
+
+        let cost = pricing.estimate(&usage);
+        assert_eq!(cost.input_cost, 1.2);
+        assert_eq!(cost.cached_input_cost, 0.12);
+        assert_eq!(cost.cache_creation_input_cost, 0.75);
+
+        let unpriced = ModelPricing::new(3.0, 15.0).estimate(&usage);
+        assert_eq!(unpriced.cache_creation_input_cost, 0.6);
+    }
+
+    #[test]
+    fn test_static_table_lookup() {
+        let mut table =
+            StaticPricingTable::new().with("openai", "gpt-4o", ModelPricing::new(2.5, 10.0));
+        table.insert_model("llama3", ModelPricing::new(0.1, 0.1));
+
+        assert!(table.pricing("openai", "gpt-4o").is_some());
+        assert!(table.pricing("azure", "gpt-4o").is_none());
+        assert!(table.pricing("ollama", "llama3").is_some());
+
+        let cost = table
+            .estimate("openai", "gpt-4o", &Usage::new(1_000_000, 0))
+            .unwrap();
+        assert_eq!(cost.total_cost(), 2.5);
+    }
+}
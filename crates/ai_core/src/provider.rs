@@ -0,0 +1,40 @@
+//! Providers as factories of models.
+
+use crate::embedding::EmbeddingModel;
+use crate::model::LanguageModel;
+use ai_error::{AiError, Result};
+
+/// A model vendor that creates models by identifier.
+///
+/// Provider crates implement this so models can be resolved from
+/// configuration strings such as `"openai:gpt-4.1-mini"` (see
+/// `ai_providers::ProviderRegistry`). Model kinds a provider does not offer
+/// keep the default implementation, which returns [`AiError::Config`].
+pub trait ModelProvider: Send + Sync {
+    /// Name used as the prefix in `"provider:model"` strings.
+    fn name(&self) -> &str;
+
+    /// Creates a language model.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AiError::Config`] if the provider has no such model.
+    fn language_model(&self, model_id: &str) -> Result<Box<dyn LanguageModel>> {
+        Err(unsupported_kind(self.name(), "language", model_id))
+    }
+
+    /// Creates an embedding model.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AiError::Config`] if the provider has no such model.
+    fn embedding_model(&self, model_id: &str) -> Result<Box<dyn EmbeddingModel>> {
+        Err(unsupported_kind(self.name(), "embedding", model_id))
+    }
+}
+
+fn unsupported_kind(provider: &str, kind: &str, model_id: &str) -> AiError {
+    AiError::Config(format!(
+        "provider '{provider}' does not offer {kind} models (requested '{model_id}')"
+    ))
+}
@@ -0,0 +1,188 @@
+//! Per-call generation settings and provider option passthrough.
+
+use std::collections::HashMap;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::types::CallWarning;
+use ai_error::{AiError, Result};
+
+/// Vendor-specific options keyed by provider name.
+///
+/// Each provider reads only its own entry, e.g. `{"openai": {"reasoningEffort": "low"}}`
+/// or `{"anthropic": {"thinking": {"type": "enabled", "budgetTokens": 1024}}}`.
+pub type ProviderOptions = HashMap<String, serde_json::Value>;
+
+/// Sampling and length settings common to all language models.
+///
+/// Unset fields fall back to the provider's defaults. Providers that cannot
+/// honour a setting ignore it and report a [`CallWarning`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallSettings {
+    /// Sampling temperature.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    /// Nucleus sampling probability mass.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    /// Only sample from the top K options for each token.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_k: Option<u32>,
+    /// Maximum number of tokens to generate.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<u32>,
+    /// Sequences that stop generation when produced.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stop_sequences: Vec<String>,
+    /// Seed for deterministic sampling.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// Penalty for tokens that already appeared in the text.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub presence_penalty: Option<f32>,
+    /// Penalty proportional to how often a token already appeared.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frequency_penalty: Option<f32>,
+}
+
+impl CallSettings {
+    /// Checks that all set values are within their valid ranges.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AiError::Validation`] naming the first invalid setting.
+    pub fn validate(&self) -> Result<()> {
+        if let Some(temperature) = self.temperature {
+            if temperature.is_nan() || temperature < 0.0 {
+                return Err(invalid("temperature", "must be >= 0"));
+            }
+        }
+        if let Some(top_p) = self.top_p {
+            if !(0.0..=1.0).contains(&top_p) {
+                return Err(invalid("top_p", "must be between 0 and 1"));
+            }
+        }
+        if self.top_k == Some(0) {
+            return Err(invalid("top_k", "must be >= 1"));
+        }
+        if self.max_output_tokens == Some(0) {
+            return Err(invalid("max_output_tokens", "must be >= 1"));
+        }
+        Ok(())
+    }
+
+    /// Names of the settings that have a value.
+    pub fn set_fields(&self) -> Vec<&'static str> {
+        [
+            ("temperature", self.temperature.is_some()),
+            ("top_p", self.top_p.is_some()),
+            ("top_k", self.top_k.is_some()),
+            ("max_output_tokens", self.max_output_tokens.is_some()),
+            ("stop_sequences", !self.stop_sequences.is_empty()),
+            ("seed", self.seed.is_some()),
+            ("presence_penalty", self.presence_penalty.is_some()),
+            ("frequency_penalty", self.frequency_penalty.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(name, set)| set.then_some(name))
+        .collect()
+    }
+
+    /// Returns an [`CallWarning::UnsupportedSetting`] for every set field
+    /// that is not listed in `supported`.
+    ///
+    /// Providers call this with the settings they forward so ignored values
+    /// are reported instead of dropped silently.
+    pub fn unsupported_warnings(&self, supported: &[&str]) -> Vec<CallWarning> {
+        self.set_fields()
+            .into_iter()
+            .filter(|name| !supported.contains(name))
+            .map(CallWarning::unsupported_setting)
+            .collect()
+    }
+}
+
+/// Deserializes the entry for `provider` from a [`ProviderOptions`] map.
+///
+/// Returns `T::default()` when no entry exists.
+///
+/// # Errors
+///
+/// Returns [`AiError::Validation`] if the entry does not match `T`.
+pub fn parse_provider_options<T>(options: &ProviderOptions, provider: &str) -> Result<T>
+where
+    T: DeserializeOwned + Default,
+{
+    match options.get(provider) {
+        Some(value) => serde_json::from_value(value.clone()).map_err(|error| {
+            AiError::Validation(format!("invalid {provider} provider options: {error}"))
+        }),
+        None => Ok(T::default()),
+    }
+}
+
+fn invalid(setting: &str, reason: &str) -> AiError {
+    AiError::Validation(format!("{setting} {reason}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate_ranges() {
+        assert!(CallSettings::default().validate().is_ok());
+
+        let settings = CallSettings {
+            top_p: Some(1.5),
+            ..Default::default()
+        };
+        assert!(matches!(settings.validate(), Err(AiError::Validation(_))));
+
+        let settings = CallSettings {
+            temperature: Some(f32::NAN),
+            ..Default::default()
+        };
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn test_unsupported_warnings() {
+        let settings = CallSettings {
+            temperature: Some(0.2),
+            top_k: Some(40),
+            seed: Some(7),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            settings.unsupported_warnings(&["temperature", "seed"]),
+            vec![CallWarning::unsupported_setting("top_k")]
+        );
+    }
+
+    #[test]
+    fn test_parse_provider_options() {
+        #[derive(Debug, Default, PartialEq, Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct OpenAiOptions {
+            reasoning_effort: Option<String>,
+        }
+
+        let mut options = ProviderOptions::new();
+        options.insert("openai".into(), json!({ "reasoningEffort": "low" }));
+
+        let parsed: OpenAiOptions = parse_provider_options(&options, "openai").unwrap();
+        assert_eq!(parsed.reasoning_effort.as_deref(), Some("low"));
+
+        let missing: OpenAiOptions = parse_provider_options(&options, "anthropic").unwrap();
+        assert_eq!(missing, OpenAiOptions::default());
+
+        options.insert("openai".into(), json!({ "reasoningEffort": 3 }));
+        let invalid = parse_provider_options::<OpenAiOptions>(&options, "openai");
+        assert!(matches!(invalid, Err(AiError::Validation(_))));
+    }
+}
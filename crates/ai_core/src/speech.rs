@@ -0,0 +1,384 @@
+//! Speech synthesis and transcription.
+//!
+//! Provider crates implement [`SpeechModel`] (text to audio) and
+//! [`TranscriptionModel`] (audio to text); applications call
+//! [`generate_speech`] and [`transcribe`].
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::message::DataContent;
+use crate::settings::ProviderOptions;
+use crate::types::CallWarning;
+use ai_error::{AiError, Result};
+
+/// A model that turns text into spoken audio.
+#[async_trait]
+pub trait SpeechModel: Send + Sync {
+    /// Name of the provider serving this model (e.g. `"openai"`).
+    fn provider(&self) -> &str;
+
+    /// Provider-specific model identifier (e.g. `"gpt-4o-mini-tts"`).
+    fn model_id(&self) -> &str;
+
+    /// Synthesizes `options.text`.
+    async fn do_generate(&self, options: SpeechOptions) -> Result<SpeechResponse>;
+}
+
+/// A model that turns audio into text.
+#[async_trait]
+pub trait TranscriptionModel: Send + Sync {
+    /// Name of the provider serving this model (e.g. `"openai"`).
+    fn provider(&self) -> &str;
+
+    /// Provider-specific model identifier (e.g. `"whisper-1"`).
+    fn model_id(&self) -> &str;
+
+    /// Transcribes `options.audio`.
+    async fn do_transcribe(&self, options: TranscriptionOptions) -> Result<TranscriptionResponse>;
+}
+
+/// Normalized input handed to [`SpeechModel::do_generate`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SpeechOptions {
+    /// Text to speak.
+    pub text: String,
+    /// Provider-specific voice name, e.g. `"alloy"`.
+    pub voice: Option<String>,
+    /// Requested audio format, e.g. `"mp3"` or `"wav"`.
+    pub output_format: Option<String>,
+    /// Style instructions for models that support them.
+    pub instructions: Option<String>,
+    /// Playback speed multiplier, where `1.0` is normal speed.
+    pub speed: Option<f32>,
+    /// Language of `text` as an ISO 639-1 code.
+    pub language: Option<String>,
+    /// Additional HTTP headers to send with the request.
+    pub headers: HashMap<String, String>,
+    /// Vendor-specific options keyed by provider name.
+    pub provider_options: ProviderOptions,
+}
+
+/// Audio produced by a [`SpeechModel`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeneratedAudio {
+    /// Raw audio bytes.
+    pub data: Vec<u8>,
+    /// Container format of `data`, e.g. `"mp3"`.
+    pub format: String,
+    /// IANA media type of `data`, e.g. `"audio/mpeg"`.
+    pub media_type: String,
+}
+
+impl GeneratedAudio {
+    /// Creates audio in `format`, deriving the media type from it.
+    pub fn new(data: Vec<u8>, format: impl Into<String>) -> Self {
+        let format = format.into();
+        let media_type = match format.as_str() {
+            "mp3" => "audio/mpeg",
+            "wav" => "audio/wav",
+            "flac" => "audio/flac",
+            "aac" => "audio/aac",
+            "opus" => "audio/opus",
+            "pcm" => "audio/pcm",
+            _ => "application/octet-stream",
+        };
+        Self {
+            data,
+            format,
+            media_type: media_type.to_string(),
+        }
+    }
+}
+
+/// Result of speech synthesis.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpeechResponse {
+    /// Synthesized audio.
+    pub audio: GeneratedAudio,
+    /// Non-fatal issues reported by the provider.
+    pub warnings: Vec<CallWarning>,
+}
+
+/// Normalized input handed to [`TranscriptionModel::do_transcribe`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranscriptionOptions {
+    /// Audio to transcribe.
+    pub audio: DataContent,
+    /// IANA media type of `audio`, e.g. `"audio/wav"`.
+    pub media_type: String,
+    /// Expected language as an ISO 639-1 code.
+    pub language: Option<String>,
+    /// Text that guides the model's style or vocabulary.
+    pub prompt: Option<String>,
+    /// Additional HTTP headers to send with the request.
+    pub headers: HashMap<String, String>,
+    /// Vendor-specific options keyed by provider name.
+    pub provider_options: ProviderOptions,
+}
+
+/// A timestamped span of a transcript.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptionSegment {
+    /// Text spoken in this span.
+    pub text: String,
+    /// Start of the span in seconds from the beginning of the audio.
+    pub start_second: f64,
+    /// End of the span in seconds from the beginning of the audio.
+    pub end_second: f64,
+}
+
+/// Result of a transcription.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TranscriptionResponse {
+    /// Full transcript.
+    pub text: String,
+    /// Timestamped segments, if the provider returns them.
+    pub segments: Vec<TranscriptionSegment>,
+    /// Detected language as an ISO 639-1 code.
+    pub language: Option<String>,
+    /// Length of the audio in seconds.
+    pub duration_seconds: Option<f64>,
+    /// Non-fatal issues reported by the provider.
+    pub warnings: Vec<CallWarning>,
+}
+
+/// Input for [`generate_speech`].
+pub struct GenerateSpeechRequest {
+    /// Model used for the call.
+    pub model: Box<dyn SpeechModel>,
+    /// Call parameters.
+    pub options: SpeechOptions,
+}
+
+impl GenerateSpeechRequest {
+    /// Creates a request speaking `text` with `model`.
+    pub fn new(model: impl SpeechModel + 'static, text: impl Into<String>) -> Self {
+        Self {
+            model: Box::new(model),
+            options: SpeechOptions {
+                text: text.into(),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Sets the voice.
+    pub fn voice(mut self, voice: impl Into<String>) -> Self {
+        self.options.voice = Some(voice.into());
+        self
+    }
+
+    /// Sets the output format, e.g. `"wav"`.
+    pub fn output_format(mut self, format: impl Into<String>) -> Self {
+        self.options.output_format = Some(format.into());
+        self
+    }
+
+    /// Sets the style instructions.
+    pub fn instructions(mut self, instructions: impl Into<String>) -> Self {
+        self.options.instructions = Some(instructions.into());
+        self
+    }
+
+    /// Sets the playback speed multiplier.
+    pub fn speed(mut self, speed: f32) -> Self {
+        self.options.speed = Some(speed);
+        self
+    }
+}
+
+/// Input for [`transcribe`].
+pub struct TranscribeRequest {
+    /// Model used for the call.
+    pub model: Box<dyn TranscriptionModel>,
+    /// Call parameters.
+    pub options: TranscriptionOptions,
+}
+
+impl TranscribeRequest {
+    /// Creates a request transcribing `audio` of the given media type.
+    pub fn new(
+        model: impl TranscriptionModel + 'static,
+        audio: impl Into<DataContent>,
+        media_type: impl Into<String>,
+    ) -> Self {
+        Self {
+            model: Box::new(model),
+            options: TranscriptionOptions {
+                audio: audio.into(),
+                media_type: media_type.into(),
+                language: None,
+                prompt: None,
+                headers: HashMap::new(),
+                provider_options: ProviderOptions::new(),
+            },
+        }
+    }
+
+    /// Sets the expected language.
+    pub fn language(mut self, language: impl Into<String>) -> Self {
+        self.options.language = Some(language.into());
+        self
+    }
+
+    /// Sets the guiding prompt.
+    pub fn prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.options.prompt = Some(prompt.into());
+        self
+    }
+}
+
+/// Synthesizes speech.
+///
+/// # Errors
+///
+/// Returns [`AiError::Validation`] for empty text or a non-positive speed,
+/// and propagates any error reported by the model.
+pub async fn generate_speech(request: GenerateSpeechRequest) -> Result<SpeechResponse> {
+    let options = request.options;
+    if options.text.trim().is_empty() {
+        return Err(AiError::Validation("text must not be empty".into()));
+    }
+    if let Some(speed) = options.speed {
+        if !speed.is_finite() || speed <= 0.0 {
+            return Err(AiError::Validation("speed must be > 0".into()));
+        }
+    }
+    request.model.do_generate(options).await
+}
+
+/// Transcribes audio.
+///
+/// # Errors
+///
+/// Returns [`AiError::Validation`] for empty audio or a non-audio media type,
+/// and propagates any error reported by the model.
+pub async fn transcribe(request: TranscribeRequest) -> Result<TranscriptionResponse> {
+    let options = request.options;
+    let empty = match &options.audio {
+        DataContent::Url(url) => url.is_empty(),
+        DataContent::Base64(data) => data.is_empty(),
+        DataContent::Bytes(data) => data.is_empty(),
+    };
+    if empty {
+        return Err(AiError::Validation("audio must not be empty".into()));
+    }
+    if !options.media_type.starts_with("audio/") && !options.media_type.starts_with("video/") {
+        return Err(AiError::Validation(format!(
+            "unsupported media type for transcription: {}",
+            options.media_type
+        )));
+    }
+    request.model.do_transcribe(options).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct ToneModel;
+
+    #[async_trait]
+    impl SpeechModel for ToneModel {
+        fn provider(&self) -> &str {
+            "test"
+        }
+
+        fn model_id(&self) -> &str {
+            "tone"
+        }
+
+        async fn do_generate(&self, options: SpeechOptions) -> Result<SpeechResponse> {
+            let format = options.output_format.unwrap_or_else(|| "mp3".into());
+            let warnings = match options.instructions {
+                Some(_) => vec![CallWarning::unsupported_setting("instructions")],
+                None => Vec::new(),
+            };
+            Ok(SpeechResponse {
+                audio: GeneratedAudio::new(options.text.into_bytes(), format),
+                warnings,
+            })
+        }
+    }
+
+    struct EchoTranscriber;
+
+    #[async_trait]
+    impl TranscriptionModel for EchoTranscriber {
+        fn provider(&self) -> &str {
+            "test"
+        }
+
+        fn model_id(&self) -> &str {
+            "echo"
+        }
+
+        async fn do_transcribe(
+            &self,
+            options: TranscriptionOptions,
+        ) -> Result<TranscriptionResponse> {
+            let DataContent::Bytes(bytes) = options.audio else {
+                return Err(AiError::Validation("expected bytes".into()));
+            };
+            let text = String::from_utf8_lossy(&bytes).into_owned();
+            Ok(TranscriptionResponse {
+                segments: vec![TranscriptionSegment {
+                    text: text.clone(),
+                    start_second: 0.0,
+                    end_second: 1.5,
+                }],
+                text,
+                language: options.language,
+                duration_seconds: Some(1.5),
+                warnings: Vec::new(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_generate_speech() {
+        let response = generate_speech(
+            GenerateSpeechRequest::new(ToneModel, "hello")
+                .voice("alloy")
+                .output_format("wav")
+                .instructions("cheerful"),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(response.audio.data, b"hello");
+        assert_eq!(response.audio.format, "wav");
+        assert_eq!(response.audio.media_type, "audio/wav");
+        assert_eq!(
+            response.warnings,
+            vec![CallWarning::unsupported_setting("instructions")]
+        );
+
+        let invalid = GenerateSpeechRequest::new(ToneModel, "hello").speed(0.0);
+        assert!(matches!(
+            generate_speech(invalid).await,
+            Err(AiError::Validation(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_transcribe() {
+        let response = transcribe(
+            TranscribeRequest::new(EchoTranscriber, b"good morning".to_vec(), "audio/wav")
+                .language("en"),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(response.text, "good morning");
+        assert_eq!(response.segments[0].end_second, 1.5);
+        assert_eq!(response.language.as_deref(), Some("en"));
+
+        let not_audio = TranscribeRequest::new(EchoTranscriber, b"x".to_vec(), "image/png");
+        assert!(transcribe(not_audio).await.is_err());
+    }
+}
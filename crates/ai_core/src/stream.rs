@@ -0,0 +1,916 @@
+//! Streaming text generation.
+//!
+//! [`stream_text`] returns a [`StreamTextHandle`] over the provider stream.
+//! The handle can hand out any number of readers ([`StreamTextHandle::text_stream`],
+//! [`StreamTextHandle::full_stream`]) and every reader observes every event,
+//! so one generation can feed an HTTP response, a logger and an aggregator at
+//! the same time.
+//!
+//! When the request's [`AbortSignal`] fires, the provider stream is dropped
+//! (closing the HTTP connection) and readers receive a final
+//! [`StreamPart::Finish`] with [`FinishReason::Cancelled`] and the usage
+//! reported so far.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures::future;
+use futures::stream::{BoxStream, Stream, StreamExt};
+
+use crate::abort::AbortSignal;
+use crate::capabilities::validate_call;
+use crate::generate::text_request_methods;
+use crate::message::{Message, MessagePart, MessageRole, ReasoningPart, ToolCallPart};
+use crate::model::{LanguageModel, UnsetModel};
+use crate::pricing::{CostEstimate, PricedModel, PricingTable};
+use crate::settings::{CallSettings, ProviderOptions};
+use crate::tool::{ToolChoice, ToolDefinition};
+use crate::types::{CallWarning, FinishReason, ProviderMetadata, Source};
+use crate::usage::Usage;
+use ai_error::{AiError, Result};
+use ai_stream::{Multicast, Subscriber};
+
+/// A single event produced while streaming a generation.
+#[derive(Debug, Clone)]
+pub enum StreamPart {
+    /// A chunk of generated text.
+    TextDelta {
+        /// Text appended to the output.
+        text: String,
+    },
+    /// A chunk of model reasoning.
+    ReasoningDelta {
+        /// Reasoning text appended to the output.
+        text: String,
+    },
+    /// The model finished a block of reasoning.
+    ///
+    /// Carries what is needed to send the reasoning back on the next turn,
+    /// such as a signature or an encrypted payload, and is attached to the
+    /// reasoning part it closes. Reasoning deltas after it start a new part.
+    /// Data about the response as a whole goes in [`StreamPart::Metadata`].
+    ReasoningEnd {
+        /// Metadata of the reasoning part, keyed by provider name.
+        provider_metadata: Option<ProviderMetadata>,
+    },
+    /// The model started a tool call.
+    ToolCallStart {
+        /// Provider-assigned tool call identifier.
+        id: String,
+        /// Name of the tool being called.
+        tool_name: String,
+    },
+    /// A chunk of tool call input (partial JSON).
+    ToolCallDelta {
+        /// Identifier of the tool call the chunk belongs to.
+        id: String,
+        /// Input text appended to the call.
+        input_delta: String,
+    },
+    /// The model finished emitting a tool call.
+    ToolCallEnd {
+        /// Identifier of the completed tool call.
+        id: String,
+    },
+    /// A source the model referenced.
+    Source(Source),
+    /// Cumulative token usage so far, for providers that report it before
+    /// the stream finishes.
+    UsageUpdate {
+        /// Tokens consumed up to this point.
+        usage: Usage,
+    },
+    /// Provider-specific data about the response as a whole, such as a
+    /// response id. Entries of later events are merged into earlier ones.
+    Metadata {
+        /// Metadata keyed by provider name.
+        provider_metadata: ProviderMetadata,
+    },
+    /// Generation finished.
+    Finish {
+        /// Why the model stopped generating.
+        finish_reason: FinishReason,
+        /// Tokens consumed by the call.
+        usage: Usage,
+    },
+    /// An error occurred while streaming.
+    Error(Arc<AiError>),
+}
+
+/// Callback invoked once the stream has been fully consumed.
+pub type OnFinish = Box<dyn FnOnce(&StreamTextResult) + Send>;
+
+/// Input for [`stream_text`].
+///
+/// The [`Default`] implementation leaves `model` unset; calling
+/// [`stream_text`] without replacing it fails with [`AiError::Config`].
+pub struct StreamTextRequest {
+    /// Model used for the call.
+    pub model: Box<dyn LanguageModel>,
+    /// Optional system instruction.
+    pub system: Option<String>,
+    /// User prompt.
+    pub prompt: String,
+    /// Conversation history.
+    pub messages: Vec<Message>,
+    /// Sampling and length settings.
+    pub settings: CallSettings,
+    /// Additional HTTP headers to send with the request.
+    pub headers: HashMap<String, String>,
+    /// Tools the model may call.
+    pub tools: Vec<ToolDefinition>,
+    /// How the model should choose among `tools`.
+    pub tool_choice: Option<ToolChoice>,
+    /// Vendor-specific options keyed by provider name.
+    pub provider_options: ProviderOptions,
+    /// Signal that cancels the call when triggered.
+    pub abort_signal: Option<AbortSignal>,
+    /// Prices used to attach a [`CostEstimate`] to the response.
+    pub pricing: Option<Arc<dyn PricingTable>>,
+    /// Called with the aggregated result when the stream completes.
+    pub on_finish: Option<OnFinish>,
+}
+
+text_request_methods!(StreamTextRequest);
+
+impl StreamTextRequest {
+    /// Sets the callback invoked with the aggregated result.
+    pub fn on_finish(mut self, on_finish: impl FnOnce(&StreamTextResult) + Send + 'static) -> Self {
+        self.on_finish = Some(Box::new(on_finish));
+        self
+    }
+}
+
+impl Default for StreamTextRequest {
+    fn default() -> Self {
+        Self {
+            model: Box::new(UnsetModel),
+            system: None,
+            prompt: String::new(),
+            messages: Vec::new(),
+            settings: CallSettings::default(),
+            headers: HashMap::new(),
+            tools: Vec::new(),
+            tool_choice: None,
+            provider_options: ProviderOptions::new(),
+            abort_signal: None,
+            pricing: None,
+            on_finish: None,
+        }
+    }
+}
+
+/// Aggregated outcome of a streamed generation.
+#[derive(Debug, Clone, Default)]
+pub struct StreamTextResult {
+    /// Concatenated text deltas.
+    pub text: String,
+    /// Concatenated reasoning deltas.
+    pub reasoning: String,
+    /// Tool calls assembled from tool call events.
+    pub tool_calls: Vec<ToolCallPart>,
+    /// All generated content in output order, including reasoning parts
+    /// with their metadata and tool calls.
+    pub content: Vec<MessagePart>,
+    /// Sources referenced by the model.
+    pub sources: Vec<Source>,
+    /// Why the model stopped generating.
+    pub finish_reason: FinishReason,
+    /// Tokens consumed by the call.
+    pub usage: Usage,
+    /// Warnings raised by the provider while handling the call.
+    pub warnings: Vec<CallWarning>,
+    /// First error encountered while streaming, if any.
+    pub error: Option<Arc<AiError>>,
+    /// Estimated cost, if the request carried a pricing table that prices
+    /// the model.
+    pub cost: Option<CostEstimate>,
+    /// Provider-specific data merged from [`StreamPart::Metadata`] events.
+    pub provider_metadata: Option<ProviderMetadata>,
+}
+
+impl StreamTextResult {
+    /// Returns the generated content as an assistant message, ready to be
+    /// appended to the conversation.
+    pub fn to_message(&self) -> Message {
+        Message {
+            provider_metadata: self.provider_metadata.clone(),
+            ..Message::new(MessageRole::Assistant, self.content.clone())
+        }
+    }
+}
+
+/// Folds stream parts into a [`StreamTextResult`].
+#[derive(Default)]
+struct Aggregator {
+    result: StreamTextResult,
+    pending_tool_calls: Vec<PendingToolCall>,
+    /// Whether the last content part is reasoning that is still streaming.
+    reasoning_open: bool,
+    pricing: Option<PricedModel>,
+}
+
+struct PendingToolCall {
+    id: String,
+    tool_name: String,
+    input: String,
+}
+
+impl Aggregator {
+    fn new(warnings: Vec<CallWarning>, pricing: Option<PricedModel>) -> Self {
+        Self {
+            result: StreamTextResult {
+                warnings,
+                ..Default::default()
+            },
+            pending_tool_calls: Vec::new(),
+            reasoning_open: false,
+            pricing,
+        }
+    }
+
+    fn apply(&mut self, part: &StreamPart) {
+        let result = &mut self.result;
+        match part {
+            StreamPart::TextDelta { text } => {
+                result.text.push_str(text);
+                match result.content.last_mut() {
+                    Some(MessagePart::Text(last)) => last.text.push_str(text),
+                    _ => result.content.push(MessagePart::text(text.clone())),
+                }
+            }
+            StreamPart::ReasoningDelta { text } => {
+                result.reasoning.push_str(text);
+                match result.content.last_mut() {
+                    Some(MessagePart::Reasoning(last)) if self.reasoning_open => {
+                        last.text.push_str(text)
+                    }
+                    _ => result.content.push(MessagePart::reasoning(text.clone())),
+                }
+                self.reasoning_open = true;
+            }
+            StreamPart::ReasoningEnd { provider_metadata } => {
+                match result.content.last_mut() {
+                    Some(MessagePart::Reasoning(last)) if self.reasoning_open => {
+                        last.provider_metadata = provider_metadata.clone();
+                    }
+                    // Reasoning without text, such as redacted thinking.
+                    _ => result.content.push(MessagePart::Reasoning(ReasoningPart {
+                        text: String::new(),
+                        provider_metadata: provider_metadata.clone(),
+                    })),
+                }
+                self.reasoning_open = false;
+            }
+            StreamPart::ToolCallStart { id, tool_name } => {
+                self.pending_tool_calls.push(PendingToolCall {
+                    id: id.clone(),
+                    tool_name: tool_name.clone(),
+                    input: String::new(),
+                });
+            }
+            StreamPart::ToolCallDelta { id, input_delta } => {
+                if let Some(call) = self.pending_tool_calls.iter_mut().find(|c| &c.id == id) {
+                    call.input.push_str(input_delta);
+                }
+            }
+            StreamPart::ToolCallEnd { id } => {
+                if let Some(index) = self.pending_tool_calls.iter().position(|c| &c.id == id) {
+                    let call = self.pending_tool_calls.remove(index);
+                    let call = ToolCallPart {
+                        tool_call_id: call.id,
+                        tool_name: call.tool_name,
+                        input: parse_tool_input(&call.input),
+                        provider_metadata: None,
+                    };
+                    result.content.push(MessagePart::ToolCall(call.clone()));
+                    result.tool_calls.push(call);
+                }
+            }
+            StreamPart::Source(source) => result.sources.push(source.clone()),
+            StreamPart::Metadata { provider_metadata } => {
+                let merged = result
+                    .provider_metadata
+                    .get_or_insert_with(Default::default);
+                for (provider, value) in provider_metadata {
+                    match (merged.get_mut(provider), value) {
+                        (
+                            Some(serde_json::Value::Object(existing)),
+                            serde_json::Value::Object(new),
+                        ) => {
+                            existing.extend(new.clone());
+                        }
+                        _ => {
+                            merged.insert(provider.clone(), value.clone());
+                        }
+                    }
+                }
+            }
+            StreamPart::UsageUpdate { usage } => {
+                result.usage = *usage;
+                result.cost = self
+                    .pricing
+                    .as_ref()
+                    .and_then(|p| p.estimate(usage, result.provider_metadata.as_ref()));
+            }
+            StreamPart::Finish {
+                finish_reason,
+                usage,
+            } => {
+                result.finish_reason = *finish_reason;
+                result.usage = *usage;
+                result.cost = self
+                    .pricing
+                    .as_ref()
+                    .and_then(|p| p.estimate(usage, result.provider_metadata.as_ref()));
+            }
+            StreamPart::Error(error) => {
+                result.finish_reason = FinishReason::Error;
+                result.error.get_or_insert_with(|| Arc::clone(error));
+            }
+        }
+    }
+}
+
+/// Parses streamed tool call input, treating an empty input as `{}`.
+///
+/// Input that is not valid JSON is kept as a string so it can still be
+/// reported back to the model.
+fn parse_tool_input(input: &str) -> serde_json::Value {
+    if input.trim().is_empty() {
+        return serde_json::Value::Object(Default::default());
+    }
+    serde_json::from_str(input).unwrap_or_else(|_| serde_json::Value::String(input.to_string()))
+}
+
+/// Handle to an in-progress streamed generation.
+///
+/// Readers are independent: each call to [`text_stream`](Self::text_stream)
+/// or [`full_stream`](Self::full_stream) starts from the first event, and the
+/// provider stream is only consumed once.
+pub struct StreamTextHandle {
+    parts: Multicast<StreamPart>,
+    warnings: Vec<CallWarning>,
+    pricing: Option<PricedModel>,
+}
+
+impl StreamTextHandle {
+    /// Returns a stream of text deltas.
+    pub fn text_stream(&self) -> impl Stream<Item = String> + Send + Unpin + 'static {
+        self.parts.subscribe().filter_map(|part| {
+            future::ready(match part {
+                StreamPart::TextDelta { text } => Some(text),
+                _ => None,
+            })
+        })
+    }
+
+    /// Returns a stream of every event, including metadata and errors.
+    pub fn full_stream(&self) -> Subscriber<StreamPart> {
+        self.parts.subscribe()
+    }
+
+    /// Warnings raised by the provider while preparing the call.
+    pub fn warnings(&self) -> &[CallWarning] {
+        &self.warnings
+    }
+
+    /// Waits for the stream to complete and returns the aggregated result.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error event the stream emitted, unchanged, so
+    /// callers can still match on its variant.
+    pub async fn result(&self) -> std::result::Result<StreamTextResult, Arc<AiError>> {
+        let result = aggregate(
+            self.full_stream(),
+            self.warnings.clone(),
+            self.pricing.clone(),
+        )
+        .await;
+        match result.error {
+            Some(error) => Err(error),
+            None => Ok(result),
+        }
+    }
+
+    /// Waits for the stream to complete and returns the generated text.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error event the stream emitted, as
+    /// [`result`](Self::result) does.
+    pub async fn text(&self) -> std::result::Result<String, Arc<AiError>> {
+        self.result().await.map(|result| result.text)
+    }
+}
+
+impl fmt::Debug for StreamTextHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StreamTextHandle")
+            .field("warnings", &self.warnings)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Streams text for a prompt using a language model.
+///
+/// The returned future resolves once the provider has accepted the request.
+///
+/// # Errors
+///
+/// Returns [`AiError::Validation`] for malformed requests or requests that use
+/// features the model does not support, [`AiError::Cancelled`] if the abort
+/// signal fires before the provider accepts the request, and propagates any
+/// error the model reports before streaming starts.
+pub async fn stream_text(mut request: StreamTextRequest) -> Result<StreamTextHandle> {
+    let options = request.call_options()?;
+    validate_call(request.model.as_ref(), &options, true)?;
+
+    let signal = options.abort_signal.clone();
+    let response = match &signal {
+        Some(signal) => {
+            signal
+                .run_until_aborted(request.model.do_stream(options))
+                .await??
+        }
+        None => request.model.do_stream(options).await?,
+    };
+
+    let mut parts = response
+        .stream
+        .map(|item| match item {
+            Ok(part) => part,
+            Err(error) => StreamPart::Error(Arc::new(error)),
+        })
+        .boxed();
+    if let Some(signal) = signal {
+        parts = AbortOnSignal::new(parts, signal).boxed();
+    }
+    let model: Arc<dyn LanguageModel> = Arc::from(request.model);
+    let pricing = request
+        .pricing
+        .clone()
+        .map(|table| PricedModel::new(table, Arc::clone(&model)));
+    let parts = FinishObserver {
+        inner: parts,
+        aggregator: Aggregator::new(response.warnings.clone(), pricing.clone()),
+        on_finish: request.on_finish.take(),
+    };
+
+    Ok(StreamTextHandle {
+        parts: Multicast::new(parts),
+        warnings: response.warnings,
+        pricing,
+    })
+}
+
+async fn aggregate(
+    mut parts: impl Stream<Item = StreamPart> + Unpin,
+    warnings: Vec<CallWarning>,
+    pricing: Option<PricedModel>,
+) -> StreamTextResult {
+    let mut aggregator = Aggregator::new(warnings, pricing);
+    while let Some(part) = parts.next().await {
+        aggregator.apply(&part);
+    }
+    aggregator.result
+}
+
+/// Ends the stream with a cancelled [`StreamPart::Finish`] once the signal
+/// fires, dropping the provider stream.
+struct AbortOnSignal {
+    inner: Option<BoxStream<'static, StreamPart>>,
+    aborted: Pin<Box<dyn Future<Output = ()> + Send>>,
+    usage: Usage,
+}
+
+impl AbortOnSignal {
+    fn new(inner: BoxStream<'static, StreamPart>, signal: AbortSignal) -> Self {
+        Self {
+            inner: Some(inner),
+            aborted: Box::pin(async move { signal.aborted().await }),
+            usage: Usage::default(),
+        }
+    }
+}
+
+impl Stream for AbortOnSignal {
+    type Item = StreamPart;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<StreamPart>> {
+        let this = &mut *self;
+        let Some(inner) = this.inner.as_mut() else {
+            return Poll::Ready(None);
+        };
+
+        if this.aborted.as_mut().poll(cx).is_ready() {
+            this.inner = None;
+            return Poll::Ready(Some(StreamPart::Finish {
+                finish_reason: FinishReason::Cancelled,
+                usage: this.usage,
+            }));
+        }
+
+        match inner.poll_next_unpin(cx) {
+            Poll::Ready(Some(part)) => {
+                match &part {
+                    StreamPart::UsageUpdate { usage } => this.usage = *usage,
+                    // Nothing left to cancel once the provider has finished.
+                    StreamPart::Finish { .. } => this.aborted = Box::pin(future::pending()),
+                    _ => {}
+                }
+                Poll::Ready(Some(part))
+            }
+            Poll::Ready(None) => {
+                this.inner = None;
+                Poll::Ready(None)
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// Aggregates parts as they pass through and fires the `on_finish` callback
+/// when the underlying stream ends.
+struct FinishObserver {
+    inner: BoxStream<'static, StreamPart>,
+    aggregator: Aggregator,
+    on_finish: Option<OnFinish>,
+}
+
+impl Stream for FinishObserver {
+    type Item = StreamPart;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<StreamPart>> {
+        let this = &mut *self;
+        match this.inner.poll_next_unpin(cx) {
+            Poll::Ready(Some(part)) => {
+                if this.on_finish.is_some() {
+                    this.aggregator.apply(&part);
+                }
+                Poll::Ready(Some(part))
+            }
+            Poll::Ready(None) => {
+                if let Some(on_finish) = this.on_finish.take() {
+                    on_finish(&this.aggregator.result);
+                }
+                Poll::Ready(None)
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capabilities::ModelCapabilities;
+    use crate::model::{CallOptions, ModelResponse, ModelStream};
+    use crate::pricing::{ModelPricing, StaticPricingTable};
+    use async_trait::async_trait;
+    use futures::stream;
+    use std::sync::Mutex;
+
+    struct ScriptedModel {
+        parts: Vec<StreamPart>,
+        streaming: bool,
+    }
+
+    #[async_trait]
+    impl LanguageModel for ScriptedModel {
+        fn provider(&self) -> &str {
+            "test"
+        }
+
+        fn model_id(&self) -> &str {
+            "scripted"
+        }
+
+        fn capabilities(&self) -> ModelCapabilities {
+            ModelCapabilities {
+                streaming: self.streaming,
+                ..Default::default()
+            }
+        }
+
+        async fn do_generate(&self, _options: CallOptions) -> Result<ModelResponse> {
+            Err(AiError::Internal("not used in this test".into()))
+        }
+
+        async fn do_stream(&self, _options: CallOptions) -> Result<ModelStream> {
+            Ok(ModelStream {
+                stream: stream::iter(self.parts.clone().into_iter().map(Ok)).boxed(),
+                warnings: vec![CallWarning::unsupported_setting("seed")],
+            })
+        }
+    }
+
+    fn text(text: &str) -> StreamPart {
+        StreamPart::TextDelta { text: text.into() }
+    }
+
+    fn scripted() -> ScriptedModel {
+        ScriptedModel {
+            parts: vec![
+                StreamPart::ReasoningDelta {
+                    text: "think".into(),
+                },
+                text("Hel"),
+                text("lo"),
+                StreamPart::Finish {
+                    finish_reason: FinishReason::Stop,
+                    usage: Usage::new(4, 2),
+                },
+            ],
+            streaming: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_settings_builders() {
+        let request = StreamTextRequest::new(scripted(), "hi")
+            .top_p(0.9)
+            .top_k(3)
+            .stop_sequence("END")
+            .seed(7);
+        assert_eq!(
+            request.call_options().unwrap().settings,
+            CallSettings {
+                top_p: Some(0.9),
+                top_k: Some(3),
+                stop_sequences: vec!["END".into()],
+                seed: Some(7),
+                ..Default::default()
+            }
+        );
+
+        let error = stream_text(StreamTextRequest::new(scripted(), "hi").top_k(0))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, AiError::Validation(_)));
+    }
+
+    #[tokio::test]
+    async fn test_readers_share_one_stream() {
+        let handle = stream_text(StreamTextRequest::new(scripted(), "hi"))
+            .await
+            .unwrap();
+
+        let text_stream = handle.text_stream();
+        let full_stream = handle.full_stream();
+
+        assert_eq!(text_stream.collect::<Vec<_>>().await, vec!["Hel", "lo"]);
+        assert_eq!(full_stream.collect::<Vec<_>>().await.len(), 4);
+        assert_eq!(handle.warnings().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_on_finish_receives_aggregate() {
+        let seen = Arc::new(Mutex::new(None));
+        let sink = Arc::clone(&seen);
+        let pricing =
+            StaticPricingTable::new().with("test", "scripted", ModelPricing::new(1.0, 1.0));
+        let handle = stream_text(
+            StreamTextRequest::new(scripted(), "hi")
+                .pricing(Arc::new(pricing))
+                .on_finish(move |result| *sink.lock().unwrap() = Some(result.clone())),
+        )
+        .await
+        .unwrap();
+
+        let result = handle.result().await.unwrap();
+        assert_eq!(result.text, "Hello");
+        assert_eq!(result.reasoning, "think");
+
+        let finished = seen.lock().unwrap().take().unwrap();
+        assert_eq!(finished.text, "Hello");
+        assert_eq!(finished.finish_reason, FinishReason::Stop);
+        assert_eq!(finished.usage, Usage::new(4, 2));
+        assert_eq!(finished.cost.unwrap().total_cost(), 6.0 / 1_000_000.0);
+        assert_eq!(result.cost, finished.cost);
+    }
+
+    #[tokio::test]
+    async fn test_result_assembles_tool_calls() {
+        let model = ScriptedModel {
+            parts: vec![
+                StreamPart::ToolCallStart {
+                    id: "call_1".into(),
+                    tool_name: "weather".into(),
+                },
+                StreamPart::ToolCallDelta {
+                    id: "call_1".into(),
+                    input_delta: "{\"city\":".into(),
+                },
+                StreamPart::ToolCallDelta {
+                    id: "call_1".into(),
+                    input_delta: "\"Oslo\"}".into(),
+                },
+                StreamPart::ToolCallEnd {
+                    id: "call_1".into(),
+                },
+            ],
+            streaming: true,
+        };
+        let handle = stream_text(StreamTextRequest::new(model, "hi"))
+            .await
+            .unwrap();
+
+        let result = handle.result().await.unwrap();
+        assert_eq!(result.tool_calls.len(), 1);
+        assert_eq!(result.tool_calls[0].tool_name, "weather");
+        assert_eq!(
+            result.tool_calls[0].input,
+            serde_json::json!({ "city": "Oslo" })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_result_content_keeps_reasoning_metadata() {
+        let signed = |signature: &str| {
+            Some(
+                [(
+                    "anthropic".to_string(),
+                    serde_json::json!({ "signature": signature }),
+                )]
+                .into(),
+            )
+        };
+        let model = ScriptedModel {
+            parts: vec![
+                StreamPart::ReasoningDelta {
+                    text: "First ".into(),
+                },
+                StreamPart::ReasoningDelta {
+                    text: "thought".into(),
+                },
+                StreamPart::ReasoningEnd {
+                    provider_metadata: signed("sig_1"),
+                },
+                StreamPart::ReasoningEnd {
+                    provider_metadata: signed("sig_2"),
+                },
+                text("Answer"),
+                StreamPart::ToolCallStart {
+                    id: "call_1".into(),
+                    tool_name: "weather".into(),
+                },
+                StreamPart::ToolCallEnd {
+                    id: "call_1".into(),
+                },
+            ],
+            streaming: true,
+        };
+        let handle = stream_text(StreamTextRequest::new(model, "hi"))
+            .await
+            .unwrap();
+
+        let message = handle.result().await.unwrap().to_message();
+        assert_eq!(message.role, MessageRole::Assistant);
+        let [MessagePart::Reasoning(first), MessagePart::Reasoning(second), MessagePart::Text(answer), MessagePart::ToolCall(call)] =
+            &message.parts[..]
+        else {
+            panic!("unexpected content: {:?}", message.parts);
+        };
+        assert_eq!(first.text, "First thought");
+        assert_eq!(first.provider_metadata, signed("sig_1"));
+        assert_eq!(second.text, "");
+        assert_eq!(second.provider_metadata, signed("sig_2"));
+        assert_eq!(answer.text, "Answer");
+        assert_eq!(call.input, serde_json::json!({}));
+    }
+
+    #[tokio::test]
+    async fn test_metadata_events_are_merged() {
+        let metadata = |value: serde_json::Value| StreamPart::Metadata {
+            provider_metadata: [("openai".to_string(), value)].into_iter().collect(),
+        };
+        let model = ScriptedModel {
+            parts: vec![
+                metadata(serde_json::json!({ "responseId": "resp_1" })),
+                text("hi"),
+                metadata(serde_json::json!({ "serviceTier": "default" })),
+            ],
+            streaming: true,
+        };
+        let handle = stream_text(StreamTextRequest::new(model, "hi"))
+            .await
+            .unwrap();
+
+        let result = handle.result().await.unwrap();
+        assert_eq!(
+            result.provider_metadata.unwrap()["openai"],
+            serde_json::json!({ "responseId": "resp_1", "serviceTier": "default" })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_error_part_fails_result() {
+        let model = ScriptedModel {
+            parts: vec![
+                text("partial"),
+                StreamPart::Error(Arc::new(AiError::RateLimit { retry_after: None })),
+            ],
+            streaming: true,
+        };
+        let handle = stream_text(StreamTextRequest::new(model, "hi"))
+            .await
+            .unwrap();
+
+        let error = handle.text().await.unwrap_err();
+        assert!(matches!(*error, AiError::RateLimit { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_stream_text_requires_streaming_capability() {
+        let model = ScriptedModel {
+            parts: Vec::new(),
+            streaming: false,
+        };
+        let error = stream_text(StreamTextRequest::new(model, "hi"))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, AiError::Validation(_)));
+    }
+
+    /// Emits a few parts and then hangs, like a provider mid-generation.
+    struct StallingModel;
+
+    #[async_trait]
+    impl LanguageModel for StallingModel {
+        fn provider(&self) -> &str {
+            "test"
+        }
+
+        fn model_id(&self) -> &str {
+            "stalling"
+        }
+
+        fn capabilities(&self) -> ModelCapabilities {
+            ModelCapabilities {
+                streaming: true,
+                ..Default::default()
+            }
+        }
+
+        async fn do_generate(&self, _options: CallOptions) -> Result<ModelResponse> {
+            Err(AiError::Internal("not used in this test".into()))
+        }
+
+        async fn do_stream(&self, _options: CallOptions) -> Result<ModelStream> {
+            let parts = vec![
+                text("Hel"),
+                StreamPart::UsageUpdate {
+                    usage: Usage::new(4, 1),
+                },
+            ];
+            Ok(ModelStream {
+                stream: stream::iter(parts.into_iter().map(Ok))
+                    .chain(stream::pending())
+                    .boxed(),
+                warnings: Vec::new(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_abort_ends_stream_as_cancelled() {
+        let signal = AbortSignal::new();
+        let handle =
+            stream_text(StreamTextRequest::new(StallingModel, "hi").abort_signal(signal.clone()))
+                .await
+                .unwrap();
+
+        let mut parts = handle.full_stream();
+        assert!(matches!(
+            parts.next().await,
+            Some(StreamPart::TextDelta { .. })
+        ));
+        assert!(matches!(
+            parts.next().await,
+            Some(StreamPart::UsageUpdate { .. })
+        ));
+        signal.abort();
+
+        let result = handle.result().await.unwrap();
+        assert_eq!(result.text, "Hel");
+        assert_eq!(result.finish_reason, FinishReason::Cancelled);
+        assert_eq!(result.usage, Usage::new(4, 1));
+        assert_eq!(result.cost, None);
+    }
+
+    #[tokio::test]
+    async fn test_already_aborted_signal_fails_fast() {
+        let signal = AbortSignal::new();
+        signal.abort();
+        let error = stream_text(StreamTextRequest::new(StallingModel, "hi").abort_signal(signal))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, AiError::Cancelled));
+    }
+}
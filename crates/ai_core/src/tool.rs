@@ -0,0 +1,173 @@
+//! Tool definitions offered to a model.
+//!
+//! These types only describe tools; executing them and feeding results back
+//! is up to the caller (or an agent loop), which hands each execution a
+//! [`ToolExecutionContext`].
+
+use std::future::Future;
+
+use serde::{Deserialize, Serialize};
+
+use crate::abort::AbortSignal;
+use crate::message::{CacheControl, Message};
+use ai_error::{AiError, Result};
+
+/// A function the model may call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolDefinition {
+    /// Unique tool name the model uses to call it.
+    pub name: String,
+    /// What the tool does, to help the model decide when to use it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// JSON Schema of the tool input.
+    pub input_schema: serde_json::Value,
+    /// Prompt-caching hint. Tools precede the messages in the prompt, so a
+    /// hint on the last tool caches all tool definitions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_control: Option<CacheControl>,
+}
+
+impl ToolDefinition {
+    /// Creates a tool definition.
+    pub fn new(
+        name: impl Into<String>,
+        description: impl Into<String>,
+        input_schema: serde_json::Value,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            description: Some(description.into()),
+            input_schema,
+            cache_control: None,
+        }
+    }
+
+    /// Sets the prompt-caching hint of this tool.
+    pub fn with_cache_control(mut self, cache_control: CacheControl) -> Self {
+        self.cache_control = Some(cache_control);
+        self
+    }
+}
+
+/// How the model should choose among the offered tools.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum ToolChoice {
+    /// The model decides whether to call a tool.
+    #[default]
+    Auto,
+    /// The model must not call a tool.
+    None,
+    /// The model must call at least one tool.
+    Required,
+    /// The model must call the named tool.
+    Tool {
+        /// Name of the tool to call.
+        #[serde(rename = "toolName")]
+        tool_name: String,
+    },
+}
+
+/// What a tool execution knows about the call that triggered it.
+///
+/// Carries the abort signal of the surrounding generation, so stopping a
+/// request also stops the tools it started. Executors should wrap their work
+/// in [`run`](Self::run) or poll [`AbortSignal::is_aborted`] between steps.
+#[derive(Debug, Clone)]
+pub struct ToolExecutionContext {
+    /// Identifier of the tool call being executed.
+    pub tool_call_id: String,
+    /// Messages of the conversation up to and including the tool call.
+    pub messages: Vec<Message>,
+    /// Signal triggered when the generation is aborted.
+    pub abort_signal: AbortSignal,
+}
+
+impl ToolExecutionContext {
+    /// Creates a context with a signal that is never triggered.
+    pub fn new(tool_call_id: impl Into<String>, messages: Vec<Message>) -> Self {
+        Self {
+            tool_call_id: tool_call_id.into(),
+            messages,
+            abort_signal: AbortSignal::new(),
+        }
+    }
+
+    /// Sets the signal, typically the one passed to
+    /// [`crate::generate_text`] or [`crate::stream_text`].
+    pub fn with_abort_signal(mut self, signal: AbortSignal) -> Self {
+        self.abort_signal = signal;
+        self
+    }
+
+    /// Runs `future` until it completes or the execution is aborted.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AiError::Cancelled`] if the signal fires first.
+    pub async fn run<F: Future>(&self, future: F) -> Result<F::Output> {
+        self.abort_signal.run_until_aborted(future).await
+    }
+}
+
+/// Checks that tool names are unique and that `choice` names an offered tool.
+pub(crate) fn validate_tools(tools: &[ToolDefinition], choice: Option<&ToolChoice>) -> Result<()> {
+    for (index, tool) in tools.iter().enumerate() {
+        if tool.name.is_empty() {
+            return Err(AiError::Validation("tool name must not be empty".into()));
+        }
+        if tools[..index].iter().any(|other| other.name == tool.name) {
+            return Err(AiError::Validation(format!(
+                "duplicate tool name '{}'",
+                tool.name
+            )));
+        }
+    }
+
+    if let Some(ToolChoice::Tool { tool_name }) = choice {
+        if !tools.iter().any(|tool| &tool.name == tool_name) {
+            return Err(AiError::Validation(format!(
+                "tool_choice names unknown tool '{tool_name}'"
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate_tools() {
+        let weather = ToolDefinition::new("weather", "Current weather", json!({"type": "object"}));
+        assert!(validate_tools(&[weather.clone()], None).is_ok());
+
+        let duplicate = validate_tools(&[weather.clone(), weather.clone()], None);
+        assert!(matches!(duplicate, Err(AiError::Validation(_))));
+
+        let choice = ToolChoice::Tool {
+            tool_name: "search".into(),
+        };
+        assert!(validate_tools(&[weather], Some(&choice)).is_err());
+        assert_eq!(
+            serde_json::to_value(&choice).unwrap(),
+            json!({ "type": "tool", "toolName": "search" })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tool_execution_observes_abort_signal() {
+        let signal = AbortSignal::new();
+        let context =
+            ToolExecutionContext::new("call_1", Vec::new()).with_abort_signal(signal.clone());
+        assert_eq!(context.run(async { "sunny" }).await.unwrap(), "sunny");
+
+        signal.abort();
+        let result = context.run(std::future::pending::<()>()).await;
+        assert!(matches!(result, Err(AiError::Cancelled)));
+    }
+}
@@ -0,0 +1,121 @@
+//! Small shared types used across requests and responses.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Provider-specific metadata keyed by provider name (e.g. `"anthropic"`).
+///
+/// Values are opaque JSON owned by the respective provider.
+pub type ProviderMetadata = HashMap<String, serde_json::Value>;
+
+/// Reason why a model stopped generating.
+///
+/// Serialized in kebab-case (`"tool-calls"`, `"content-filter"`) to match the
+/// AI SDK wire format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FinishReason {
+    /// The model reached a natural stop point or a stop sequence.
+    Stop,
+    /// The maximum number of output tokens was reached.
+    Length,
+    /// Output was withheld by the provider's content filter.
+    ContentFilter,
+    /// The prompt was rejected by the provider's safety filter before any
+    /// output was generated.
+    PromptBlocked,
+    /// The model stopped to request one or more tool calls.
+    ToolCalls,
+    /// Generation stopped because of an error.
+    Error,
+    /// Generation was stopped through an abort signal.
+    Cancelled,
+    /// The provider reported a reason that has no dedicated variant.
+    Other,
+    /// The provider did not report a reason.
+    #[default]
+    Unknown,
+}
+
+/// Non-fatal notice produced while preparing or executing a model call.
+///
+/// Providers return warnings instead of failing when a request contains
+/// something they cannot honour, so callers can surface or log the mismatch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum CallWarning {
+    /// A call setting is not supported by the model and was ignored.
+    UnsupportedSetting {
+        /// Name of the ignored setting (e.g. `"top_k"`).
+        setting: String,
+        /// Optional explanation of why the setting was ignored.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        details: Option<String>,
+    },
+    /// Any other warning.
+    Other {
+        /// Human readable warning message.
+        message: String,
+    },
+}
+
+impl CallWarning {
+    /// Creates an [`CallWarning::UnsupportedSetting`] warning without details.
+    pub fn unsupported_setting(setting: impl Into<String>) -> Self {
+        CallWarning::UnsupportedSetting {
+            setting: setting.into(),
+            details: None,
+        }
+    }
+
+    /// Creates an [`CallWarning::Other`] warning.
+    pub fn other(message: impl Into<String>) -> Self {
+        CallWarning::Other {
+            message: message.into(),
+        }
+    }
+}
+
+/// A document or web page the model used to ground its answer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Source {
+    /// Identifier of the source, unique within a response.
+    pub id: String,
+    /// URL of the source.
+    pub url: String,
+    /// Optional human readable title.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_finish_reason_wire_names() {
+        assert_eq!(
+            serde_json::to_string(&FinishReason::ToolCalls).unwrap(),
+            "\"tool-calls\""
+        );
+        assert_eq!(
+            serde_json::from_str::<FinishReason>("\"content-filter\"").unwrap(),
+            FinishReason::ContentFilter
+        );
+        assert_eq!(
+            serde_json::to_string(&FinishReason::Cancelled).unwrap(),
+            "\"cancelled\""
+        );
+    }
+
+    #[test]
+    fn test_call_warning_serialization() {
+        let warning = CallWarning::unsupported_setting("top_k");
+        assert_eq!(
+            serde_json::to_value(&warning).unwrap(),
+            serde_json::json!({ "type": "unsupported-setting", "setting": "top_k" })
+        );
+    }
+}
@@ -0,0 +1,106 @@
+//! Token usage reported by model calls.
+
+use std::iter::Sum;
+use std::ops::{Add, AddAssign};
+
+use serde::{Deserialize, Serialize};
+
+/// Token counts consumed by a model call.
+///
+/// Usage values can be added together to total several calls, e.g. the steps
+/// of an agent loop or the batches of an embedding job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Usage {
+    /// Tokens consumed by the prompt.
+    pub input_tokens: u64,
+    /// Tokens produced by the model.
+    pub output_tokens: u64,
+    /// Portion of `input_tokens` served from the provider's prompt cache
+    /// (cache reads).
+    pub cached_input_tokens: u64,
+    /// Portion of `input_tokens` written to the provider's prompt cache
+    /// (cache creation). Only reported by providers that bill cache writes
+    /// separately, such as Anthropic.
+    pub cache_creation_input_tokens: u64,
+    /// Portion of `output_tokens` spent on reasoning.
+    pub reasoning_tokens: u64,
+}
+
+impl Usage {
+    /// Creates a usage record from input and output token counts.
+    pub fn new(input_tokens: u64, output_tokens: u64) -> Self {
+        Self {
+            input_tokens,
+            output_tokens,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the sum of input and output tokens.
+    pub fn total_tokens(&self) -> u64 {
+        self.input_tokens + self.output_tokens
+    }
+}
+
+impl Add for Usage {
+    type Output = Usage;
+
+    fn add(mut self, other: Usage) -> Usage {
+        self += other;
+        self
+    }
+}
+
+impl AddAssign for Usage {
+    fn add_assign(&mut self, other: Usage) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cached_input_tokens += other.cached_input_tokens;
+        self.cache_creation_input_tokens += other.cache_creation_input_tokens;
+        self.reasoning_tokens += other.reasoning_tokens;
+    }
+}
+
+impl Sum for Usage {
+    fn sum<I: Iterator<Item = Usage>>(iter: I) -> Usage {
+        iter.fold(Usage::default(), Add::add)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_total_tokens() {
+        assert_eq!(Usage::new(12, 30).total_tokens(), 42);
+        assert_eq!(Usage::default().total_tokens(), 0);
+    }
+
+    #[test]
+    fn test_usage_adds_across_steps() {
+        let step = Usage {
+            cached_input_tokens: 4,
+            cache_creation_input_tokens: 3,
+            reasoning_tokens: 2,
+            ..Usage::new(10, 5)
+        };
+        let total: Usage = [step, step, Usage::new(1, 1)].into_iter().sum();
+
+        assert_eq!(
+            total,
+            Usage {
+                input_tokens: 21,
+                output_tokens: 11,
+                cached_input_tokens: 8,
+                cache_creation_input_tokens: 6,
+                reasoning_tokens: 4,
+            }
+        );
+        assert_eq!(
+            serde_json::from_str::<Usage>(r#"{"inputTokens":3,"outputTokens":1}"#).unwrap(),
+            Usage::new(3, 1)
+        );
+    }
+}
@@ -0,0 +1,126 @@
+//! Diagnostic context attached to provider errors.
+
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+
+/// Headers whose values are replaced by [`REDACTED`] when captured.
+///
+/// Compared case-insensitively. Response headers rarely carry credentials,
+/// but gateways and proxies sometimes echo them back.
+pub const SENSITIVE_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "set-cookie",
+    "api-key",
+    "x-api-key",
+    "x-goog-api-key",
+    "x-amz-security-token",
+];
+
+/// Placeholder stored instead of a redacted header value.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Headers vendors use to identify a request, in order of preference.
+const REQUEST_ID_HEADERS: &[&str] = &[
+    "x-request-id",
+    "request-id",
+    "x-amzn-requestid",
+    "apim-request-id",
+    "x-ms-request-id",
+];
+
+/// What was known about the HTTP exchange when an error occurred.
+///
+/// Attached to [`AiError::Provider`](crate::AiError::Provider) by
+/// [`ensure_success`](crate::ensure_success), and meant for logs and support
+/// tickets rather than for deciding how to handle the error.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ErrorDetails {
+    /// HTTP status of the response, if one was received.
+    pub status: Option<u16>,
+    /// Vendor request id, taken from `x-request-id` or the vendor's
+    /// equivalent header.
+    pub request_id: Option<String>,
+    /// URL of the request, without its query string.
+    pub url: Option<String>,
+    /// Response headers with lowercase names, in the order received.
+    pub headers: Vec<(String, String)>,
+    /// Raw response body.
+    pub body: Option<String>,
+}
+
+impl ErrorDetails {
+    /// Captures a failed response, redacting [`SENSITIVE_HEADERS`].
+    pub fn from_response(status: StatusCode, headers: &HeaderMap, body: &str) -> Self {
+        let mut details = Self {
+            status: Some(status.as_u16()),
+            headers: headers
+                .iter()
+                .map(|(name, value)| {
+                    let value = String::from_utf8_lossy(value.as_bytes()).into_owned();
+                    (name.as_str().to_string(), value)
+                })
+                .collect(),
+            body: (!body.is_empty()).then(|| body.to_string()),
+            ..Self::default()
+        };
+        details.request_id = REQUEST_ID_HEADERS
+            .iter()
+            .find_map(|name| details.header(name))
+            .map(str::to_string);
+        for name in SENSITIVE_HEADERS {
+            details.redact(name);
+        }
+        details
+    }
+
+    /// Captures what a transport error knows about the request.
+    ///
+    /// [`AiError::Network`](crate::AiError::Network) carries the
+    /// `reqwest::Error` itself rather than details, so this is for callers
+    /// that want to log it in the same shape as a provider error.
+    pub fn from_reqwest(error: &reqwest::Error) -> Self {
+        Self {
+            status: error.status().map(|status| status.as_u16()),
+            url: error.url().map(|url| {
+                let mut url = url.clone();
+                url.set_query(None);
+                url.to_string()
+            }),
+            ..Self::default()
+        }
+    }
+
+    /// Returns the first value of header `name`, compared
+    /// case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Replaces every value of header `name` with [`REDACTED`].
+    pub fn redact(&mut self, name: &str) {
+        for (header, value) in &mut self.headers {
+            if header.eq_ignore_ascii_case(name) {
+                *value = REDACTED.to_string();
+            }
+        }
+    }
+
+    /// Returns the details with the values of `names` redacted, in addition
+    /// to [`SENSITIVE_HEADERS`].
+    pub fn with_redacted(mut self, names: &[&str]) -> Self {
+        for name in names {
+            self.redact(name);
+        }
+        self
+    }
+
+    /// Returns true if nothing was captured.
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
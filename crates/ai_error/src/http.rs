@@ -0,0 +1,78 @@
+//! Helpers for providers that talk to an HTTP API.
+//!
+//! Every provider checks the response status the same way and only differs
+//! in how it reads its error bodies, so it passes a mapper to
+//! [`ensure_success`] instead of reimplementing the check.
+
+use std::time::Duration;
+
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{Response, StatusCode};
+
+use crate::{AiError, ErrorDetails, Result};
+
+/// Returns `response` unchanged if it succeeded.
+///
+/// Otherwise reads the body and passes the provider name, status, headers
+/// and body to `map_error`, whose result is returned as the error. If that
+/// is an [`AiError::Provider`], the response is attached to it as
+/// [`ErrorDetails`].
+///
+/// # Errors
+///
+/// Returns the error built by `map_error` for any non-2xx status.
+pub async fn ensure_success<F>(provider: &str, response: Response, map_error: F) -> Result<Response>
+where
+    F: FnOnce(&str, StatusCode, &HeaderMap, &str) -> AiError,
+{
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let mut url = response.url().clone();
+    url.set_query(None);
+    let headers = response.headers().clone();
+    let body = response.text().await.unwrap_or_default();
+    let details = ErrorDetails {
+        url: Some(url.to_string()),
+        ..ErrorDetails::from_response(status, &headers, &body)
+    };
+    Err(map_error(provider, status, &headers, &body).with_details(details))
+}
+
+/// Parses a `Retry-After` header given in seconds.
+///
+/// HTTP dates are not supported and yield `None`, as do negative or
+/// non-finite values.
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    headers
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|seconds| seconds.is_finite() && *seconds >= 0.0)
+        .map(Duration::from_secs_f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_retry_after() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static(" 1.5 "));
+        assert_eq!(retry_after(&headers), Some(Duration::from_millis(1500)));
+
+        for value in ["-1", "soon", "Wed, 21 Oct 2015 07:28:00 GMT"] {
+            headers.insert(RETRY_AFTER, HeaderValue::from_static(value));
+            assert_eq!(retry_after(&headers), None, "{value}");
+        }
+    }
+}
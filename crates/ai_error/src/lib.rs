@@ -6,14 +6,24 @@
 #![forbid(unsafe_code)]
 #![warn(missing_docs, rust_2018_idioms)]
 
+mod details;
+
 use std::time::Duration;
 use thiserror::Error;
 
+pub mod http;
+
+pub use details::{ErrorDetails, REDACTED, SENSITIVE_HEADERS};
+pub use http::{ensure_success, retry_after};
+
 /// Main error type for AI SDK operations.
 ///
 /// This error type covers all possible error conditions that can occur
 /// during AI operations, from authentication failures to provider-specific errors.
+///
+/// New variants may be added in minor releases, so matches need a wildcard arm.
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum AiError {
     /// Authentication with the provider failed.
     #[error("Authentication failed: {0}")]
@@ -52,6 +62,8 @@ pub enum AiError {
         message: String,
         /// Optional error code from the provider
         code: Option<String>,
+        /// Optional context about the failed response
+        details: Option<Box<ErrorDetails>>,
     },
 
     /// Request timeout.
@@ -90,6 +102,10 @@ pub enum AiError {
     /// Configuration error.
     #[error("Configuration error: {0}")]
     Config(String),
+
+    /// The operation was cancelled through an abort signal.
+    #[error("Operation cancelled")]
+    Cancelled,
 }
 
 impl AiError {
@@ -113,6 +129,7 @@ impl AiError {
             AiError::SchemaValidation(_) => "VALIDATION_ERROR",
             AiError::Stream(_) => "STREAM_ERROR",
             AiError::Config(_) => "CONFIG_ERROR",
+            AiError::Cancelled => "CANCELLED_ERROR",
         }
     }
 
@@ -133,6 +150,36 @@ impl AiError {
             _ => None,
         }
     }
+
+    /// Returns the diagnostic context of a provider error.
+    pub fn details(&self) -> Option<&ErrorDetails> {
+        match self {
+            AiError::Provider { details, .. } => details.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Returns the diagnostic context mutably, e.g. to redact more headers.
+    pub fn details_mut(&mut self) -> Option<&mut ErrorDetails> {
+        match self {
+            AiError::Provider { details, .. } => details.as_deref_mut(),
+            _ => None,
+        }
+    }
+
+    /// Returns the vendor request id, if one was captured.
+    pub fn request_id(&self) -> Option<&str> {
+        self.details()?.request_id.as_deref()
+    }
+
+    /// Attaches `details` to a provider error, replacing any already
+    /// present. Other variants are returned unchanged.
+    pub fn with_details(mut self, new: ErrorDetails) -> Self {
+        if let AiError::Provider { details, .. } = &mut self {
+            *details = Some(Box::new(new));
+        }
+        self
+    }
 }
 
 /// Result type alias using [`AiError`].
@@ -182,4 +229,42 @@ mod tests {
         let error = AiError::Auth("failed".into());
         assert_eq!(error.retry_after(), None);
     }
+
+    #[test]
+    fn test_cancelled() {
+        assert_eq!(AiError::Cancelled.error_code(), "CANCELLED_ERROR");
+        assert!(!AiError::Cancelled.is_retryable());
+    }
+
+    #[test]
+    fn test_provider_error_details() {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert("x-request-id", "req_123".parse().unwrap());
+        headers.insert("set-cookie", "session=secret".parse().unwrap());
+        headers.insert("x-trace", "abc".parse().unwrap());
+        let details = ErrorDetails::from_response(
+            reqwest::StatusCode::BAD_GATEWAY,
+            &headers,
+            "upstream failed",
+        );
+
+        let error = AiError::Provider {
+            provider: "openai".into(),
+            message: "HTTP 502".into(),
+            code: None,
+            details: None,
+        }
+        .with_details(details.with_redacted(&["X-Trace"]));
+        assert_eq!(error.error_code(), "PROVIDER_ERROR");
+        assert_eq!(error.request_id(), Some("req_123"));
+
+        let details = error.details().unwrap();
+        assert_eq!(details.status, Some(502));
+        assert_eq!(details.body.as_deref(), Some("upstream failed"));
+        assert_eq!(details.header("Set-Cookie"), Some(REDACTED));
+        assert_eq!(details.header("x-trace"), Some(REDACTED));
+
+        let error = AiError::Auth("failed".into()).with_details(ErrorDetails::default());
+        assert!(error.details().is_none());
+    }
 }
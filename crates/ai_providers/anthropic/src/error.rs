@@ -0,0 +1,92 @@
+//! Mapping of Anthropic HTTP errors onto [`AiError`].
+
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+use ai_error::{retry_after, AiError};
+
+/// Error envelope returned by the Messages API, also sent as an `error`
+/// event mid-stream.
+#[derive(Debug, Deserialize)]
+pub(crate) struct ErrorEnvelope {
+    error: ErrorBody,
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorBody {
+    #[serde(rename = "type")]
+    kind: String,
+    message: String,
+}
+
+impl ErrorEnvelope {
+    /// Converts the error into [`AiError::Provider`] with the error type
+    /// (e.g. `"overloaded_error"`) as the code.
+    pub(crate) fn into_error(self, provider: &str) -> AiError {
+        AiError::Provider {
+            provider: provider.to_string(),
+            message: self.error.message,
+            code: Some(self.error.kind),
+            details: None,
+        }
+    }
+}
+
+/// Maps a non-success response onto the matching [`AiError`] variant.
+pub(crate) fn map_error(
+    provider: &str,
+    status: StatusCode,
+    headers: &HeaderMap,
+    body: &str,
+) -> AiError {
+    let parsed = serde_json::from_str::<ErrorEnvelope>(body).ok();
+
+    match (status, parsed) {
+        (StatusCode::TOO_MANY_REQUESTS, _) => AiError::RateLimit {
+            retry_after: retry_after(headers),
+        },
+        (StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN, parsed) => AiError::Auth(
+            parsed.map_or_else(|| format!("HTTP {status}: {body}"), |e| e.error.message),
+        ),
+        (_, Some(envelope)) => envelope.into_error(provider),
+        (_, None) => AiError::Provider {
+            provider: provider.to_string(),
+            message: format!("HTTP {status}: {body}"),
+            code: None,
+            details: None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::{HeaderValue, RETRY_AFTER};
+    use std::time::Duration;
+
+    #[test]
+    fn test_map_error_by_status() {
+        let body = r#"{"type":"error","error":{"type":"authentication_error","message":"invalid x-api-key"}}"#;
+        let error = map_error(
+            "anthropic",
+            StatusCode::UNAUTHORIZED,
+            &HeaderMap::new(),
+            body,
+        );
+        assert!(matches!(error, AiError::Auth(message) if message == "invalid x-api-key"));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("12"));
+        let error = map_error("anthropic", StatusCode::TOO_MANY_REQUESTS, &headers, "");
+        assert_eq!(error.retry_after(), Some(Duration::from_secs(12)));
+
+        let body = r#"{"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#;
+        let status = StatusCode::from_u16(529).unwrap();
+        let error = map_error("anthropic", status, &HeaderMap::new(), body);
+        assert!(matches!(
+            error,
+            AiError::Provider { code: Some(code), .. } if code == "overloaded_error"
+        ));
+    }
+}
@@ -1,14 +1,27 @@
-pub fn add(left: usize, right: usize) -> usize {
-    left + right
-}
+//! Anthropic provider for the AI SDK.
+//!
+//! [`AnthropicMessagesModel`] implements the Messages API, including tool
+//! use, image and PDF input, and extended thinking.
+//!
+//! ```no_run
+//! use ai_core::{generate_text, GenerateTextRequest};
+//! use ai_providers_anthropic::AnthropicProvider;
+//!
+//! # async fn run() -> ai_error::Result<()> {
+//! let provider = AnthropicProvider::from_env()?;
+//! let model = provider.messages_model("claude-sonnet-4-20250514");
+//! let response = generate_text(GenerateTextRequest::new(model, "Hello!")).await?;
+//! println!("{}", response.text);
+//! # Ok(())
+//! # }
+//! ```
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+#![forbid(unsafe_code)]
+#![warn(missing_docs, rust_2018_idioms)]
 
-    #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
-    }
-}
+mod error;
+mod messages;
+mod provider;
+
+pub use messages::AnthropicMessagesModel;
+pub use provider::AnthropicProvider;
@@ -0,0 +1,937 @@
+//! Text generation through the Anthropic Messages API.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use futures::stream::StreamExt;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::error::{map_error, ErrorEnvelope};
+use crate::provider::AnthropicProvider;
+use ai_core::{
+    CacheControl, CallOptions, CallWarning, DataContent, FinishReason, LanguageModel, Message,
+    MessagePart, MessageRole, ModelCapabilities, ModelProvider, ModelResponse, ModelStream,
+    ProviderMetadata, ReasoningPart, StreamPart, ToolCallPart, ToolChoice, Usage,
+};
+use ai_error::{ensure_success, AiError, Result};
+use ai_stream::sse::{self, SseEvent};
+use ai_stream::{decode_events, Emitter, EventDecoder};
+
+/// `max_tokens` is mandatory for the Messages API; used when the call does
+/// not set `max_output_tokens`.
+const DEFAULT_MAX_TOKENS: u32 = 4096;
+
+/// An Anthropic model such as `claude-sonnet-4-20250514`.
+///
+/// Extended thinking is enabled through the `"anthropic"` provider options:
+/// `{ "thinking": { "type": "enabled", "budgetTokens": 2048 } }`. The budget
+/// is added to `max_output_tokens`, and thinking blocks are returned as
+/// reasoning parts whose `"anthropic"` metadata carries the `signature` (or
+/// `redactedData`) needed to send them back in a later turn.
+#[derive(Debug, Clone)]
+pub struct AnthropicMessagesModel {
+    provider: AnthropicProvider,
+    model_id: String,
+}
+
+impl AnthropicProvider {
+    /// Creates a Messages API model with the given identifier.
+    pub fn messages_model(&self, model_id: impl Into<String>) -> AnthropicMessagesModel {
+        AnthropicMessagesModel {
+            provider: self.clone(),
+            model_id: model_id.into(),
+        }
+    }
+
+    /// Shorthand for [`messages_model`](Self::messages_model).
+    pub fn model(&self, model_id: impl Into<String>) -> AnthropicMessagesModel {
+        self.messages_model(model_id)
+    }
+}
+
+/// Typed view of the `"anthropic"` provider options.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AnthropicOptions {
+    thinking: Option<ThinkingOptions>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ThinkingOptions {
+    #[serde(rename = "type")]
+    kind: String,
+    budget_tokens: Option<u32>,
+}
+
+impl AnthropicMessagesModel {
+    /// Claude 3.7 and the Claude 4 family support extended thinking.
+    fn supports_thinking(&self) -> bool {
+        ["claude-3-7", "claude-sonnet-4", "claude-opus-4"]
+            .iter()
+            .any(|prefix| self.model_id.starts_with(prefix))
+    }
+
+    /// Builds the request body and the warnings for settings it drops.
+    fn request_body(
+        &self,
+        options: &CallOptions,
+        stream: bool,
+    ) -> Result<(Value, Vec<CallWarning>)> {
+        let settings = &options.settings;
+        let provider_options: AnthropicOptions = options.provider_options(self.provider.name())?;
+        let thinking_budget = match provider_options.thinking {
+            Some(thinking) if thinking.kind == "enabled" => Some(
+                thinking
+                    .budget_tokens
+                    .ok_or_else(|| AiError::Validation("thinking requires budgetTokens".into()))?,
+            ),
+            _ => None,
+        };
+
+        // Sampling settings are rejected while thinking is enabled.
+        let supported: &[&str] = if thinking_budget.is_some() {
+            &["max_output_tokens", "stop_sequences"]
+        } else {
+            &[
+                "temperature",
+                "top_p",
+                "top_k",
+                "max_output_tokens",
+                "stop_sequences",
+            ]
+        };
+        let warnings = settings.unsupported_warnings(supported);
+
+        let (system, messages) = convert_messages(&options.messages)?;
+        let max_tokens = settings.max_output_tokens.unwrap_or(DEFAULT_MAX_TOKENS);
+        let mut body = json!({
+            "model": self.model_id,
+            "max_tokens": max_tokens + thinking_budget.unwrap_or(0),
+            "messages": messages,
+        });
+        if !system.is_empty() {
+            body["system"] = json!(system);
+        }
+        if let Some(budget) = thinking_budget {
+            body["thinking"] = json!({ "type": "enabled", "budget_tokens": budget });
+        } else {
+            body["temperature"] = json!(settings.temperature);
+            body["top_p"] = json!(settings.top_p);
+            body["top_k"] = json!(settings.top_k);
+        }
+        if !settings.stop_sequences.is_empty() {
+            body["stop_sequences"] = json!(settings.stop_sequences);
+        }
+
+        if !options.tools.is_empty() {
+            let tools: Vec<Value> = options
+                .tools
+                .iter()
+                .map(|tool| {
+                    let mut definition = json!({
+                        "name": tool.name,
+                        "input_schema": tool.input_schema,
+                    });
+                    if let Some(description) = &tool.description {
+                        definition["description"] = json!(description);
+                    }
+                    with_cache_control(definition, tool.cache_control)
+                })
+                .collect();
+            body["tools"] = json!(tools);
+        }
+        if let Some(choice) = &options.tool_choice {
+            body["tool_choice"] = match choice {
+                ToolChoice::Auto => json!({ "type": "auto" }),
+                ToolChoice::None => json!({ "type": "none" }),
+                ToolChoice::Required => json!({ "type": "any" }),
+                ToolChoice::Tool { tool_name } => json!({ "type": "tool", "name": tool_name }),
+            };
+        }
+        if stream {
+            body["stream"] = json!(true);
+        }
+
+        // The Messages API rejects `null` for optional fields, so drop the
+        // settings the caller left unset.
+        if let Value::Object(fields) = &mut body {
+            fields.retain(|_, value| !value.is_null());
+        }
+        Ok((body, warnings))
+    }
+
+    async fn send(&self, options: &CallOptions, body: &Value) -> Result<reqwest::Response> {
+        let response = self
+            .provider
+            .post("/messages", &options.headers)
+            .json(body)
+            .send()
+            .await?;
+        ensure_success(self.provider.name(), response, map_error).await
+    }
+}
+
+/// Returns the `"anthropic"` metadata entry of a part, if any.
+fn anthropic_metadata(metadata: Option<&ProviderMetadata>) -> Option<&Value> {
+    metadata?.get("anthropic")
+}
+
+fn metadata(fields: Value) -> ProviderMetadata {
+    HashMap::from([("anthropic".to_string(), fields)])
+}
+
+/// Converts messages into the `system` blocks and the alternating
+/// user/assistant turns the Messages API requires.
+///
+/// System messages are hoisted into `system`, tool results become
+/// `tool_result` blocks of a user turn, and consecutive turns with the same
+/// role are merged.
+fn convert_messages(messages: &[Message]) -> Result<(Vec<Value>, Vec<Value>)> {
+    let mut system = Vec::new();
+    let mut turns: Vec<(&'static str, Vec<Value>)> = Vec::new();
+
+    for message in messages {
+        let (role, blocks) = match message.role {
+            MessageRole::System => {
+                system.extend(message.parts.iter().filter_map(|part| {
+                    let text = part.as_text()?;
+                    let block = json!({ "type": "text", "text": text });
+                    Some(with_cache_control(block, part.cache_control()))
+                }));
+                continue;
+            }
+            MessageRole::User => (
+                "user",
+                message
+                    .parts
+                    .iter()
+                    .map(convert_user_part)
+                    .collect::<Result<Vec<_>>>()?,
+            ),
+            MessageRole::Assistant => (
+                "assistant",
+                message
+                    .parts
+                    .iter()
+                    .filter_map(convert_assistant_part)
+                    .collect(),
+            ),
+            MessageRole::Tool => (
+                "user",
+                message
+                    .parts
+                    .iter()
+                    .filter_map(|part| match part {
+                        MessagePart::ToolResult(result) => {
+                            let content = match &result.output {
+                                Value::String(text) => text.clone(),
+                                output => output.to_string(),
+                            };
+                            let block = json!({
+                                "type": "tool_result",
+                                "tool_use_id": result.tool_call_id,
+                                "content": content,
+                                "is_error": result.is_error,
+                            });
+                            Some(with_cache_control(block, result.cache_control))
+                        }
+                        _ => None,
+                    })
+                    .collect(),
+            ),
+        };
+
+        match turns.last_mut() {
+            Some((last_role, last_blocks)) if *last_role == role => last_blocks.extend(blocks),
+            _ => turns.push((role, blocks)),
+        }
+    }
+
+    let messages = turns
+        .into_iter()
+        .map(|(role, content)| json!({ "role": role, "content": content }))
+        .collect();
+    Ok((system, messages))
+}
+
+/// Converts inline data or a URL into an Anthropic `source` object.
+fn source(data: &DataContent, media_type: &str) -> Value {
+    match data {
+        DataContent::Url(url) => json!({ "type": "url", "url": url }),
+        _ => json!({
+            "type": "base64",
+            "media_type": media_type,
+            "data": data.to_base64().unwrap_or_default(),
+        }),
+    }
+}
+
+/// Adds `cache_control` to a content block or tool if a hint is set.
+fn with_cache_control(mut block: Value, cache_control: Option<CacheControl>) -> Value {
+    if let Some(cache_control) = cache_control {
+        block["cache_control"] = match cache_control {
+            CacheControl::Ephemeral => json!({ "type": "ephemeral" }),
+            CacheControl::OneHour => json!({ "type": "ephemeral", "ttl": "1h" }),
+        };
+    }
+    block
+}
+
+fn convert_user_part(part: &MessagePart) -> Result<Value> {
+    let block = match part {
+        MessagePart::Text(text) => json!({ "type": "text", "text": text.text }),
+        MessagePart::Image(image) => {
+            let media_type = image.media_type.as_deref().unwrap_or("image/jpeg");
+            json!({ "type": "image", "source": source(&image.image, media_type) })
+        }
+        MessagePart::File(file) if file.media_type == "application/pdf" => json!({
+            "type": "document",
+            "source": source(&file.data, &file.media_type),
+        }),
+        MessagePart::File(file) => {
+            return Err(AiError::Validation(format!(
+                "unsupported file media type '{}'",
+                file.media_type
+            )))
+        }
+        _ => {
+            return Err(AiError::Validation(
+                "user messages may only contain text, image and file parts".into(),
+            ))
+        }
+    };
+    Ok(with_cache_control(block, part.cache_control()))
+}
+
+/// Converts an assistant part, dropping reasoning that cannot be sent back
+/// because it lacks a signature.
+fn convert_assistant_part(part: &MessagePart) -> Option<Value> {
+    match part {
+        MessagePart::Text(text) if !text.text.is_empty() => Some(with_cache_control(
+            json!({ "type": "text", "text": text.text }),
+            text.cache_control,
+        )),
+        MessagePart::ToolCall(call) => Some(json!({
+            "type": "tool_use",
+            "id": call.tool_call_id,
+            "name": call.tool_name,
+            "input": call.input,
+        })),
+        MessagePart::Reasoning(reasoning) => {
+            let fields = anthropic_metadata(reasoning.provider_metadata.as_ref())?;
+            if let Some(data) = fields.get("redactedData") {
+                return Some(json!({ "type": "redacted_thinking", "data": data }));
+            }
+            Some(json!({
+                "type": "thinking",
+                "thinking": reasoning.text,
+                "signature": fields.get("signature")?,
+            }))
+        }
+        _ => None,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct MessagesResponse {
+    content: Vec<ContentBlock>,
+    #[serde(default)]
+    stop_reason: Option<String>,
+    #[serde(default)]
+    usage: AnthropicUsage,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ContentBlock {
+    Text {
+        text: String,
+    },
+    Thinking {
+        thinking: String,
+        #[serde(default)]
+        signature: String,
+    },
+    RedactedThinking {
+        data: String,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        #[serde(default)]
+        input: Value,
+    },
+    /// Server tool blocks and future block types.
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+struct AnthropicUsage {
+    #[serde(default)]
+    input_tokens: u64,
+    #[serde(default)]
+    output_tokens: u64,
+    #[serde(default)]
+    cache_creation_input_tokens: u64,
+    #[serde(default)]
+    cache_read_input_tokens: u64,
+}
+
+impl From<AnthropicUsage> for Usage {
+    /// Anthropic reports cached tokens separately from `input_tokens`; they
+    /// are folded into the total here.
+    fn from(usage: AnthropicUsage) -> Self {
+        Usage {
+            input_tokens: usage.input_tokens
+                + usage.cache_creation_input_tokens
+                + usage.cache_read_input_tokens,
+            output_tokens: usage.output_tokens,
+            cached_input_tokens: usage.cache_read_input_tokens,
+            cache_creation_input_tokens: usage.cache_creation_input_tokens,
+            reasoning_tokens: 0,
+        }
+    }
+}
+
+fn map_finish_reason(reason: Option<&str>) -> FinishReason {
+    match reason {
+        Some("end_turn" | "stop_sequence") => FinishReason::Stop,
+        Some("max_tokens") => FinishReason::Length,
+        Some("tool_use") => FinishReason::ToolCalls,
+        Some("refusal") => FinishReason::ContentFilter,
+        Some(_) => FinishReason::Other,
+        None => FinishReason::Unknown,
+    }
+}
+
+#[async_trait]
+impl LanguageModel for AnthropicMessagesModel {
+    fn provider(&self) -> &str {
+        self.provider.name()
+    }
+
+    fn model_id(&self) -> &str {
+        &self.model_id
+    }
+
+    fn capabilities(&self) -> ModelCapabilities {
+        ModelCapabilities {
+            tool_calling: true,
+            structured_outputs: false,
+            image_input: true,
+            file_input: true,
+            reasoning: self.supports_thinking(),
+            streaming: true,
+            max_context_tokens: Some(200_000),
+        }
+    }
+
+    async fn do_generate(&self, options: CallOptions) -> Result<ModelResponse> {
+        let (body, warnings) = self.request_body(&options, false)?;
+        let response: MessagesResponse = self.send(&options, &body).await?.json().await?;
+
+        let content = response
+            .content
+            .into_iter()
+            .filter_map(|block| match block {
+                ContentBlock::Text { text } => Some(MessagePart::text(text)),
+                ContentBlock::Thinking {
+                    thinking,
+                    signature,
+                } => Some(MessagePart::Reasoning(ReasoningPart {
+                    text: thinking,
+                    provider_metadata: Some(metadata(json!({ "signature": signature }))),
+                })),
+                ContentBlock::RedactedThinking { data } => {
+                    Some(MessagePart::Reasoning(ReasoningPart {
+                        text: String::new(),
+                        provider_metadata: Some(metadata(json!({ "redactedData": data }))),
+                    }))
+                }
+                ContentBlock::ToolUse { id, name, input } => {
+                    Some(MessagePart::ToolCall(ToolCallPart {
+                        tool_call_id: id,
+                        tool_name: name,
+                        input,
+                        provider_metadata: None,
+                    }))
+                }
+                ContentBlock::Other => None,
+            })
+            .collect();
+
+        Ok(ModelResponse {
+            content,
+            finish_reason: map_finish_reason(response.stop_reason.as_deref()),
+            usage: response.usage.into(),
+            sources: Vec::new(),
+            warnings,
+            provider_metadata: None,
+        })
+    }
+
+    async fn do_stream(&self, options: CallOptions) -> Result<ModelStream> {
+        let (body, warnings) = self.request_body(&options, true)?;
+        let response = self.send(&options, &body).await?;
+
+        let events = sse::decode(response.bytes_stream()).boxed();
+        let state = EventState::new(self.provider.name());
+        Ok(ModelStream {
+            stream: decode_events(events, state).boxed(),
+            warnings,
+        })
+    }
+}
+
+/// A streaming event, identified by its `type` field.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum StreamEvent {
+    MessageStart {
+        message: StartMessage,
+    },
+    ContentBlockStart {
+        index: usize,
+        content_block: ContentBlock,
+    },
+    ContentBlockDelta {
+        index: usize,
+        delta: BlockDelta,
+    },
+    ContentBlockStop {
+        index: usize,
+    },
+    MessageDelta {
+        delta: MessageDeltaBody,
+        #[serde(default)]
+        usage: Option<DeltaUsage>,
+    },
+    MessageStop,
+    Error {
+        #[serde(flatten)]
+        error: ErrorEnvelope,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+struct StartMessage {
+    #[serde(default)]
+    usage: AnthropicUsage,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum BlockDelta {
+    TextDelta {
+        text: String,
+    },
+    ThinkingDelta {
+        thinking: String,
+    },
+    InputJsonDelta {
+        partial_json: String,
+    },
+    SignatureDelta {
+        signature: String,
+    },
+    /// Citation deltas and future delta types.
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessageDeltaBody {
+    #[serde(default)]
+    stop_reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeltaUsage {
+    #[serde(default)]
+    output_tokens: u64,
+}
+
+type Parts = Emitter<StreamPart, AiError>;
+
+/// Turns Messages API events into [`StreamPart`]s.
+///
+/// Deltas refer to content blocks by index; `tool_calls` maps the index of
+/// each `tool_use` block to its id, and `reasoning` the index of each
+/// thinking block to the metadata emitted when it stops.
+struct EventState {
+    provider: String,
+    tool_calls: HashMap<usize, String>,
+    reasoning: HashMap<usize, ReasoningBlock>,
+    usage: AnthropicUsage,
+    finish_reason: FinishReason,
+}
+
+impl EventState {
+    fn new(provider: &str) -> Self {
+        Self {
+            provider: provider.to_string(),
+            tool_calls: HashMap::new(),
+            reasoning: HashMap::new(),
+            usage: AnthropicUsage::default(),
+            finish_reason: FinishReason::Unknown,
+        }
+    }
+}
+
+impl EventDecoder for EventState {
+    type Event = SseEvent;
+    type Item = StreamPart;
+    type Error = AiError;
+
+    fn decode(&mut self, event: SseEvent, out: &mut Parts) {
+        let event: StreamEvent = match serde_json::from_str(&event.data) {
+            Ok(event) => event,
+            Err(error) => return out.fail(error.into()),
+        };
+
+        match event {
+            StreamEvent::MessageStart { message } => self.usage = message.usage,
+            StreamEvent::ContentBlockStart {
+                index,
+                content_block: ContentBlock::ToolUse { id, name, .. },
+            } => {
+                self.tool_calls.insert(index, id.clone());
+                out.push(StreamPart::ToolCallStart {
+                    id,
+                    tool_name: name,
+                });
+            }
+            StreamEvent::ContentBlockStart {
+                index,
+                content_block: ContentBlock::Thinking { signature, .. },
+            } => {
+                self.reasoning
+                    .insert(index, ReasoningBlock::Thinking { signature });
+            }
+            StreamEvent::ContentBlockStart {
+                index,
+                content_block: ContentBlock::RedactedThinking { data },
+            } => {
+                self.reasoning
+                    .insert(index, ReasoningBlock::Redacted { data });
+            }
+            StreamEvent::ContentBlockDelta { index, delta } => match delta {
+                BlockDelta::TextDelta { text } => out.push(StreamPart::TextDelta { text }),
+                BlockDelta::ThinkingDelta { thinking } => {
+                    out.push(StreamPart::ReasoningDelta { text: thinking });
+                }
+                BlockDelta::InputJsonDelta { partial_json } if !partial_json.is_empty() => {
+                    if let Some(id) = self.tool_calls.get(&index).cloned() {
+                        out.push(StreamPart::ToolCallDelta {
+                            id,
+                            input_delta: partial_json,
+                        });
+                    }
+                }
+                BlockDelta::SignatureDelta { signature } => {
+                    if let Some(ReasoningBlock::Thinking { signature: current }) =
+                        self.reasoning.get_mut(&index)
+                    {
+                        current.push_str(&signature);
+                    }
+                }
+                BlockDelta::InputJsonDelta { .. } | BlockDelta::Other => {}
+            },
+            StreamEvent::ContentBlockStop { index } => {
+                if let Some(id) = self.tool_calls.remove(&index) {
+                    out.push(StreamPart::ToolCallEnd { id });
+                }
+                if let Some(block) = self.reasoning.remove(&index) {
+                    out.push(StreamPart::ReasoningEnd {
+                        provider_metadata: Some(block.into_metadata()),
+                    });
+                }
+            }
+            StreamEvent::MessageDelta { delta, usage } => {
+                if let Some(reason) = delta.stop_reason {
+                    self.finish_reason = map_finish_reason(Some(&reason));
+                }
+                if let Some(usage) = usage {
+                    self.usage.output_tokens = usage.output_tokens;
+                }
+            }
+            StreamEvent::MessageStop => {
+                out.push(StreamPart::Finish {
+                    finish_reason: self.finish_reason,
+                    usage: self.usage.into(),
+                });
+                out.close();
+            }
+            StreamEvent::Error { error } => out.fail(error.into_error(&self.provider)),
+            StreamEvent::ContentBlockStart { .. } | StreamEvent::Other => {}
+        }
+    }
+
+    fn end(&mut self, out: &mut Parts) {
+        out.fail(AiError::Stream(
+            "message stream ended before message_stop".into(),
+        ));
+    }
+}
+
+/// A thinking block whose metadata is emitted at `content_block_stop`.
+enum ReasoningBlock {
+    /// Signature accumulated from `signature_delta` events.
+    Thinking { signature: String },
+    /// Encrypted reasoning, which arrives whole with the block start.
+    Redacted { data: String },
+}
+
+impl ReasoningBlock {
+    fn into_metadata(self) -> ProviderMetadata {
+        match self {
+            ReasoningBlock::Thinking { signature } => metadata(json!({ "signature": signature })),
+            ReasoningBlock::Redacted { data } => metadata(json!({ "redactedData": data })),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ai_core::{
+        generate_text, stream_text, GenerateTextRequest, StreamTextRequest, ToolDefinition,
+        ToolResultPart,
+    };
+    use futures::stream;
+    use mockito::Matcher;
+
+    const TOOL_USE_RESPONSE: &str = include_str!("../fixtures/messages_tool_use.json");
+    const THINKING_STREAM: &str = include_str!("../fixtures/stream_thinking_tool_use.sse");
+
+    fn weather_tool() -> ToolDefinition {
+        ToolDefinition::new(
+            "weather",
+            "Current weather for a city",
+            json!({ "type": "object", "properties": { "city": { "type": "string" } } }),
+        )
+    }
+
+    #[test]
+    fn test_convert_messages_hoists_system_and_merges_turns() {
+        let messages = vec![
+            Message::system("Be brief."),
+            Message::user("Weather in Paris?"),
+            Message::new(
+                MessageRole::Assistant,
+                vec![
+                    MessagePart::reasoning("unsigned, dropped"),
+                    MessagePart::ToolCall(ToolCallPart {
+                        tool_call_id: "toolu_1".into(),
+                        tool_name: "weather".into(),
+                        input: json!({ "city": "Paris" }),
+                        provider_metadata: None,
+                    }),
+                ],
+            ),
+            Message::tool([ToolResultPart {
+                tool_call_id: "toolu_1".into(),
+                tool_name: "weather".into(),
+                output: json!({ "temperature": 21 }),
+                is_error: false,
+                cache_control: Some(CacheControl::Ephemeral),
+                provider_metadata: None,
+            }]),
+            Message::user("And tomorrow?"),
+        ];
+
+        let (system, turns) = convert_messages(&messages).unwrap();
+        assert_eq!(system, vec![json!({ "type": "text", "text": "Be brief." })]);
+        assert_eq!(turns.len(), 3);
+        assert_eq!(turns[1]["content"].as_array().unwrap().len(), 1);
+        assert_eq!(turns[2]["role"], "user");
+        assert_eq!(turns[2]["content"][0]["type"], "tool_result");
+        assert_eq!(turns[2]["content"][0]["content"], "{\"temperature\":21}");
+        assert_eq!(
+            turns[2]["content"][0]["cache_control"],
+            json!({ "type": "ephemeral" })
+        );
+        assert_eq!(turns[2]["content"][1]["text"], "And tomorrow?");
+    }
+
+    #[tokio::test]
+    async fn test_cache_control_and_cache_usage() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/messages")
+            .match_body(Matcher::PartialJson(json!({
+                "system": [{
+                    "type": "text",
+                    "text": "Long style guide.",
+                    "cache_control": { "type": "ephemeral", "ttl": "1h" },
+                }],
+                "tools": [{ "name": "weather", "cache_control": { "type": "ephemeral" } }],
+                "messages": [{ "role": "user", "content": [{ "text": "Hi", }] }],
+            })))
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"content":[{"type":"text","text":"Hello"}],"stop_reason":"end_turn",
+                "usage":{"input_tokens":5,"cache_creation_input_tokens":1800,"cache_read_input_tokens":0,"output_tokens":2}}"#,
+            )
+            .create_async()
+            .await;
+
+        let model = AnthropicProvider::new("test-key")
+            .with_base_url(server.url())
+            .messages_model("claude-sonnet-4-20250514");
+        let messages = vec![
+            Message::system("Long style guide.").with_cache_control(CacheControl::OneHour),
+            Message::user("Hi"),
+        ];
+        let response = generate_text(
+            GenerateTextRequest::from_messages(model, messages)
+                .tool(weather_tool().with_cache_control(CacheControl::Ephemeral)),
+        )
+        .await
+        .unwrap();
+
+        mock.assert_async().await;
+        assert!(response.warnings.is_empty());
+        assert_eq!(response.usage.input_tokens, 1805);
+        assert_eq!(response.usage.cache_creation_input_tokens, 1800);
+        assert_eq!(response.usage.cached_input_tokens, 0);
+    }
+
+    #[tokio::test]
+    async fn test_generate_with_thinking_and_tool_use() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/messages")
+            .match_header("x-api-key", "test-key")
+            .match_header("anthropic-version", "2023-06-01")
+            .match_header("anthropic-beta", "interleaved-thinking-2025-05-14")
+            .match_body(Matcher::PartialJson(json!({
+                "model": "claude-sonnet-4-20250514",
+                "max_tokens": 3024,
+                "system": [{ "type": "text", "text": "Be brief." }],
+                "thinking": { "type": "enabled", "budget_tokens": 2000 },
+                "tools": [{ "name": "weather" }],
+                "tool_choice": { "type": "any" },
+            })))
+            .with_header("content-type", "application/json")
+            .with_body(TOOL_USE_RESPONSE)
+            .create_async()
+            .await;
+
+        let model = AnthropicProvider::new("test-key")
+            .with_base_url(server.url())
+            .with_beta("interleaved-thinking-2025-05-14")
+            .messages_model("claude-sonnet-4-20250514");
+        let response = generate_text(
+            GenerateTextRequest::new(model, "Weather in Paris?")
+                .system("Be brief.")
+                .max_output_tokens(1024)
+                .temperature(0.5)
+                .tool(weather_tool())
+                .tool_choice(ToolChoice::Required)
+                .provider_options(
+                    "anthropic",
+                    json!({ "thinking": { "type": "enabled", "budgetTokens": 2000 } }),
+                ),
+        )
+        .await
+        .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(
+            response.warnings,
+            vec![CallWarning::unsupported_setting("temperature")]
+        );
+        assert_eq!(response.finish_reason, FinishReason::ToolCalls);
+        assert_eq!(response.text, "I'll check the current weather in Paris.");
+        assert_eq!(response.usage, Usage::new(412, 96));
+
+        let MessagePart::Reasoning(reasoning) = &response.content[0] else {
+            panic!("expected thinking first");
+        };
+        assert!(reasoning.text.starts_with("The user wants the weather"));
+        let calls = response.tool_calls();
+        assert_eq!(
+            calls[0].input,
+            json!({ "city": "Paris", "unit": "celsius" })
+        );
+
+        // Signed thinking is sent back verbatim on the next turn.
+        let (_, turns) = convert_messages(&[response.to_message()]).unwrap();
+        assert_eq!(turns[0]["content"][0]["type"], "thinking");
+        assert_eq!(
+            turns[0]["content"][0]["signature"],
+            "EqQBCgIYAhIM1gbcDa9GJwZA2b3hGgxBdjrkzLoky3dl1pkiMOYds"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stream_thinking_and_tool_use() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/messages")
+            .match_body(Matcher::PartialJson(json!({ "stream": true })))
+            .with_header("content-type", "text/event-stream")
+            .with_body(THINKING_STREAM)
+            .create_async()
+            .await;
+
+        let model = AnthropicProvider::new("test-key")
+            .with_base_url(server.url())
+            .messages_model("claude-sonnet-4-20250514");
+        let handle =
+            stream_text(StreamTextRequest::new(model, "Weather in Paris?").tool(weather_tool()))
+                .await
+                .unwrap();
+        let result = handle.result().await.unwrap();
+
+        assert_eq!(result.reasoning, "Need the weather tool for Paris.");
+        assert_eq!(result.text, "Checking now.");
+        assert_eq!(result.tool_calls.len(), 1);
+        assert_eq!(
+            result.tool_calls[0].tool_call_id,
+            "toolu_01T1x1fJ34qAmk2tNTrN7Up6"
+        );
+        assert_eq!(result.tool_calls[0].input, json!({ "city": "Paris" }));
+        assert_eq!(result.finish_reason, FinishReason::ToolCalls);
+        assert_eq!(result.usage, Usage::new(472, 89));
+
+        // The streamed signature survives the round trip as well.
+        let (_, turns) = convert_messages(&[result.to_message()]).unwrap();
+        assert_eq!(
+            turns[0]["content"][0],
+            json!({
+                "type": "thinking",
+                "thinking": "Need the weather tool for Paris.",
+                "signature": "EqQBCgIYAhIM1gbcDa9GJwZA2b3hGgxB",
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stream_redacted_thinking_closes_with_data() {
+        let events = [
+            r#"{"type":"content_block_start","index":0,"content_block":{"type":"redacted_thinking","data":"EmwKAhgB"}}"#,
+            r#"{"type":"content_block_stop","index":0}"#,
+        ]
+        .map(|data| {
+            Ok::<_, AiError>(SseEvent {
+                event: None,
+                data: data.to_string(),
+                id: None,
+            })
+        });
+        let mut parts = decode_events(stream::iter(events), EventState::new("anthropic")).boxed();
+
+        let Some(Ok(StreamPart::ReasoningEnd { provider_metadata })) = parts.next().await else {
+            panic!("expected the redacted block to close with its data");
+        };
+        let metadata = provider_metadata.unwrap();
+        assert_eq!(metadata["anthropic"], json!({ "redactedData": "EmwKAhgB" }));
+    }
+}
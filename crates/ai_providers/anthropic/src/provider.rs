@@ -0,0 +1,125 @@
+//! Provider configuration shared by all Anthropic models.
+
+use std::collections::HashMap;
+
+use ai_core::{LanguageModel, ModelProvider};
+use ai_error::{AiError, Result};
+
+/// Default endpoint of the Anthropic API.
+const DEFAULT_BASE_URL: &str = "https://api.anthropic.com/v1";
+
+/// Value of the `anthropic-version` header.
+const API_VERSION: &str = "2023-06-01";
+
+/// Entry point for Anthropic models.
+///
+/// Holds the credentials, endpoint and HTTP client used by every model
+/// created from it.
+#[derive(Debug, Clone)]
+pub struct AnthropicProvider {
+    api_key: String,
+    base_url: String,
+    headers: HashMap<String, String>,
+    betas: Vec<String>,
+    client: reqwest::Client,
+}
+
+impl AnthropicProvider {
+    /// Creates a provider that authenticates with `api_key`.
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            api_key: api_key.into(),
+            base_url: DEFAULT_BASE_URL.to_string(),
+            headers: HashMap::new(),
+            betas: Vec::new(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Creates a provider from `ANTHROPIC_API_KEY`, and optionally
+    /// `ANTHROPIC_BASE_URL`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AiError::Config`] if `ANTHROPIC_API_KEY` is not set.
+    pub fn from_env() -> Result<Self> {
+        let api_key = std::env::var("ANTHROPIC_API_KEY")
+            .map_err(|_| AiError::Config("ANTHROPIC_API_KEY is not set".into()))?;
+
+        let mut provider = Self::new(api_key);
+        if let Ok(base_url) = std::env::var("ANTHROPIC_BASE_URL") {
+            provider = provider.with_base_url(base_url);
+        }
+        Ok(provider)
+    }
+
+    /// Sends requests to `base_url` instead of the default endpoint.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Adds a header sent with every request.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.insert(name.into(), value.into());
+        self
+    }
+
+    /// Opts into a beta feature (e.g. `"interleaved-thinking-2025-05-14"`),
+    /// sent in the `anthropic-beta` header.
+    pub fn with_beta(mut self, beta: impl Into<String>) -> Self {
+        self.betas.push(beta.into());
+        self
+    }
+
+    /// Replaces the HTTP client, e.g. to allow longer timeouts for extended
+    /// thinking.
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Builds an authenticated `POST` request to `path`, applying provider
+    /// headers followed by the per-call `headers`.
+    pub(crate) fn post(
+        &self,
+        path: &str,
+        headers: &HashMap<String, String>,
+    ) -> reqwest::RequestBuilder {
+        let mut request = self
+            .client
+            .post(format!("{}{}", self.base_url, path))
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", API_VERSION);
+
+        if !self.betas.is_empty() {
+            request = request.header("anthropic-beta", self.betas.join(","));
+        }
+        for (name, value) in self.headers.iter().chain(headers) {
+            request = request.header(name, value);
+        }
+        request
+    }
+}
+
+impl ModelProvider for AnthropicProvider {
+    fn name(&self) -> &str {
+        "anthropic"
+    }
+
+    fn language_model(&self, model_id: &str) -> Result<Box<dyn LanguageModel>> {
+        Ok(Box::new(self.messages_model(model_id)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base_url_is_normalized() {
+        let provider = AnthropicProvider::new("key").with_base_url("http://localhost:1234/v1/");
+        assert_eq!(provider.base_url, "http://localhost:1234/v1");
+        assert_eq!(AnthropicProvider::new("key").base_url, DEFAULT_BASE_URL);
+    }
+}
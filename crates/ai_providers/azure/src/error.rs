@@ -0,0 +1,99 @@
+//! Mapping of Azure content-filter errors onto [`AiError`].
+//!
+//! Everything else Azure returns uses the OpenAI error format and is left to
+//! the OpenAI mapping.
+
+use std::collections::BTreeMap;
+
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+use ai_error::AiError;
+
+#[derive(Debug, Deserialize)]
+struct ErrorEnvelope {
+    error: ErrorBody,
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorBody {
+    message: String,
+    #[serde(default)]
+    code: Option<String>,
+    #[serde(default)]
+    innererror: Option<InnerError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct InnerError {
+    #[serde(default)]
+    code: Option<String>,
+    #[serde(default)]
+    content_filter_result: BTreeMap<String, FilterResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FilterResult {
+    #[serde(default)]
+    filtered: bool,
+}
+
+/// Maps a content-filter rejection to [`AiError::Provider`] whose code names
+/// the filtered categories (e.g. `"hate"` or `"jailbreak,violence"`).
+///
+/// Returns `None` for any other error.
+pub(crate) fn map_error(provider: &str, _status: StatusCode, body: &str) -> Option<AiError> {
+    let error = serde_json::from_str::<ErrorEnvelope>(body).ok()?.error;
+    let inner_code = error
+        .innererror
+        .as_ref()
+        .and_then(|inner| inner.code.as_deref());
+    let is_filter = error.code.as_deref() == Some("content_filter")
+        || inner_code == Some("ResponsibleAIPolicyViolation");
+    if !is_filter {
+        return None;
+    }
+
+    let categories: Vec<&str> = error
+        .innererror
+        .iter()
+        .flat_map(|inner| &inner.content_filter_result)
+        .filter(|(_, result)| result.filtered)
+        .map(|(category, _)| category.as_str())
+        .collect();
+    let code = if categories.is_empty() {
+        "content_filter".to_string()
+    } else {
+        categories.join(",")
+    };
+
+    Some(AiError::Provider {
+        provider: provider.to_string(),
+        message: error.message,
+        code: Some(code),
+        details: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_maps_content_filter_category() {
+        let body = r#"{"error":{"message":"The prompt was filtered","type":null,"param":"prompt","code":"content_filter","status":400,
+            "innererror":{"code":"ResponsibleAIPolicyViolation","content_filter_result":{
+                "hate":{"filtered":false,"severity":"safe"},
+                "jailbreak":{"filtered":true,"detected":true},
+                "violence":{"filtered":false,"severity":"low"}}}}}"#;
+        let error = map_error("azure", StatusCode::BAD_REQUEST, body).unwrap();
+        assert!(matches!(
+            error,
+            AiError::Provider { code: Some(code), message, .. }
+                if code == "jailbreak" && message == "The prompt was filtered"
+        ));
+
+        let other = r#"{"error":{"code":"DeploymentNotFound","message":"The API deployment for this resource does not exist."}}"#;
+        assert!(map_error("azure", StatusCode::NOT_FOUND, other).is_none());
+    }
+}
@@ -0,0 +1,29 @@
+//! Azure OpenAI provider for the AI SDK.
+//!
+//! Azure serves OpenAI models from per-resource endpoints, addressed by
+//! deployment name rather than model id. Requests and responses use the
+//! OpenAI wire format, so the models are
+//! [`OpenAiChatModel`](ai_providers_openai::OpenAiChatModel)s.
+//!
+//! ```no_run
+//! use ai_core::{generate_text, GenerateTextRequest};
+//! use ai_providers_azure::AzureOpenAiProvider;
+//!
+//! # async fn run() -> ai_error::Result<()> {
+//! let provider = AzureOpenAiProvider::from_env()?;
+//! let response = generate_text(GenerateTextRequest::new(
+//!     provider.chat_model("my-gpt-4o-deployment"),
+//!     "Write a haiku about Rust.",
+//! ))
+//! .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs, rust_2018_idioms)]
+
+mod error;
+mod provider;
+
+pub use provider::AzureOpenAiProvider;
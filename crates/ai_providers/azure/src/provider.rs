@@ -0,0 +1,325 @@
+//! Provider configuration shared by all Azure OpenAI deployments.
+
+use std::collections::HashMap;
+
+use ai_core::{LanguageModel, ModelCapabilities, ModelProvider};
+use ai_error::{AiError, Result};
+use ai_providers_openai::{AuthStyle, OpenAiChatModel, OpenAiCompatibleProvider};
+
+use crate::error::map_error;
+
+/// `api-version` sent when none is configured (latest GA version).
+const DEFAULT_API_VERSION: &str = "2024-10-21";
+
+/// How requests authenticate.
+#[derive(Debug, Clone)]
+enum Credential {
+    /// Resource key, sent in the `api-key` header.
+    ApiKey(String),
+    /// Microsoft Entra ID access token, sent as a bearer token.
+    BearerToken(String),
+}
+
+/// Entry point for Azure OpenAI deployments.
+///
+/// Models are addressed by deployment name:
+/// `{base_url}/deployments/{deployment}/chat/completions?api-version=...`.
+/// Content-filter rejections are returned as [`AiError::Provider`] with the
+/// filtered categories as the code.
+#[derive(Debug, Clone)]
+pub struct AzureOpenAiProvider {
+    base_url: String,
+    api_version: String,
+    credential: Credential,
+    headers: HashMap<String, String>,
+    capabilities: Option<ModelCapabilities>,
+    deployment_capabilities: HashMap<String, ModelCapabilities>,
+    client: reqwest::Client,
+}
+
+impl AzureOpenAiProvider {
+    /// Creates a provider for the resource `resource_name`
+    /// (`https://{resource_name}.openai.azure.com`), authenticating with the
+    /// resource's API key.
+    pub fn new(resource_name: impl AsRef<str>, api_key: impl Into<String>) -> Self {
+        let base_url = format!("https://{}.openai.azure.com/openai", resource_name.as_ref());
+        Self::with_endpoint(base_url, api_key.into())
+    }
+
+    fn with_endpoint(base_url: String, api_key: String) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_version: DEFAULT_API_VERSION.to_string(),
+            credential: Credential::ApiKey(api_key),
+            headers: HashMap::new(),
+            capabilities: None,
+            deployment_capabilities: HashMap::new(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Creates a provider from `AZURE_OPENAI_API_KEY` and either
+    /// `AZURE_OPENAI_RESOURCE_NAME` or `AZURE_OPENAI_BASE_URL`, and
+    /// optionally `AZURE_OPENAI_API_VERSION`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AiError::Config`] if the key or both endpoint variables are
+    /// not set.
+    pub fn from_env() -> Result<Self> {
+        let api_key = std::env::var("AZURE_OPENAI_API_KEY")
+            .map_err(|_| AiError::Config("AZURE_OPENAI_API_KEY is not set".into()))?;
+
+        let mut provider = match (
+            std::env::var("AZURE_OPENAI_BASE_URL"),
+            std::env::var("AZURE_OPENAI_RESOURCE_NAME"),
+        ) {
+            (Ok(base_url), _) => Self::with_endpoint(base_url, api_key),
+            (_, Ok(resource_name)) => Self::new(resource_name, api_key),
+            _ => {
+                return Err(AiError::Config(
+                    "AZURE_OPENAI_RESOURCE_NAME or AZURE_OPENAI_BASE_URL must be set".into(),
+                ))
+            }
+        };
+        if let Ok(api_version) = std::env::var("AZURE_OPENAI_API_VERSION") {
+            provider = provider.with_api_version(api_version);
+        }
+        Ok(provider)
+    }
+
+    /// Uses `base_url` (ending in `/openai`) instead of the URL derived from
+    /// the resource name, e.g. for a custom domain or a mock server.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Sets the `api-version` query parameter.
+    pub fn with_api_version(mut self, api_version: impl Into<String>) -> Self {
+        self.api_version = api_version.into();
+        self
+    }
+
+    /// Authenticates with a Microsoft Entra ID access token instead of the
+    /// API key.
+    pub fn with_bearer_token(mut self, token: impl Into<String>) -> Self {
+        self.credential = Credential::BearerToken(token.into());
+        self
+    }
+
+    /// Adds a header sent with every request.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.insert(name.into(), value.into());
+        self
+    }
+
+    /// Reports `capabilities` for every deployment instead of deriving them
+    /// from the deployment name.
+    ///
+    /// Deployment names only identify the model when they follow the model
+    /// id (`o4-mini`, `gpt-4o-prod`). Set `reasoning` for reasoning models
+    /// deployed under other names, so requests use `max_completion_tokens`
+    /// and omit the sampling settings these models reject.
+    pub fn with_capabilities(mut self, capabilities: ModelCapabilities) -> Self {
+        self.capabilities = Some(capabilities);
+        self
+    }
+
+    /// Reports `capabilities` for `deployment` only, taking precedence over
+    /// [`with_capabilities`](Self::with_capabilities).
+    pub fn with_deployment_capabilities(
+        mut self,
+        deployment: impl Into<String>,
+        capabilities: ModelCapabilities,
+    ) -> Self {
+        self.deployment_capabilities
+            .insert(deployment.into(), capabilities);
+        self
+    }
+
+    /// Replaces the HTTP client, e.g. with one that presents a client
+    /// certificate to a private endpoint.
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Creates a chat model for the deployment `deployment`.
+    pub fn chat_model(&self, deployment: impl Into<String>) -> OpenAiChatModel {
+        let deployment = deployment.into();
+        let mut provider = OpenAiCompatibleProvider::new(
+            self.name(),
+            format!("{}/deployments/{}", self.base_url, deployment),
+        )
+        .with_query_param("api-version", &self.api_version)
+        .with_error_mapper(map_error)
+        .with_client(self.client.clone());
+
+        provider = match &self.credential {
+            Credential::ApiKey(key) => provider
+                .with_api_key(key)
+                .with_auth_style(AuthStyle::Header("api-key".into())),
+            Credential::BearerToken(token) => provider.with_api_key(token),
+        };
+        for (name, value) in &self.headers {
+            provider = provider.with_header(name, value);
+        }
+        let capabilities = self
+            .deployment_capabilities
+            .get(&deployment)
+            .copied()
+            .or(self.capabilities);
+        if let Some(capabilities) = capabilities {
+            provider = provider.with_capabilities(capabilities);
+        }
+        provider.chat_model(deployment)
+    }
+}
+
+impl ModelProvider for AzureOpenAiProvider {
+    fn name(&self) -> &str {
+        "azure"
+    }
+
+    fn language_model(&self, model_id: &str) -> Result<Box<dyn LanguageModel>> {
+        Ok(Box::new(self.chat_model(model_id)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ai_core::{generate_text, GenerateTextRequest};
+    use mockito::Matcher;
+
+    const COMPLETION: &str = r#"{"choices":[{"message":{"role":"assistant","content":"Hello"},"finish_reason":"stop"}],"usage":{"prompt_tokens":4,"completion_tokens":1}}"#;
+
+    #[test]
+    fn test_resource_url() {
+        let provider = AzureOpenAiProvider::new("contoso", "key");
+        assert_eq!(provider.base_url, "https://contoso.openai.azure.com/openai");
+        assert_eq!(provider.api_version, DEFAULT_API_VERSION);
+    }
+
+    #[tokio::test]
+    async fn test_calls_deployment_with_api_version() {
+        let mut server = mockito::Server::new_async().await;
+        let keyed = server
+            .mock("POST", "/openai/deployments/gpt-4o-prod/chat/completions")
+            .match_query(Matcher::UrlEncoded(
+                "api-version".into(),
+                "2025-01-01-preview".into(),
+            ))
+            .match_header("api-key", "secret")
+            .match_header("authorization", Matcher::Missing)
+            .with_header("content-type", "application/json")
+            .with_body(COMPLETION)
+            .create_async()
+            .await;
+        let bearer = server
+            .mock("POST", "/openai/deployments/gpt-4o-prod/chat/completions")
+            .match_query(Matcher::UrlEncoded(
+                "api-version".into(),
+                DEFAULT_API_VERSION.into(),
+            ))
+            .match_header("authorization", "Bearer entra-token")
+            .with_header("content-type", "application/json")
+            .with_body(COMPLETION)
+            .create_async()
+            .await;
+
+        let provider = AzureOpenAiProvider::new("contoso", "secret")
+            .with_base_url(format!("{}/openai/", server.url()))
+            .with_api_version("2025-01-01-preview");
+        let response = generate_text(GenerateTextRequest::new(
+            provider.chat_model("gpt-4o-prod"),
+            "hi",
+        ))
+        .await
+        .unwrap();
+        assert_eq!(response.text, "Hello");
+
+        let provider = AzureOpenAiProvider::new("contoso", "secret")
+            .with_base_url(format!("{}/openai", server.url()))
+            .with_bearer_token("entra-token");
+        let model = provider.chat_model("gpt-4o-prod");
+        assert_eq!(model.provider(), "azure");
+        generate_text(GenerateTextRequest::new(model, "hi"))
+            .await
+            .unwrap();
+
+        keyed.assert_async().await;
+        bearer.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_reasoning_deployment_is_shaped_by_capabilities() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock(
+                "POST",
+                "/openai/deployments/reasoning-prod/chat/completions",
+            )
+            .match_query(Matcher::Any)
+            .match_request(|request| {
+                let body: serde_json::Value =
+                    serde_json::from_slice(request.body().unwrap()).unwrap();
+                body["max_completion_tokens"] == 64
+                    && body.get("max_tokens").is_none()
+                    && body.get("temperature").is_none()
+            })
+            .with_header("content-type", "application/json")
+            .with_body(COMPLETION)
+            .create_async()
+            .await;
+
+        let reasoning = ModelCapabilities {
+            reasoning: true,
+            ..AzureOpenAiProvider::new("contoso", "secret")
+                .chat_model("gpt-4o")
+                .capabilities()
+        };
+        let provider = AzureOpenAiProvider::new("contoso", "secret")
+            .with_base_url(format!("{}/openai", server.url()))
+            .with_deployment_capabilities("reasoning-prod", reasoning);
+        assert!(!provider.chat_model("chat-prod").capabilities().reasoning);
+
+        let response = generate_text(
+            GenerateTextRequest::new(provider.chat_model("reasoning-prod"), "hi")
+                .temperature(0.2)
+                .max_output_tokens(64),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.text, "Hello");
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_content_filter_error_carries_category() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/openai/deployments/gpt-4o/chat/completions")
+            .match_query(Matcher::Any)
+            .with_status(400)
+            .with_body(r#"{"error":{"message":"The response was filtered","code":"content_filter","innererror":{"code":"ResponsibleAIPolicyViolation","content_filter_result":{"violence":{"filtered":true,"severity":"high"}}}}}"#)
+            .create_async()
+            .await;
+
+        let provider = AzureOpenAiProvider::new("contoso", "secret")
+            .with_base_url(format!("{}/openai", server.url()));
+        let error = generate_text(GenerateTextRequest::new(
+            provider.chat_model("gpt-4o"),
+            "hi",
+        ))
+        .await
+        .unwrap_err();
+
+        assert!(matches!(
+            error,
+            AiError::Provider { provider, code: Some(code), .. }
+                if provider == "azure" && code == "violence"
+        ));
+    }
+}
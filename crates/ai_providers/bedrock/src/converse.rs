@@ -0,0 +1,968 @@
+//! Text generation through the Bedrock Converse and ConverseStream APIs.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use futures::stream::StreamExt;
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::error::{map_error, map_exception, ErrorBody};
+use crate::eventstream::{self, EventMessage};
+use crate::provider::BedrockProvider;
+use crate::sigv4::uri_encode;
+use ai_core::{
+    CallOptions, CallWarning, DataContent, FinishReason, LanguageModel, Message, MessagePart,
+    MessageRole, ModelCapabilities, ModelProvider, ModelResponse, ModelStream, ProviderMetadata,
+    ReasoningPart, StreamPart, ToolCallPart, ToolChoice, Usage,
+};
+use ai_error::{ensure_success, AiError, Result};
+use ai_stream::{decode_events, Emitter, EventDecoder};
+
+/// A model served through Bedrock's Converse API, such as
+/// `anthropic.claude-3-5-sonnet-20240620-v1:0`, an inference profile
+/// (`us.anthropic.…`) or a provisioned throughput ARN.
+///
+/// Guardrails are applied through the `"bedrock"` provider options:
+///
+/// ```json
+/// { "guardrailConfig": {
+///     "guardrailIdentifier": "gr-abc123",
+///     "guardrailVersion": "1",
+///     "trace": "enabled" } }
+/// ```
+///
+/// When a guardrail intervenes the call finishes with
+/// [`FinishReason::ContentFilter`]; with tracing enabled the assessment is
+/// returned as `"bedrock"` metadata under `trace`. Model-specific fields
+/// (e.g. `top_k` for Anthropic models) can be passed through
+/// `additionalModelRequestFields`.
+#[derive(Debug, Clone)]
+pub struct BedrockConverseModel {
+    provider: BedrockProvider,
+    model_id: String,
+}
+
+impl BedrockProvider {
+    /// Creates a Converse API model with the given identifier.
+    pub fn converse_model(&self, model_id: impl Into<String>) -> BedrockConverseModel {
+        BedrockConverseModel {
+            provider: self.clone(),
+            model_id: model_id.into(),
+        }
+    }
+
+    /// Shorthand for [`converse_model`](Self::converse_model).
+    pub fn model(&self, model_id: impl Into<String>) -> BedrockConverseModel {
+        self.converse_model(model_id)
+    }
+}
+
+/// Typed view of the `"bedrock"` provider options.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BedrockOptions {
+    guardrail_config: Option<GuardrailConfig>,
+    additional_model_request_fields: Option<Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GuardrailConfig {
+    guardrail_identifier: String,
+    guardrail_version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    trace: Option<String>,
+    /// `"sync"` or `"async"`; only accepted by ConverseStream.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream_processing_mode: Option<String>,
+}
+
+impl BedrockConverseModel {
+    /// Builds the request body and the warnings for settings it drops.
+    fn request_body(
+        &self,
+        options: &CallOptions,
+        stream: bool,
+    ) -> Result<(Value, Vec<CallWarning>)> {
+        let settings = &options.settings;
+        let provider_options: BedrockOptions = options.provider_options(self.provider.name())?;
+        let mut warnings = settings.unsupported_warnings(&[
+            "temperature",
+            "top_p",
+            "max_output_tokens",
+            "stop_sequences",
+        ]);
+        warnings.extend(options.cache_control_warning());
+
+        let (system, messages) = convert_messages(&options.messages)?;
+        let mut body = json!({ "messages": messages });
+        if !system.is_empty() {
+            body["system"] = json!(system);
+        }
+
+        let mut inference = json!({
+            "maxTokens": settings.max_output_tokens,
+            "temperature": settings.temperature,
+            "topP": settings.top_p,
+        });
+        if !settings.stop_sequences.is_empty() {
+            inference["stopSequences"] = json!(settings.stop_sequences);
+        }
+        if let Value::Object(fields) = &mut inference {
+            fields.retain(|_, value| !value.is_null());
+            if !fields.is_empty() {
+                body["inferenceConfig"] = inference;
+            }
+        }
+
+        // Converse has no way to forbid tool use, so `None` omits the tools.
+        if !options.tools.is_empty() && options.tool_choice != Some(ToolChoice::None) {
+            let tools: Vec<Value> = options
+                .tools
+                .iter()
+                .map(|tool| {
+                    let mut spec = json!({
+                        "name": tool.name,
+                        "inputSchema": { "json": tool.input_schema },
+                    });
+                    if let Some(description) = &tool.description {
+                        spec["description"] = json!(description);
+                    }
+                    json!({ "toolSpec": spec })
+                })
+                .collect();
+            let mut tool_config = json!({ "tools": tools });
+            match &options.tool_choice {
+                Some(ToolChoice::Auto) => tool_config["toolChoice"] = json!({ "auto": {} }),
+                Some(ToolChoice::Required) => tool_config["toolChoice"] = json!({ "any": {} }),
+                Some(ToolChoice::Tool { tool_name }) => {
+                    tool_config["toolChoice"] = json!({ "tool": { "name": tool_name } });
+                }
+                Some(ToolChoice::None) | None => {}
+            }
+            body["toolConfig"] = tool_config;
+        }
+
+        if let Some(mut guardrail) = provider_options.guardrail_config {
+            if !stream {
+                guardrail.stream_processing_mode = None;
+            }
+            body["guardrailConfig"] = json!(guardrail);
+        }
+        if let Some(fields) = provider_options.additional_model_request_fields {
+            body["additionalModelRequestFields"] = fields;
+        }
+        Ok((body, warnings))
+    }
+
+    async fn send(
+        &self,
+        options: &CallOptions,
+        body: &Value,
+        stream: bool,
+    ) -> Result<reqwest::Response> {
+        let action = if stream {
+            "converse-stream"
+        } else {
+            "converse"
+        };
+        let path = format!("/model/{}/{action}", uri_encode(&self.model_id));
+        let response = self.provider.post(&path, &options.headers, body).await?;
+        ensure_success(self.provider.name(), response, map_error).await
+    }
+}
+
+/// Returns the `"bedrock"` metadata entry of a part, if any.
+fn bedrock_metadata(metadata: Option<&ProviderMetadata>) -> Option<&Value> {
+    metadata?.get("bedrock")
+}
+
+fn metadata(fields: Value) -> ProviderMetadata {
+    HashMap::from([("bedrock".to_string(), fields)])
+}
+
+/// Converts messages into the `system` blocks and the alternating
+/// user/assistant turns Converse requires.
+///
+/// System messages are hoisted into `system`, tool results become
+/// `toolResult` blocks of a user turn, and consecutive turns with the same
+/// role are merged.
+fn convert_messages(messages: &[Message]) -> Result<(Vec<Value>, Vec<Value>)> {
+    let mut system = Vec::new();
+    let mut turns: Vec<(&'static str, Vec<Value>)> = Vec::new();
+    let mut documents = 0;
+
+    for message in messages {
+        let (role, blocks) = match message.role {
+            MessageRole::System => {
+                system.extend(
+                    message
+                        .parts
+                        .iter()
+                        .filter_map(|part| Some(json!({ "text": part.as_text()? }))),
+                );
+                continue;
+            }
+            MessageRole::User => (
+                "user",
+                message
+                    .parts
+                    .iter()
+                    .map(|part| convert_user_part(part, &mut documents))
+                    .collect::<Result<Vec<_>>>()?,
+            ),
+            MessageRole::Assistant => (
+                "assistant",
+                message
+                    .parts
+                    .iter()
+                    .filter_map(convert_assistant_part)
+                    .collect(),
+            ),
+            MessageRole::Tool => (
+                "user",
+                message
+                    .parts
+                    .iter()
+                    .filter_map(|part| match part {
+                        MessagePart::ToolResult(result) => {
+                            let content = match &result.output {
+                                Value::String(text) => json!({ "text": text }),
+                                output @ Value::Object(_) => json!({ "json": output }),
+                                output => json!({ "text": output.to_string() }),
+                            };
+                            let mut block = json!({
+                                "toolUseId": result.tool_call_id,
+                                "content": [content],
+                            });
+                            if result.is_error {
+                                block["status"] = json!("error");
+                            }
+                            Some(json!({ "toolResult": block }))
+                        }
+                        _ => None,
+                    })
+                    .collect(),
+            ),
+        };
+
+        match turns.last_mut() {
+            Some((last_role, last_blocks)) if *last_role == role => last_blocks.extend(blocks),
+            _ => turns.push((role, blocks)),
+        }
+    }
+
+    let messages = turns
+        .into_iter()
+        .map(|(role, content)| json!({ "role": role, "content": content }))
+        .collect();
+    Ok((system, messages))
+}
+
+/// Returns inline data as base64; Converse does not fetch URLs.
+fn inline_bytes(data: &DataContent) -> Result<String> {
+    data.to_base64().ok_or_else(|| {
+        AiError::Validation("Bedrock requires inline image and file data, not URLs".into())
+    })
+}
+
+/// Maps a media type onto a Converse document format.
+fn document_format(media_type: &str) -> Option<&'static str> {
+    Some(match media_type {
+        "application/pdf" => "pdf",
+        "text/csv" => "csv",
+        "text/html" => "html",
+        "text/plain" => "txt",
+        "text/markdown" => "md",
+        "application/msword" => "doc",
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document" => "docx",
+        "application/vnd.ms-excel" => "xls",
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet" => "xlsx",
+        _ => return None,
+    })
+}
+
+fn convert_user_part(part: &MessagePart, documents: &mut usize) -> Result<Value> {
+    match part {
+        MessagePart::Text(text) => Ok(json!({ "text": text.text })),
+        MessagePart::Image(image) => {
+            let media_type = image.media_type.as_deref().unwrap_or("image/jpeg");
+            let format = media_type.strip_prefix("image/").unwrap_or(media_type);
+            Ok(json!({
+                "image": {
+                    "format": format,
+                    "source": { "bytes": inline_bytes(&image.image)? },
+                },
+            }))
+        }
+        MessagePart::File(file) => {
+            let format = document_format(&file.media_type).ok_or_else(|| {
+                AiError::Validation(format!("unsupported file media type '{}'", file.media_type))
+            })?;
+            // Document names must be unique within a request.
+            *documents += 1;
+            Ok(json!({
+                "document": {
+                    "format": format,
+                    "name": format!("document-{documents}"),
+                    "source": { "bytes": inline_bytes(&file.data)? },
+                },
+            }))
+        }
+        _ => Err(AiError::Validation(
+            "user messages may only contain text, image and file parts".into(),
+        )),
+    }
+}
+
+/// Converts an assistant part, dropping reasoning that cannot be sent back
+/// because it lacks a signature.
+fn convert_assistant_part(part: &MessagePart) -> Option<Value> {
+    match part {
+        MessagePart::Text(text) if !text.text.is_empty() => Some(json!({ "text": text.text })),
+        MessagePart::ToolCall(call) => Some(json!({
+            "toolUse": {
+                "toolUseId": call.tool_call_id,
+                "name": call.tool_name,
+                "input": call.input,
+            },
+        })),
+        MessagePart::Reasoning(reasoning) => {
+            let fields = bedrock_metadata(reasoning.provider_metadata.as_ref())?;
+            if let Some(data) = fields.get("redactedData") {
+                return Some(json!({ "reasoningContent": { "redactedContent": data } }));
+            }
+            Some(json!({
+                "reasoningContent": {
+                    "reasoningText": {
+                        "text": reasoning.text,
+                        "signature": fields.get("signature")?,
+                    },
+                },
+            }))
+        }
+        _ => None,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ConverseResponse {
+    output: ConverseOutput,
+    #[serde(default)]
+    stop_reason: Option<String>,
+    #[serde(default)]
+    usage: BedrockUsage,
+    #[serde(default)]
+    trace: Option<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ConverseOutput {
+    #[serde(default)]
+    message: Option<OutputMessage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OutputMessage {
+    #[serde(default)]
+    content: Vec<ContentBlock>,
+}
+
+/// A content block is an object with a single key naming its type; blocks
+/// of other types deserialize with every field unset.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ContentBlock {
+    text: Option<String>,
+    tool_use: Option<ToolUseBlock>,
+    reasoning_content: Option<ReasoningBlock>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ToolUseBlock {
+    tool_use_id: String,
+    name: String,
+    #[serde(default)]
+    input: Value,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReasoningBlock {
+    reasoning_text: Option<ReasoningText>,
+    redacted_content: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReasoningText {
+    text: String,
+    #[serde(default)]
+    signature: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BedrockUsage {
+    #[serde(default)]
+    input_tokens: u64,
+    #[serde(default)]
+    output_tokens: u64,
+    #[serde(default)]
+    cache_read_input_tokens: u64,
+    #[serde(default)]
+    cache_write_input_tokens: u64,
+}
+
+impl From<BedrockUsage> for Usage {
+    /// Cached tokens are reported separately from `inputTokens`; they are
+    /// folded into the total here.
+    fn from(usage: BedrockUsage) -> Self {
+        Usage {
+            input_tokens: usage.input_tokens
+                + usage.cache_read_input_tokens
+                + usage.cache_write_input_tokens,
+            output_tokens: usage.output_tokens,
+            cached_input_tokens: usage.cache_read_input_tokens,
+            cache_creation_input_tokens: usage.cache_write_input_tokens,
+            reasoning_tokens: 0,
+        }
+    }
+}
+
+fn map_finish_reason(reason: Option<&str>) -> FinishReason {
+    match reason {
+        Some("end_turn" | "stop_sequence") => FinishReason::Stop,
+        Some("max_tokens") => FinishReason::Length,
+        Some("tool_use") => FinishReason::ToolCalls,
+        Some("guardrail_intervened" | "content_filtered") => FinishReason::ContentFilter,
+        Some(_) => FinishReason::Other,
+        None => FinishReason::Unknown,
+    }
+}
+
+#[async_trait]
+impl LanguageModel for BedrockConverseModel {
+    fn provider(&self) -> &str {
+        self.provider.name()
+    }
+
+    fn model_id(&self) -> &str {
+        &self.model_id
+    }
+
+    fn capabilities(&self) -> ModelCapabilities {
+        ModelCapabilities {
+            tool_calling: true,
+            structured_outputs: false,
+            image_input: true,
+            file_input: true,
+            reasoning: self.model_id.contains("anthropic.claude-3-7")
+                || self.model_id.contains("anthropic.claude-sonnet-4")
+                || self.model_id.contains("anthropic.claude-opus-4"),
+            streaming: true,
+            max_context_tokens: None,
+        }
+    }
+
+    async fn do_generate(&self, options: CallOptions) -> Result<ModelResponse> {
+        let (body, warnings) = self.request_body(&options, false)?;
+        let response: ConverseResponse = self.send(&options, &body, false).await?.json().await?;
+
+        let content = response
+            .output
+            .message
+            .map(|message| message.content)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|block| {
+                if let Some(text) = block.text {
+                    return Some(MessagePart::text(text));
+                }
+                if let Some(call) = block.tool_use {
+                    return Some(MessagePart::ToolCall(ToolCallPart {
+                        tool_call_id: call.tool_use_id,
+                        tool_name: call.name,
+                        input: call.input,
+                        provider_metadata: None,
+                    }));
+                }
+                let reasoning = block.reasoning_content?;
+                let (text, fields) = match (reasoning.reasoning_text, reasoning.redacted_content) {
+                    (Some(text), _) => (text.text, json!({ "signature": text.signature })),
+                    (None, Some(data)) => (String::new(), json!({ "redactedData": data })),
+                    (None, None) => return None,
+                };
+                Some(MessagePart::Reasoning(ReasoningPart {
+                    text,
+                    provider_metadata: Some(metadata(fields)),
+                }))
+            })
+            .collect();
+
+        Ok(ModelResponse {
+            content,
+            finish_reason: map_finish_reason(response.stop_reason.as_deref()),
+            usage: response.usage.into(),
+            sources: Vec::new(),
+            warnings,
+            provider_metadata: response
+                .trace
+                .map(|trace| metadata(json!({ "trace": trace }))),
+        })
+    }
+
+    async fn do_stream(&self, options: CallOptions) -> Result<ModelStream> {
+        let (body, warnings) = self.request_body(&options, true)?;
+        let response = self.send(&options, &body, true).await?;
+
+        let messages = eventstream::decode(response.bytes_stream()).boxed();
+        let state = EventState::new(self.provider.name());
+        Ok(ModelStream {
+            stream: decode_events(messages, state).boxed(),
+            warnings,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BlockStart {
+    content_block_index: usize,
+    #[serde(default)]
+    start: Option<StartBody>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StartBody {
+    tool_use: Option<ToolUseStart>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ToolUseStart {
+    tool_use_id: String,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BlockDelta {
+    content_block_index: usize,
+    delta: DeltaBody,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DeltaBody {
+    text: Option<String>,
+    tool_use: Option<ToolUseDelta>,
+    reasoning_content: Option<ReasoningDelta>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ToolUseDelta {
+    input: String,
+}
+
+/// Signature and redacted-content deltas are ignored.
+#[derive(Debug, Deserialize)]
+struct ReasoningDelta {
+    text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BlockStop {
+    content_block_index: usize,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MessageStop {
+    stop_reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamMetadata {
+    #[serde(default)]
+    usage: BedrockUsage,
+    #[serde(default)]
+    trace: Option<Value>,
+}
+
+type Parts = Emitter<StreamPart, AiError>;
+
+/// Turns ConverseStream events into [`StreamPart`]s.
+///
+/// Deltas refer to content blocks by index; `tool_calls` maps the index of
+/// each `toolUse` block to its id. `messageStop` carries the stop reason and
+/// the final `metadata` event the usage, so the finish part is emitted on
+/// the latter.
+struct EventState {
+    provider: String,
+    tool_calls: HashMap<usize, String>,
+    finish_reason: Option<FinishReason>,
+}
+
+impl EventState {
+    fn new(provider: &str) -> Self {
+        Self {
+            provider: provider.to_string(),
+            tool_calls: HashMap::new(),
+            finish_reason: None,
+        }
+    }
+
+    fn finish(&self, usage: Usage, out: &mut Parts) {
+        out.push(StreamPart::Finish {
+            finish_reason: self.finish_reason.unwrap_or_default(),
+            usage,
+        });
+        out.close();
+    }
+}
+
+impl EventDecoder for EventState {
+    type Event = EventMessage;
+    type Item = StreamPart;
+    type Error = AiError;
+
+    fn decode(&mut self, message: EventMessage, out: &mut Parts) {
+        if message.header(":message-type") == Some("exception") {
+            let code = message.header(":exception-type").map(str::to_string);
+            let text = serde_json::from_slice::<ErrorBody>(&message.payload)
+                .map_or_else(|_| "stream exception".to_string(), |body| body.message);
+            return out.fail(map_exception(&self.provider, StatusCode::OK, code, text));
+        }
+
+        match message.header(":event-type") {
+            Some("contentBlockStart") => {
+                let Some(event) = parse::<BlockStart>(&message.payload, out) else {
+                    return;
+                };
+                if let Some(tool) = event.start.and_then(|start| start.tool_use) {
+                    self.tool_calls
+                        .insert(event.content_block_index, tool.tool_use_id.clone());
+                    out.push(StreamPart::ToolCallStart {
+                        id: tool.tool_use_id,
+                        tool_name: tool.name,
+                    });
+                }
+            }
+            Some("contentBlockDelta") => {
+                let Some(event) = parse::<BlockDelta>(&message.payload, out) else {
+                    return;
+                };
+                let delta = event.delta;
+                if let Some(text) = delta.text {
+                    out.push(StreamPart::TextDelta { text });
+                } else if let Some(text) = delta.reasoning_content.and_then(|r| r.text) {
+                    out.push(StreamPart::ReasoningDelta { text });
+                } else if let Some(tool) = delta.tool_use {
+                    if let Some(id) = self.tool_calls.get(&event.content_block_index).cloned() {
+                        out.push(StreamPart::ToolCallDelta {
+                            id,
+                            input_delta: tool.input,
+                        });
+                    }
+                }
+            }
+            Some("contentBlockStop") => {
+                let Some(event) = parse::<BlockStop>(&message.payload, out) else {
+                    return;
+                };
+                if let Some(id) = self.tool_calls.remove(&event.content_block_index) {
+                    out.push(StreamPart::ToolCallEnd { id });
+                }
+            }
+            Some("messageStop") => {
+                let Some(event) = parse::<MessageStop>(&message.payload, out) else {
+                    return;
+                };
+                self.finish_reason = Some(map_finish_reason(event.stop_reason.as_deref()));
+            }
+            Some("metadata") => {
+                let Some(event) = parse::<StreamMetadata>(&message.payload, out) else {
+                    return;
+                };
+                if let Some(trace) = event.trace {
+                    out.push(StreamPart::Metadata {
+                        provider_metadata: metadata(json!({ "trace": trace })),
+                    });
+                }
+                self.finish(event.usage.into(), out);
+            }
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, out: &mut Parts) {
+        // Usage is lost without `metadata`, but the output is whole.
+        if self.finish_reason.is_some() {
+            self.finish(Usage::default(), out);
+        } else {
+            out.fail(AiError::Stream(
+                "event stream ended before messageStop".into(),
+            ));
+        }
+    }
+}
+
+fn parse<T: DeserializeOwned>(payload: &[u8], out: &mut Parts) -> Option<T> {
+    match serde_json::from_slice(payload) {
+        Ok(event) => Some(event),
+        Err(error) => {
+            out.fail(error.into());
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eventstream::encode;
+    use crate::AwsCredentials;
+    use ai_core::{
+        generate_text, stream_text, GenerateTextRequest, StreamTextRequest, ToolDefinition,
+        ToolResultPart,
+    };
+    use mockito::Matcher;
+
+    const MODEL_ID: &str = "anthropic.claude-3-5-sonnet-20240620-v1:0";
+    const CONVERSE_PATH: &str = "/model/anthropic.claude-3-5-sonnet-20240620-v1%3A0/converse";
+    const GUARDRAIL_RESPONSE: &str = include_str!("../fixtures/converse_guardrail.json");
+
+    fn provider(server: &mockito::Server) -> BedrockProvider {
+        let credentials = AwsCredentials::new("AKIDEXAMPLE", "secret").with_session_token("token");
+        BedrockProvider::new("us-east-1", credentials).with_base_url(server.url())
+    }
+
+    fn weather_tool() -> ToolDefinition {
+        ToolDefinition::new(
+            "weather",
+            "Current weather for a city",
+            json!({ "type": "object", "properties": { "city": { "type": "string" } } }),
+        )
+    }
+
+    fn event(event_type: &str, payload: Value) -> Vec<u8> {
+        encode(
+            &[
+                (":event-type", event_type),
+                (":content-type", "application/json"),
+                (":message-type", "event"),
+            ],
+            payload.to_string().as_bytes(),
+        )
+    }
+
+    #[test]
+    fn test_convert_messages_hoists_system_and_merges_turns() {
+        let messages = vec![
+            Message::system("Be brief."),
+            Message::new(
+                MessageRole::User,
+                vec![
+                    MessagePart::text("Summarize this."),
+                    MessagePart::file(b"%PDF-1.4".to_vec(), "application/pdf"),
+                ],
+            ),
+            Message::new(
+                MessageRole::Assistant,
+                vec![
+                    MessagePart::reasoning("unsigned, dropped"),
+                    MessagePart::ToolCall(ToolCallPart {
+                        tool_call_id: "tooluse_1".into(),
+                        tool_name: "weather".into(),
+                        input: json!({ "city": "Paris" }),
+                        provider_metadata: None,
+                    }),
+                ],
+            ),
+            Message::tool([ToolResultPart {
+                tool_call_id: "tooluse_1".into(),
+                tool_name: "weather".into(),
+                output: json!({ "temperature": 21 }),
+                is_error: false,
+                cache_control: None,
+                provider_metadata: None,
+            }]),
+            Message::user("And tomorrow?"),
+        ];
+
+        let (system, turns) = convert_messages(&messages).unwrap();
+        assert_eq!(system, vec![json!({ "text": "Be brief." })]);
+        assert_eq!(turns.len(), 3);
+        assert_eq!(
+            turns[0]["content"][1]["document"],
+            json!({ "format": "pdf", "name": "document-1", "source": { "bytes": "JVBERi0xLjQ=" } })
+        );
+        assert_eq!(turns[1]["content"].as_array().unwrap().len(), 1);
+        assert_eq!(
+            turns[2]["content"][0]["toolResult"],
+            json!({ "toolUseId": "tooluse_1", "content": [{ "json": { "temperature": 21 } }] })
+        );
+        assert_eq!(turns[2]["content"][1]["text"], "And tomorrow?");
+
+        let url_image = Message::new(
+            MessageRole::User,
+            vec![MessagePart::image(
+                DataContent::Url("https://example.com/cat.png".into()),
+                None,
+            )],
+        );
+        assert!(matches!(
+            convert_messages(&[url_image]),
+            Err(AiError::Validation(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_generate_signed_with_guardrail_intervention() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", CONVERSE_PATH)
+            .match_header(
+                "authorization",
+                Matcher::Regex(
+                    r"^AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/\d{8}/us-east-1/bedrock/aws4_request, SignedHeaders=content-type;host;x-amz-date;x-amz-security-token, Signature=[0-9a-f]{64}$"
+                        .into(),
+                ),
+            )
+            .match_header("x-amz-security-token", "token")
+            .match_body(Matcher::Json(json!({
+                "messages": [{ "role": "user", "content": [{ "text": "How do I pick a lock?" }] }],
+                "system": [{ "text": "Be brief." }],
+                "inferenceConfig": { "maxTokens": 512, "temperature": 0.5 },
+                "guardrailConfig": {
+                    "guardrailIdentifier": "gr-abc123",
+                    "guardrailVersion": "1",
+                    "trace": "enabled",
+                },
+            })))
+            .with_header("content-type", "application/json")
+            .with_body(GUARDRAIL_RESPONSE)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let model = provider(&server).converse_model(MODEL_ID);
+        let mut request = GenerateTextRequest::new(model, "How do I pick a lock?")
+            .system("Be brief.")
+            .max_output_tokens(512)
+            .temperature(0.5)
+            .provider_options(
+                "bedrock",
+                json!({ "guardrailConfig": {
+                    "guardrailIdentifier": "gr-abc123",
+                    "guardrailVersion": "1",
+                    "trace": "enabled",
+                    "streamProcessingMode": "sync",
+                } }),
+            );
+        request.settings.top_k = Some(40);
+        let response = generate_text(request).await.unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(
+            response.warnings,
+            vec![CallWarning::unsupported_setting("top_k")]
+        );
+        assert_eq!(response.finish_reason, FinishReason::ContentFilter);
+        assert_eq!(response.text, "Sorry, I can't help with that.");
+        assert_eq!(response.usage, Usage::new(18, 9));
+        let trace = &response.provider_metadata.unwrap()["bedrock"]["trace"];
+        assert_eq!(
+            trace["guardrail"]["inputAssessment"]["gr-abc123"]["topicPolicy"]["topics"][0]
+                ["action"],
+            "BLOCKED"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stream_text_and_tool_use() {
+        let mut body = Vec::new();
+        for (event_type, payload) in [
+            ("messageStart", json!({ "role": "assistant" })),
+            (
+                "contentBlockDelta",
+                json!({ "contentBlockIndex": 0, "delta": { "text": "Checking " } }),
+            ),
+            (
+                "contentBlockDelta",
+                json!({ "contentBlockIndex": 0, "delta": { "text": "now." } }),
+            ),
+            ("contentBlockStop", json!({ "contentBlockIndex": 0 })),
+            (
+                "contentBlockStart",
+                json!({
+                    "contentBlockIndex": 1,
+                    "start": { "toolUse": { "toolUseId": "tooluse_kZJMlvQmRJ6eAyJE5GIl7Q", "name": "weather" } },
+                }),
+            ),
+            (
+                "contentBlockDelta",
+                json!({ "contentBlockIndex": 1, "delta": { "toolUse": { "input": "{\"city\":" } } }),
+            ),
+            (
+                "contentBlockDelta",
+                json!({ "contentBlockIndex": 1, "delta": { "toolUse": { "input": "\"Paris\"}" } } }),
+            ),
+            ("contentBlockStop", json!({ "contentBlockIndex": 1 })),
+            ("messageStop", json!({ "stopReason": "tool_use" })),
+            (
+                "metadata",
+                json!({
+                    "usage": { "inputTokens": 412, "outputTokens": 57, "totalTokens": 469 },
+                    "metrics": { "latencyMs": 1203 },
+                }),
+            ),
+        ] {
+            body.extend(event(event_type, payload));
+        }
+
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock(
+                "POST",
+                "/model/anthropic.claude-3-5-sonnet-20240620-v1%3A0/converse-stream",
+            )
+            .match_body(Matcher::PartialJson(json!({
+                "toolConfig": {
+                    "tools": [{ "toolSpec": { "name": "weather", "inputSchema": { "json": { "type": "object" } } } }],
+                    "toolChoice": { "auto": {} },
+                },
+            })))
+            .with_header("content-type", "application/vnd.amazon.eventstream")
+            .with_body(body)
+            .create_async()
+            .await;
+
+        let model = provider(&server).converse_model(MODEL_ID);
+        let handle = stream_text(
+            StreamTextRequest::new(model, "Weather in Paris?")
+                .tool(weather_tool())
+                .tool_choice(ToolChoice::Auto),
+        )
+        .await
+        .unwrap();
+        let result = handle.result().await.unwrap();
+
+        assert_eq!(result.text, "Checking now.");
+        assert_eq!(result.tool_calls.len(), 1);
+        assert_eq!(
+            result.tool_calls[0].tool_call_id,
+            "tooluse_kZJMlvQmRJ6eAyJE5GIl7Q"
+        );
+        assert_eq!(result.tool_calls[0].input, json!({ "city": "Paris" }));
+        assert_eq!(result.finish_reason, FinishReason::ToolCalls);
+        assert_eq!(result.usage, Usage::new(412, 57));
+    }
+}
@@ -0,0 +1,196 @@
+//! AWS credentials and region resolution.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+
+use ai_error::{AiError, Result};
+
+/// Static AWS credentials used to sign requests.
+#[derive(Clone, PartialEq, Eq)]
+pub struct AwsCredentials {
+    /// Access key id (`AKIA…` or `ASIA…`).
+    pub access_key_id: String,
+    /// Secret access key.
+    pub secret_access_key: String,
+    /// Session token for temporary credentials.
+    pub session_token: Option<String>,
+}
+
+impl AwsCredentials {
+    /// Creates long-term credentials without a session token.
+    pub fn new(access_key_id: impl Into<String>, secret_access_key: impl Into<String>) -> Self {
+        Self {
+            access_key_id: access_key_id.into(),
+            secret_access_key: secret_access_key.into(),
+            session_token: None,
+        }
+    }
+
+    /// Adds the session token of temporary credentials.
+    pub fn with_session_token(mut self, session_token: impl Into<String>) -> Self {
+        self.session_token = Some(session_token.into());
+        self
+    }
+
+    /// Reads credentials from `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY`
+    /// and `AWS_SESSION_TOKEN`, falling back to the shared credentials file
+    /// for the profile named by `AWS_PROFILE` (or `default`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AiError::Config`] if neither source has credentials.
+    pub fn from_env() -> Result<Self> {
+        match (
+            std::env::var("AWS_ACCESS_KEY_ID"),
+            std::env::var("AWS_SECRET_ACCESS_KEY"),
+        ) {
+            (Ok(access_key_id), Ok(secret_access_key)) => {
+                let mut credentials = Self::new(access_key_id, secret_access_key);
+                credentials.session_token = std::env::var("AWS_SESSION_TOKEN").ok();
+                Ok(credentials)
+            }
+            _ => Self::from_profile(&profile_name()),
+        }
+    }
+
+    /// Reads credentials for `profile` from the shared credentials file
+    /// (`AWS_SHARED_CREDENTIALS_FILE`, or `~/.aws/credentials`).
+    ///
+    /// Only static keys are supported; profiles that use SSO, roles or a
+    /// `credential_process` must be resolved to keys beforehand.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AiError::Config`] if the file cannot be read or the profile
+    /// has no keys.
+    pub fn from_profile(profile: &str) -> Result<Self> {
+        let path = shared_file("AWS_SHARED_CREDENTIALS_FILE", "credentials")?;
+        let contents = std::fs::read_to_string(&path).map_err(|error| {
+            AiError::Config(format!(
+                "AWS credentials not found in the environment or {}: {error}",
+                path.display()
+            ))
+        })?;
+        Self::parse_profile(&contents, profile)
+    }
+
+    fn parse_profile(contents: &str, profile: &str) -> Result<Self> {
+        let mut section = parse_section(contents, profile);
+        match (
+            section.remove("aws_access_key_id"),
+            section.remove("aws_secret_access_key"),
+        ) {
+            (Some(access_key_id), Some(secret_access_key)) => Ok(Self {
+                access_key_id,
+                secret_access_key,
+                session_token: section.remove("aws_session_token"),
+            }),
+            _ => Err(AiError::Config(format!(
+                "AWS profile '{profile}' has no aws_access_key_id and aws_secret_access_key"
+            ))),
+        }
+    }
+}
+
+impl fmt::Debug for AwsCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AwsCredentials")
+            .field("access_key_id", &self.access_key_id)
+            .field("secret_access_key", &"<redacted>")
+            .field(
+                "session_token",
+                &self.session_token.as_ref().map(|_| "<redacted>"),
+            )
+            .finish()
+    }
+}
+
+/// Reads the region from `AWS_REGION` or `AWS_DEFAULT_REGION`, falling back
+/// to the `region` of the active profile in the shared config file.
+pub(crate) fn region_from_env() -> Option<String> {
+    if let Ok(region) = std::env::var("AWS_REGION").or_else(|_| std::env::var("AWS_DEFAULT_REGION"))
+    {
+        return Some(region);
+    }
+
+    let path = shared_file("AWS_CONFIG_FILE", "config").ok()?;
+    let contents = std::fs::read_to_string(path).ok()?;
+    let profile = match profile_name().as_str() {
+        "default" => "default".to_string(),
+        name => format!("profile {name}"),
+    };
+    parse_section(&contents, &profile).remove("region")
+}
+
+fn profile_name() -> String {
+    std::env::var("AWS_PROFILE").unwrap_or_else(|_| "default".to_string())
+}
+
+/// Returns the path in `variable`, or `~/.aws/<name>`.
+fn shared_file(variable: &str, name: &str) -> Result<PathBuf> {
+    if let Ok(path) = std::env::var(variable) {
+        return Ok(PathBuf::from(path));
+    }
+    let home = std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .ok_or_else(|| AiError::Config(format!("cannot locate ~/.aws/{name}: HOME is not set")))?;
+    Ok(PathBuf::from(home).join(".aws").join(name))
+}
+
+/// Returns the `key = value` pairs of the INI section `[name]`.
+fn parse_section(contents: &str, name: &str) -> HashMap<String, String> {
+    let mut values = HashMap::new();
+    let mut in_section = false;
+    for line in contents.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(header) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            in_section = header.trim() == name;
+            continue;
+        }
+        if in_section {
+            if let Some((key, value)) = line.split_once('=') {
+                values.insert(key.trim().to_lowercase(), value.trim().to_string());
+            }
+        }
+    }
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_profile() {
+        let contents = "\
+[default]
+aws_access_key_id = AKIDDEFAULT
+aws_secret_access_key = default-secret
+
+# temporary credentials
+[work]
+aws_access_key_id=ASIAWORK
+aws_secret_access_key=work-secret
+aws_session_token = token==
+";
+        let credentials = AwsCredentials::parse_profile(contents, "work").unwrap();
+        assert_eq!(
+            credentials,
+            AwsCredentials::new("ASIAWORK", "work-secret").with_session_token("token==")
+        );
+        assert_eq!(
+            AwsCredentials::parse_profile(contents, "default")
+                .unwrap()
+                .session_token,
+            None
+        );
+        assert!(matches!(
+            AwsCredentials::parse_profile(contents, "missing"),
+            Err(AiError::Config(_))
+        ));
+        assert!(!format!("{credentials:?}").contains("work-secret"));
+    }
+}
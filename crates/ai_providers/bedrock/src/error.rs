@@ -0,0 +1,91 @@
+//! Mapping of Bedrock HTTP errors onto [`AiError`].
+
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+use ai_error::AiError;
+
+/// Error body returned by the API, also sent as an exception message
+/// mid-stream.
+#[derive(Debug, Deserialize)]
+pub(crate) struct ErrorBody {
+    #[serde(alias = "Message")]
+    pub(crate) message: String,
+}
+
+/// Maps a non-success response onto the matching [`AiError`] variant.
+///
+/// The exception name comes from the `x-amzn-ErrorType` header (e.g.
+/// `"ValidationException:http://internal.amazon.com/coral/…"`) and is used
+/// as the error code.
+pub(crate) fn map_error(
+    provider: &str,
+    status: StatusCode,
+    headers: &HeaderMap,
+    body: &str,
+) -> AiError {
+    let code = headers
+        .get("x-amzn-errortype")
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.split(':').next().unwrap_or(value).to_string());
+    let message = serde_json::from_str::<ErrorBody>(body)
+        .map_or_else(|_| format!("HTTP {status}: {body}"), |body| body.message);
+    map_exception(provider, status, code, message)
+}
+
+/// Maps an exception by status and name. Mid-stream exceptions have no
+/// status of their own and pass `200 OK`.
+pub(crate) fn map_exception(
+    provider: &str,
+    status: StatusCode,
+    code: Option<String>,
+    message: String,
+) -> AiError {
+    let name = code.as_deref().unwrap_or_default();
+    if status == StatusCode::TOO_MANY_REQUESTS || name.eq_ignore_ascii_case("throttlingException") {
+        return AiError::RateLimit { retry_after: None };
+    }
+    match status {
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => AiError::Auth(message),
+        _ => AiError::Provider {
+            provider: provider.to_string(),
+            message,
+            code,
+            details: None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_map_error_by_status_and_error_type() {
+        let body = r#"{"message":"The security token included in the request is invalid."}"#;
+        let error = map_error("bedrock", StatusCode::FORBIDDEN, &HeaderMap::new(), body);
+        assert!(matches!(error, AiError::Auth(message) if message.contains("security token")));
+
+        let error = map_error(
+            "bedrock",
+            StatusCode::TOO_MANY_REQUESTS,
+            &HeaderMap::new(),
+            "",
+        );
+        assert!(matches!(error, AiError::RateLimit { .. }));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-amzn-ErrorType",
+            HeaderValue::from_static("ValidationException:http://internal.amazon.com/coral/"),
+        );
+        let body = r#"{"message":"The provided model identifier is invalid."}"#;
+        let error = map_error("bedrock", StatusCode::BAD_REQUEST, &headers, body);
+        assert!(matches!(
+            error,
+            AiError::Provider { code: Some(code), .. } if code == "ValidationException"
+        ));
+    }
+}
@@ -0,0 +1,266 @@
+//! Decoding of the AWS event-stream binary framing.
+//!
+//! `ConverseStream` responses (`application/vnd.amazon.eventstream`) are a
+//! sequence of length-prefixed messages:
+//!
+//! ```text
+//! total length (u32) | headers length (u32) | prelude CRC32 (u32)
+//! headers | payload | message CRC32 (u32)
+//! ```
+//!
+//! All integers are big-endian. Each header is a name, a type tag and a
+//! typed value; only string headers such as `:event-type` are kept.
+
+use std::collections::{HashMap, VecDeque};
+
+use futures::stream::{self, Stream, StreamExt};
+
+use ai_error::{AiError, Result};
+
+/// Prelude (two lengths and a checksum) plus the trailing checksum.
+const OVERHEAD: usize = 16;
+
+/// Header value type tag of a UTF-8 string.
+const STRING: u8 = 7;
+
+/// One decoded message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct EventMessage {
+    pub(crate) headers: HashMap<String, String>,
+    pub(crate) payload: Vec<u8>,
+}
+
+impl EventMessage {
+    pub(crate) fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(String::as_str)
+    }
+}
+
+/// Incremental decoder for arbitrarily chunked event-stream bytes.
+#[derive(Debug, Default)]
+pub(crate) struct EventStreamDecoder {
+    buffer: Vec<u8>,
+}
+
+impl EventStreamDecoder {
+    /// Feeds a chunk of bytes and returns every message it completes.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AiError::Stream`] if a checksum does not match or a message
+    /// is malformed; the stream cannot be resynchronized after that.
+    pub(crate) fn feed(&mut self, chunk: &[u8]) -> Result<Vec<EventMessage>> {
+        self.buffer.extend_from_slice(chunk);
+
+        let mut messages = Vec::new();
+        while self.buffer.len() >= 12 {
+            let total = read_u32(&self.buffer, 0) as usize;
+            if total < OVERHEAD {
+                return Err(malformed("message shorter than its prelude"));
+            }
+            if crc32(&self.buffer[..8]) != read_u32(&self.buffer, 8) {
+                return Err(malformed("prelude checksum mismatch"));
+            }
+            if self.buffer.len() < total {
+                break;
+            }
+
+            let message: Vec<u8> = self.buffer.drain(..total).collect();
+            if crc32(&message[..total - 4]) != read_u32(&message, total - 4) {
+                return Err(malformed("message checksum mismatch"));
+            }
+            let headers_len = read_u32(&message, 4) as usize;
+            let payload_start = 12 + headers_len;
+            if payload_start > total - 4 {
+                return Err(malformed("headers overrun the message"));
+            }
+            messages.push(EventMessage {
+                headers: parse_headers(&message[12..payload_start])?,
+                payload: message[payload_start..total - 4].to_vec(),
+            });
+        }
+        Ok(messages)
+    }
+
+    /// Checks that the stream did not end inside a message.
+    pub(crate) fn finish(&self) -> Result<()> {
+        if self.buffer.is_empty() {
+            Ok(())
+        } else {
+            Err(malformed("stream ended inside a message"))
+        }
+    }
+}
+
+/// Decodes a byte stream into messages.
+///
+/// Transport and framing errors end the stream.
+pub(crate) fn decode<S, B, E>(bytes: S) -> impl Stream<Item = Result<EventMessage>> + Send
+where
+    S: Stream<Item = std::result::Result<B, E>> + Send + Unpin,
+    B: AsRef<[u8]>,
+    E: Into<AiError> + Send,
+{
+    let state = (bytes, EventStreamDecoder::default(), VecDeque::new(), false);
+    stream::unfold(
+        state,
+        |(mut bytes, mut decoder, mut pending, mut done)| async move {
+            loop {
+                if let Some(message) = pending.pop_front() {
+                    return Some((message, (bytes, decoder, pending, done)));
+                }
+                if done {
+                    return None;
+                }
+                let result = match bytes.next().await {
+                    Some(Ok(chunk)) => decoder.feed(chunk.as_ref()),
+                    Some(Err(error)) => Err(error.into()),
+                    None => {
+                        done = true;
+                        decoder.finish().map(|()| Vec::new())
+                    }
+                };
+                match result {
+                    Ok(messages) => pending.extend(messages.into_iter().map(Ok)),
+                    Err(error) => {
+                        pending.push_back(Err(error));
+                        done = true;
+                    }
+                }
+            }
+        },
+    )
+}
+
+fn parse_headers(mut bytes: &[u8]) -> Result<HashMap<String, String>> {
+    let mut headers = HashMap::new();
+    while !bytes.is_empty() {
+        let name_len = bytes[0] as usize;
+        let name = take(&mut bytes, 1, name_len)?;
+        let name = String::from_utf8_lossy(name).into_owned();
+        let kind = *take(&mut bytes, 0, 1)?.first().unwrap_or(&0);
+        let value_len = match kind {
+            0 | 1 => 0,
+            2 => 1,
+            3 => 2,
+            4 => 4,
+            5 | 8 => 8,
+            9 => 16,
+            6 | STRING => {
+                let len = take(&mut bytes, 0, 2)?;
+                u16::from_be_bytes([len[0], len[1]]) as usize
+            }
+            _ => return Err(malformed("unknown header value type")),
+        };
+        let value = take(&mut bytes, 0, value_len)?;
+        if kind == STRING {
+            headers.insert(name, String::from_utf8_lossy(value).into_owned());
+        }
+    }
+    Ok(headers)
+}
+
+/// Skips `skip` bytes, then splits `len` bytes off the front of `bytes`.
+fn take<'a>(bytes: &mut &'a [u8], skip: usize, len: usize) -> Result<&'a [u8]> {
+    if bytes.len() < skip + len {
+        return Err(malformed("headers are truncated"));
+    }
+    let (taken, rest) = bytes[skip..].split_at(len);
+    *bytes = rest;
+    Ok(taken)
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_be_bytes([
+        bytes[offset],
+        bytes[offset + 1],
+        bytes[offset + 2],
+        bytes[offset + 3],
+    ])
+}
+
+fn malformed(reason: &str) -> AiError {
+    AiError::Stream(format!("malformed event stream: {reason}"))
+}
+
+/// CRC-32 (IEEE 802.3), the checksum used by the framing.
+fn crc32(bytes: &[u8]) -> u32 {
+    const TABLE: [u32; 256] = {
+        let mut table = [0u32; 256];
+        let mut i = 0;
+        while i < 256 {
+            let mut crc = i as u32;
+            let mut bit = 0;
+            while bit < 8 {
+                crc = if crc & 1 == 1 {
+                    (crc >> 1) ^ 0xEDB8_8320
+                } else {
+                    crc >> 1
+                };
+                bit += 1;
+            }
+            table[i] = crc;
+            i += 1;
+        }
+        table
+    };
+
+    !bytes.iter().fold(!0u32, |crc, &byte| {
+        TABLE[((crc ^ byte as u32) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
+
+/// Encodes a message with string headers, for stubbing `ConverseStream`
+/// in tests.
+#[cfg(test)]
+pub(crate) fn encode(headers: &[(&str, &str)], payload: &[u8]) -> Vec<u8> {
+    let mut encoded_headers = Vec::new();
+    for (name, value) in headers {
+        encoded_headers.push(name.len() as u8);
+        encoded_headers.extend_from_slice(name.as_bytes());
+        encoded_headers.push(STRING);
+        encoded_headers.extend_from_slice(&(value.len() as u16).to_be_bytes());
+        encoded_headers.extend_from_slice(value.as_bytes());
+    }
+
+    let total = OVERHEAD + encoded_headers.len() + payload.len();
+    let mut message = Vec::with_capacity(total);
+    message.extend_from_slice(&(total as u32).to_be_bytes());
+    message.extend_from_slice(&(encoded_headers.len() as u32).to_be_bytes());
+    message.extend_from_slice(&crc32(&message).to_be_bytes());
+    message.extend_from_slice(&encoded_headers);
+    message.extend_from_slice(payload);
+    message.extend_from_slice(&crc32(&message).to_be_bytes());
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_decode_chunked_messages_and_checksums() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+
+        let mut bytes = encode(
+            &[(":message-type", "event"), (":event-type", "messageStart")],
+            br#"{"role":"assistant"}"#,
+        );
+        bytes.extend(encode(&[(":event-type", "messageStop")], b"{}"));
+        let chunks: Vec<std::result::Result<Vec<u8>, AiError>> =
+            bytes.chunks(7).map(|chunk| Ok(chunk.to_vec())).collect();
+
+        let messages: Vec<_> = decode(stream::iter(chunks)).collect().await;
+        let messages: Vec<_> = messages.into_iter().map(Result::unwrap).collect();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].header(":event-type"), Some("messageStart"));
+        assert_eq!(messages[0].payload, br#"{"role":"assistant"}"#);
+        assert_eq!(messages[1].header(":message-type"), None);
+
+        let mut corrupted = encode(&[(":event-type", "messageStop")], b"{}");
+        let last = corrupted.len() - 5;
+        corrupted[last] ^= 1;
+        let error = EventStreamDecoder::default().feed(&corrupted).unwrap_err();
+        assert!(matches!(error, AiError::Stream(message) if message.contains("checksum")));
+    }
+}
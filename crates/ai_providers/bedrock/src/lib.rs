@@ -0,0 +1,40 @@
+//! Amazon Bedrock provider for the AI SDK.
+//!
+//! [`BedrockConverseModel`] talks to the Converse and ConverseStream APIs,
+//! which offer one request shape for every model family hosted on Bedrock.
+//! Requests are signed with AWS Signature Version 4 using credentials from
+//! the environment or a shared credentials profile, and streamed responses
+//! are decoded from AWS event-stream framing.
+//!
+//! ```no_run
+//! use ai_core::{generate_text, GenerateTextRequest};
+//! use ai_providers_bedrock::BedrockProvider;
+//!
+//! # async fn run() -> ai_error::Result<()> {
+//! let model = BedrockProvider::from_env()?
+//!     .converse_model("anthropic.claude-3-5-sonnet-20240620-v1:0");
+//! let request = GenerateTextRequest::new(model, "Hello!").provider_options(
+//!     "bedrock",
+//!     serde_json::json!({
+//!         "guardrailConfig": { "guardrailIdentifier": "gr-abc123", "guardrailVersion": "1" },
+//!     }),
+//! );
+//! let response = generate_text(request).await?;
+//! println!("{}", response.text);
+//! # Ok(())
+//! # }
+//! ```
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs, rust_2018_idioms)]
+
+mod converse;
+mod credentials;
+mod error;
+mod eventstream;
+mod provider;
+mod sigv4;
+
+pub use converse::BedrockConverseModel;
+pub use credentials::AwsCredentials;
+pub use provider::BedrockProvider;
@@ -0,0 +1,146 @@
+//! Provider configuration shared by all Bedrock models.
+
+use std::collections::HashMap;
+
+use chrono::Utc;
+use serde_json::Value;
+
+use crate::credentials::{region_from_env, AwsCredentials};
+use crate::sigv4;
+use ai_core::{LanguageModel, ModelProvider};
+use ai_error::{AiError, Result};
+
+/// Service name in the SigV4 credential scope.
+const SIGNING_SERVICE: &str = "bedrock";
+
+/// Entry point for Amazon Bedrock models.
+///
+/// Holds the credentials, region, endpoint and HTTP client used by every
+/// model created from it. Requests are signed with AWS Signature Version 4.
+#[derive(Debug, Clone)]
+pub struct BedrockProvider {
+    region: String,
+    credentials: AwsCredentials,
+    base_url: String,
+    headers: HashMap<String, String>,
+    client: reqwest::Client,
+}
+
+impl BedrockProvider {
+    /// Creates a provider for the Bedrock runtime endpoint of `region`.
+    pub fn new(region: impl Into<String>, credentials: AwsCredentials) -> Self {
+        let region = region.into();
+        Self {
+            base_url: format!("https://bedrock-runtime.{region}.amazonaws.com"),
+            region,
+            credentials,
+            headers: HashMap::new(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Creates a provider from the standard AWS environment.
+    ///
+    /// The region comes from `AWS_REGION`, `AWS_DEFAULT_REGION` or the
+    /// active profile in `~/.aws/config`; credentials as described in
+    /// [`AwsCredentials::from_env`]. `BEDROCK_BASE_URL` overrides the
+    /// endpoint, e.g. for a VPC endpoint or a local stub.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AiError::Config`] if no region or credentials are found.
+    pub fn from_env() -> Result<Self> {
+        let region =
+            region_from_env().ok_or_else(|| AiError::Config("AWS_REGION is not set".into()))?;
+
+        let mut provider = Self::new(region, AwsCredentials::from_env()?);
+        if let Ok(base_url) = std::env::var("BEDROCK_BASE_URL") {
+            provider = provider.with_base_url(base_url);
+        }
+        Ok(provider)
+    }
+
+    /// Sends requests to `base_url` instead of the regional endpoint.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Adds a header sent with every request.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.insert(name.into(), value.into());
+        self
+    }
+
+    /// Replaces the HTTP client, e.g. to route requests through a VPC
+    /// endpoint proxy.
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// The AWS region requests are signed for.
+    pub fn region(&self) -> &str {
+        &self.region
+    }
+
+    /// Sends a signed `POST` request with a JSON `body` to `path`, applying
+    /// provider headers followed by the per-call `headers`.
+    ///
+    /// Unlike the other providers this sends the request itself: the
+    /// signature covers the body and headers, so both must be final first.
+    pub(crate) async fn post(
+        &self,
+        path: &str,
+        headers: &HashMap<String, String>,
+        body: &Value,
+    ) -> Result<reqwest::Response> {
+        let mut request = self
+            .client
+            .post(format!("{}{}", self.base_url, path))
+            .header("content-type", "application/json")
+            .body(serde_json::to_vec(body)?);
+        for (name, value) in self.headers.iter().chain(headers) {
+            request = request.header(name, value);
+        }
+
+        let mut request = request.build()?;
+        sigv4::sign(
+            &mut request,
+            &self.credentials,
+            &self.region,
+            SIGNING_SERVICE,
+            Utc::now(),
+        )?;
+        Ok(self.client.execute(request).await?)
+    }
+}
+
+impl ModelProvider for BedrockProvider {
+    fn name(&self) -> &str {
+        "bedrock"
+    }
+
+    fn language_model(&self, model_id: &str) -> Result<Box<dyn LanguageModel>> {
+        Ok(Box::new(self.converse_model(model_id)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base_url_follows_region() {
+        let credentials = AwsCredentials::new("AKID", "secret");
+        let provider = BedrockProvider::new("eu-west-1", credentials.clone());
+        assert_eq!(
+            provider.base_url,
+            "https://bedrock-runtime.eu-west-1.amazonaws.com"
+        );
+
+        let provider = provider.with_base_url("http://localhost:4566/");
+        assert_eq!(provider.base_url, "http://localhost:4566");
+        assert_eq!(provider.region(), "eu-west-1");
+    }
+}
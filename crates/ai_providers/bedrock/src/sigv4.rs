@@ -0,0 +1,249 @@
+//! AWS Signature Version 4 request signing.
+//!
+//! See <https://docs.aws.amazon.com/IAM/latest/UserGuide/reference_sigv-create-signed-request.html>.
+//! Only what Bedrock needs is implemented: the signature goes in the
+//! `Authorization` header and the payload is always hashed.
+
+use std::fmt::Write;
+
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderValue, AUTHORIZATION, HOST};
+use reqwest::Request;
+use ring::{digest, hmac};
+
+use crate::credentials::AwsCredentials;
+use ai_error::{AiError, Result};
+
+const ALGORITHM: &str = "AWS4-HMAC-SHA256";
+
+/// Signs `request` in place for `service` in `region` at `time`.
+///
+/// Sets `host`, `x-amz-date` and, for temporary credentials,
+/// `x-amz-security-token`, then adds the `Authorization` header. Every
+/// header already on the request is signed, so headers must not change
+/// afterwards.
+pub(crate) fn sign(
+    request: &mut Request,
+    credentials: &AwsCredentials,
+    region: &str,
+    service: &str,
+    time: DateTime<Utc>,
+) -> Result<()> {
+    let amz_date = time.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = &amz_date[..8];
+
+    let url = request.url();
+    let host = match url.port() {
+        Some(port) => format!("{}:{port}", url.host_str().unwrap_or_default()),
+        None => url.host_str().unwrap_or_default().to_string(),
+    };
+    let canonical_uri = canonical_uri(url.path());
+    let canonical_query = canonical_query(url.query().unwrap_or_default());
+
+    let headers = request.headers_mut();
+    headers.insert(HOST, header_value(&host)?);
+    headers.insert("x-amz-date", header_value(&amz_date)?);
+    if let Some(token) = &credentials.session_token {
+        headers.insert("x-amz-security-token", header_value(token)?);
+    }
+
+    let mut signed: Vec<(String, String)> = request
+        .headers()
+        .iter()
+        .map(|(name, value)| {
+            let value = String::from_utf8_lossy(value.as_bytes());
+            (name.as_str().to_string(), collapse_whitespace(&value))
+        })
+        .collect();
+    signed.sort();
+    let canonical_headers = signed.iter().fold(String::new(), |mut out, (name, value)| {
+        let _ = writeln!(out, "{name}:{value}");
+        out
+    });
+    let signed_headers = signed
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>()
+        .join(";");
+
+    let body = request
+        .body()
+        .and_then(|body| body.as_bytes())
+        .unwrap_or_default();
+    let canonical_request = format!(
+        "{}\n{canonical_uri}\n{canonical_query}\n{canonical_headers}\n{signed_headers}\n{}",
+        request.method(),
+        hex(digest::digest(&digest::SHA256, body).as_ref()),
+    );
+
+    let scope = format!("{date}/{region}/{service}/aws4_request");
+    let string_to_sign = format!(
+        "{ALGORITHM}\n{amz_date}\n{scope}\n{}",
+        hex(digest::digest(&digest::SHA256, canonical_request.as_bytes()).as_ref()),
+    );
+
+    let secret = format!("AWS4{}", credentials.secret_access_key);
+    let key = [date, region, service, "aws4_request"]
+        .iter()
+        .fold(secret.into_bytes(), |key, part| {
+            hmac_sha256(&key, part.as_bytes())
+        });
+    let signature = hex(&hmac_sha256(&key, string_to_sign.as_bytes()));
+
+    let authorization = format!(
+        "{ALGORITHM} Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+        credentials.access_key_id
+    );
+    request
+        .headers_mut()
+        .insert(AUTHORIZATION, header_value(&authorization)?);
+    Ok(())
+}
+
+/// Percent-encodes everything except unreserved characters, as SigV4
+/// requires for path segments and query components.
+pub(crate) fn uri_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            let _ = write!(encoded, "%{byte:02X}");
+        }
+    }
+    encoded
+}
+
+/// Encodes each segment of an already encoded path a second time, which
+/// every service except S3 expects.
+fn canonical_uri(path: &str) -> String {
+    if path.is_empty() {
+        return "/".to_string();
+    }
+    path.split('/')
+        .map(uri_encode)
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn canonical_query(query: &str) -> String {
+    let mut pairs: Vec<(String, String)> = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (uri_encode(&decode(name)), uri_encode(&decode(value)))
+        })
+        .collect();
+    pairs.sort();
+    pairs
+        .iter()
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// Decodes `%XX` escapes so query components are not encoded twice.
+fn decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .filter(|_| bytes[i] == b'%')
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn collapse_whitespace(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, key);
+    hmac::sign(&key, data).as_ref().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut out, byte| {
+        let _ = write!(out, "{byte:02x}");
+        out
+    })
+}
+
+fn header_value(value: &str) -> Result<HeaderValue> {
+    HeaderValue::from_str(value)
+        .map_err(|_| AiError::Config(format!("invalid characters in header value '{value}'")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use reqwest::Method;
+
+    fn example_credentials() -> AwsCredentials {
+        AwsCredentials::new("AKIDEXAMPLE", "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY")
+    }
+
+    /// `get-vanilla` and `post-vanilla` from the AWS SigV4 test suite.
+    #[test]
+    fn test_sign_matches_aws_test_suite() {
+        let time = Utc.with_ymd_and_hms(2015, 8, 30, 12, 36, 0).unwrap();
+        let scope = "AKIDEXAMPLE/20150830/us-east-1/service/aws4_request";
+
+        for (method, signature) in [
+            (
+                Method::GET,
+                "5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31",
+            ),
+            (
+                Method::POST,
+                "5da7c1a2acd57cee7505fc6676e4e544621c30862966e37dddb68e92efbe5d6b",
+            ),
+        ] {
+            let url = "https://example.amazonaws.com/".parse().unwrap();
+            let mut request = Request::new(method, url);
+            sign(
+                &mut request,
+                &example_credentials(),
+                "us-east-1",
+                "service",
+                time,
+            )
+            .unwrap();
+
+            assert_eq!(
+                request.headers()[AUTHORIZATION],
+                format!(
+                    "{ALGORITHM} Credential={scope}, SignedHeaders=host;x-amz-date, Signature={signature}"
+                )
+            );
+            assert_eq!(request.headers()["x-amz-date"], "20150830T123600Z");
+        }
+    }
+
+    #[test]
+    fn test_canonical_uri_and_query() {
+        let path = format!("/model/{}/converse", uri_encode("anthropic.claude-v2:1"));
+        assert_eq!(path, "/model/anthropic.claude-v2%3A1/converse");
+        assert_eq!(
+            canonical_uri(&path),
+            "/model/anthropic.claude-v2%253A1/converse"
+        );
+        assert_eq!(canonical_query("b=2&a=x%20y&c"), "a=x%20y&b=2&c=");
+    }
+}
@@ -0,0 +1,132 @@
+//! Mapping of Google Generative AI HTTP errors onto [`AiError`].
+
+use std::time::Duration;
+
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use serde_json::Value;
+
+use ai_error::{retry_after, AiError};
+
+/// Error envelope returned by the API, also sent as a chunk mid-stream.
+#[derive(Debug, Deserialize)]
+pub(crate) struct ErrorEnvelope {
+    error: ErrorBody,
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorBody {
+    message: String,
+    /// Canonical status name such as `"INVALID_ARGUMENT"`.
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    details: Vec<Value>,
+}
+
+impl ErrorBody {
+    /// Returns the `reason` of the first `ErrorInfo` detail.
+    fn reason(&self) -> Option<&str> {
+        self.details
+            .iter()
+            .find_map(|detail| detail.get("reason")?.as_str())
+    }
+
+    /// Returns the delay of the first `RetryInfo` detail (e.g. `"31s"`).
+    fn retry_delay(&self) -> Option<Duration> {
+        self.details
+            .iter()
+            .find_map(|detail| detail.get("retryDelay")?.as_str())
+            .and_then(|delay| delay.strip_suffix('s')?.parse::<f64>().ok())
+            .filter(|seconds| seconds.is_finite() && *seconds >= 0.0)
+            .map(Duration::from_secs_f64)
+    }
+}
+
+impl ErrorEnvelope {
+    /// Converts the error into [`AiError::Provider`] with the status name as
+    /// the code.
+    pub(crate) fn into_error(self, provider: &str) -> AiError {
+        AiError::Provider {
+            provider: provider.to_string(),
+            message: self.error.message,
+            code: self.error.status,
+            details: None,
+        }
+    }
+}
+
+/// Maps a non-success response onto the matching [`AiError`] variant.
+///
+/// An invalid API key is reported with status 400 and the reason
+/// `API_KEY_INVALID`, so it is recognized by reason as well as by status.
+/// Rate limits carry their delay in a `RetryInfo` detail; the `Retry-After`
+/// header is only consulted when that is missing.
+pub(crate) fn map_error(
+    provider: &str,
+    status: StatusCode,
+    headers: &HeaderMap,
+    body: &str,
+) -> AiError {
+    let parsed = serde_json::from_str::<ErrorEnvelope>(body).ok();
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        return AiError::RateLimit {
+            retry_after: parsed
+                .and_then(|e| e.error.retry_delay())
+                .or_else(|| retry_after(headers)),
+        };
+    }
+
+    let is_auth = matches!(status, StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN)
+        || parsed.as_ref().and_then(|e| e.error.reason()) == Some("API_KEY_INVALID");
+    match parsed {
+        Some(envelope) if is_auth => AiError::Auth(envelope.error.message),
+        None if is_auth => AiError::Auth(format!("HTTP {status}: {body}")),
+        Some(envelope) => envelope.into_error(provider),
+        None => AiError::Provider {
+            provider: provider.to_string(),
+            message: format!("HTTP {status}: {body}"),
+            code: None,
+            details: None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::{HeaderValue, RETRY_AFTER};
+
+    #[test]
+    fn test_map_error_by_status_and_reason() {
+        let body = r#"{"error":{"code":400,"message":"API key not valid. Please pass a valid API key.","status":"INVALID_ARGUMENT",
+            "details":[{"@type":"type.googleapis.com/google.rpc.ErrorInfo","reason":"API_KEY_INVALID","domain":"googleapis.com"}]}}"#;
+        let error = map_error("google", StatusCode::BAD_REQUEST, &HeaderMap::new(), body);
+        assert!(
+            matches!(error, AiError::Auth(message) if message.starts_with("API key not valid"))
+        );
+
+        let body = r#"{"error":{"code":429,"message":"Resource has been exhausted","status":"RESOURCE_EXHAUSTED",
+            "details":[{"@type":"type.googleapis.com/google.rpc.RetryInfo","retryDelay":"31s"}]}}"#;
+        let error = map_error(
+            "google",
+            StatusCode::TOO_MANY_REQUESTS,
+            &HeaderMap::new(),
+            body,
+        );
+        assert_eq!(error.retry_after(), Some(Duration::from_secs(31)));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("5"));
+        let error = map_error("google", StatusCode::TOO_MANY_REQUESTS, &headers, "");
+        assert_eq!(error.retry_after(), Some(Duration::from_secs(5)));
+
+        let body = r#"{"error":{"code":400,"message":"Invalid JSON payload","status":"INVALID_ARGUMENT"}}"#;
+        let error = map_error("google", StatusCode::BAD_REQUEST, &HeaderMap::new(), body);
+        assert!(matches!(
+            error,
+            AiError::Provider { code: Some(code), .. } if code == "INVALID_ARGUMENT"
+        ));
+    }
+}
@@ -0,0 +1,847 @@
+//! Text generation through the Gemini `generateContent` API.
+
+use std::collections::{BTreeSet, HashMap};
+
+use async_trait::async_trait;
+use futures::stream::StreamExt;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::error::{map_error, ErrorEnvelope};
+use crate::provider::GoogleProvider;
+use crate::safety::SafetySetting;
+use crate::schema::{convert_schema, is_empty_object};
+use ai_core::{
+    CallOptions, CallWarning, DataContent, FinishReason, LanguageModel, Message, MessagePart,
+    MessageRole, ModelCapabilities, ModelProvider, ModelResponse, ModelStream, ProviderMetadata,
+    ReasoningPart, Source, StreamPart, TextPart, ToolCallPart, ToolChoice, Usage,
+};
+use ai_error::{ensure_success, AiError, Result};
+use ai_stream::sse::{self, SseEvent};
+use ai_stream::{decode_events, Emitter, EventDecoder};
+
+/// A Gemini model such as `gemini-2.5-flash`.
+///
+/// Provider options are read from the `"google"` entry:
+///
+/// - `safetySettings` — array of [`SafetySetting`]s.
+/// - `useSearchGrounding` — ground answers with Google Search. The web pages
+///   used are returned as sources.
+///
+/// A prompt rejected by the safety filter finishes with
+/// [`FinishReason::PromptBlocked`] and the `blockReason` in the `"google"`
+/// provider metadata.
+#[derive(Debug, Clone)]
+pub struct GoogleGenerativeAiModel {
+    provider: GoogleProvider,
+    model_id: String,
+}
+
+impl GoogleProvider {
+    /// Creates a Gemini model with the given identifier.
+    pub fn generative_model(&self, model_id: impl Into<String>) -> GoogleGenerativeAiModel {
+        let model_id = model_id.into();
+        GoogleGenerativeAiModel {
+            provider: self.clone(),
+            model_id: model_id
+                .strip_prefix("models/")
+                .map(str::to_string)
+                .unwrap_or(model_id),
+        }
+    }
+
+    /// Shorthand for [`generative_model`](Self::generative_model).
+    pub fn model(&self, model_id: impl Into<String>) -> GoogleGenerativeAiModel {
+        self.generative_model(model_id)
+    }
+}
+
+/// Typed view of the `"google"` provider options.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GoogleOptions {
+    #[serde(default)]
+    safety_settings: Vec<SafetySetting>,
+    #[serde(default)]
+    use_search_grounding: bool,
+}
+
+impl GoogleGenerativeAiModel {
+    /// Builds the request body and the warnings for settings it drops.
+    fn request_body(&self, options: &CallOptions) -> Result<(Value, Vec<CallWarning>)> {
+        let settings = &options.settings;
+        let provider_options: GoogleOptions = options.provider_options(self.provider.name())?;
+        let mut warnings = settings.unsupported_warnings(&[
+            "temperature",
+            "top_p",
+            "top_k",
+            "max_output_tokens",
+            "stop_sequences",
+            "seed",
+            "presence_penalty",
+            "frequency_penalty",
+        ]);
+        warnings.extend(options.cache_control_warning());
+
+        let (system, contents) = convert_messages(&options.messages)?;
+        let mut generation_config = json!({
+            "temperature": settings.temperature,
+            "topP": settings.top_p,
+            "topK": settings.top_k,
+            "maxOutputTokens": settings.max_output_tokens,
+            "seed": settings.seed,
+            "presencePenalty": settings.presence_penalty,
+            "frequencyPenalty": settings.frequency_penalty,
+        });
+        if !settings.stop_sequences.is_empty() {
+            generation_config["stopSequences"] = json!(settings.stop_sequences);
+        }
+        // `json!` wrote every unset setting as `null`; Gemini treats those as
+        // explicit values, so strip them.
+        if let Value::Object(fields) = &mut generation_config {
+            fields.retain(|_, value| !value.is_null());
+        }
+
+        let mut body = json!({
+            "contents": contents,
+            "generationConfig": generation_config,
+        });
+        if !system.is_empty() {
+            body["systemInstruction"] = json!({ "parts": system });
+        }
+        if !provider_options.safety_settings.is_empty() {
+            body["safetySettings"] = json!(provider_options.safety_settings);
+        }
+
+        let mut tools = Vec::new();
+        if !options.tools.is_empty() {
+            let declarations: Vec<Value> = options
+                .tools
+                .iter()
+                .map(|tool| {
+                    let mut removed = BTreeSet::new();
+                    let parameters = convert_schema(&tool.input_schema, &mut removed);
+                    if !removed.is_empty() {
+                        let keywords: Vec<String> = removed.into_iter().collect();
+                        warnings.push(CallWarning::other(format!(
+                            "tool '{}': removed JSON Schema keywords unsupported by Gemini: {}",
+                            tool.name,
+                            keywords.join(", ")
+                        )));
+                    }
+
+                    let mut declaration = json!({ "name": tool.name });
+                    if let Some(description) = &tool.description {
+                        declaration["description"] = json!(description);
+                    }
+                    if !is_empty_object(&parameters) {
+                        declaration["parameters"] = parameters;
+                    }
+                    declaration
+                })
+                .collect();
+            tools.push(json!({ "functionDeclarations": declarations }));
+        }
+        if provider_options.use_search_grounding {
+            tools.push(json!({ "googleSearch": {} }));
+        }
+        if !tools.is_empty() {
+            body["tools"] = json!(tools);
+        }
+
+        if let Some(choice) = &options.tool_choice {
+            let config = match choice {
+                ToolChoice::Auto => json!({ "mode": "AUTO" }),
+                ToolChoice::None => json!({ "mode": "NONE" }),
+                ToolChoice::Required => json!({ "mode": "ANY" }),
+                ToolChoice::Tool { tool_name } => {
+                    json!({ "mode": "ANY", "allowedFunctionNames": [tool_name] })
+                }
+            };
+            body["toolConfig"] = json!({ "functionCallingConfig": config });
+        }
+
+        Ok((body, warnings))
+    }
+
+    async fn send(
+        &self,
+        options: &CallOptions,
+        method: &str,
+        body: &Value,
+    ) -> Result<reqwest::Response> {
+        let path = format!("/models/{}:{}", self.model_id, method);
+        let response = self
+            .provider
+            .post(&path, &options.headers)
+            .json(body)
+            .send()
+            .await?;
+        ensure_success(self.provider.name(), response, map_error).await
+    }
+}
+
+fn metadata(fields: Value) -> ProviderMetadata {
+    HashMap::from([("google".to_string(), fields)])
+}
+
+/// Returns the thought signature stored in a part's `"google"` metadata.
+fn thought_signature(metadata: Option<&ProviderMetadata>) -> Option<&Value> {
+    metadata?.get("google")?.get("thoughtSignature")
+}
+
+/// Converts messages into the `systemInstruction` parts and the `user` and
+/// `model` turns of `contents`.
+///
+/// Tool results are sent as `functionResponse` parts of a user turn, and
+/// consecutive turns with the same role are merged.
+fn convert_messages(messages: &[Message]) -> Result<(Vec<Value>, Vec<Value>)> {
+    let mut system = Vec::new();
+    let mut turns: Vec<(&'static str, Vec<Value>)> = Vec::new();
+
+    for message in messages {
+        let (role, parts) = match message.role {
+            MessageRole::System => {
+                system.extend(
+                    message
+                        .parts
+                        .iter()
+                        .filter_map(MessagePart::as_text)
+                        .map(|text| json!({ "text": text })),
+                );
+                continue;
+            }
+            MessageRole::User => (
+                "user",
+                message
+                    .parts
+                    .iter()
+                    .map(convert_user_part)
+                    .collect::<Result<Vec<_>>>()?,
+            ),
+            MessageRole::Assistant => (
+                "model",
+                message
+                    .parts
+                    .iter()
+                    .filter_map(convert_model_part)
+                    .collect(),
+            ),
+            MessageRole::Tool => (
+                "user",
+                message
+                    .parts
+                    .iter()
+                    .filter_map(|part| match part {
+                        MessagePart::ToolResult(result) => {
+                            // `response` must be an object.
+                            let response = match &result.output {
+                                Value::Object(_) => result.output.clone(),
+                                output => json!({ "content": output }),
+                            };
+                            Some(json!({
+                                "functionResponse": {
+                                    "name": result.tool_name,
+                                    "response": response,
+                                }
+                            }))
+                        }
+                        _ => None,
+                    })
+                    .collect(),
+            ),
+        };
+
+        match turns.last_mut() {
+            Some((last_role, last_parts)) if *last_role == role => last_parts.extend(parts),
+            _ => turns.push((role, parts)),
+        }
+    }
+
+    let contents = turns
+        .into_iter()
+        .map(|(role, parts)| json!({ "role": role, "parts": parts }))
+        .collect();
+    Ok((system, contents))
+}
+
+/// Converts inline data into `inlineData` and URLs into `fileData`.
+fn data_part(data: &DataContent, media_type: &str) -> Value {
+    match data {
+        DataContent::Url(url) => json!({ "fileData": { "mimeType": media_type, "fileUri": url } }),
+        _ => json!({
+            "inlineData": {
+                "mimeType": media_type,
+                "data": data.to_base64().unwrap_or_default(),
+            }
+        }),
+    }
+}
+
+fn convert_user_part(part: &MessagePart) -> Result<Value> {
+    match part {
+        MessagePart::Text(text) => Ok(json!({ "text": text.text })),
+        MessagePart::Image(image) => {
+            let media_type = image.media_type.as_deref().unwrap_or("image/jpeg");
+            Ok(data_part(&image.image, media_type))
+        }
+        MessagePart::File(file) => Ok(data_part(&file.data, &file.media_type)),
+        _ => Err(AiError::Validation(
+            "user messages may only contain text, image and file parts".into(),
+        )),
+    }
+}
+
+/// Converts an assistant part, keeping thought signatures so Gemini can
+/// resume its reasoning. Reasoning without a signature is dropped.
+fn convert_model_part(part: &MessagePart) -> Option<Value> {
+    let mut converted = match part {
+        MessagePart::Text(text) if !text.text.is_empty() => json!({ "text": text.text }),
+        MessagePart::ToolCall(call) => json!({
+            "functionCall": { "name": call.tool_name, "args": call.input }
+        }),
+        MessagePart::Reasoning(reasoning) => {
+            thought_signature(reasoning.provider_metadata.as_ref())?;
+            json!({ "text": reasoning.text, "thought": true })
+        }
+        _ => return None,
+    };
+    if let Some(signature) = thought_signature(part.provider_metadata()) {
+        converted["thoughtSignature"] = signature.clone();
+    }
+    Some(converted)
+}
+
+/// A `generateContent` response, also the shape of each streamed chunk.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GenerateContentResponse {
+    #[serde(default)]
+    candidates: Vec<Candidate>,
+    #[serde(default)]
+    prompt_feedback: Option<PromptFeedback>,
+    #[serde(default)]
+    usage_metadata: Option<UsageMetadata>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Candidate {
+    #[serde(default)]
+    content: Option<Content>,
+    #[serde(default)]
+    finish_reason: Option<String>,
+    #[serde(default)]
+    grounding_metadata: Option<GroundingMetadata>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Content {
+    #[serde(default)]
+    parts: Vec<Part>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Part {
+    #[serde(default)]
+    text: Option<String>,
+    #[serde(default)]
+    thought: bool,
+    #[serde(default)]
+    thought_signature: Option<String>,
+    #[serde(default)]
+    function_call: Option<FunctionCall>,
+}
+
+impl Part {
+    fn metadata(&self) -> Option<ProviderMetadata> {
+        let signature = self.thought_signature.as_ref()?;
+        Some(metadata(json!({ "thoughtSignature": signature })))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct FunctionCall {
+    name: String,
+    #[serde(default)]
+    args: Value,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PromptFeedback {
+    #[serde(default)]
+    block_reason: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GroundingMetadata {
+    #[serde(default)]
+    grounding_chunks: Vec<GroundingChunk>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GroundingChunk {
+    #[serde(default)]
+    web: Option<GroundingSource>,
+    #[serde(default)]
+    retrieved_context: Option<GroundingSource>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GroundingSource {
+    uri: String,
+    #[serde(default)]
+    title: Option<String>,
+}
+
+impl GroundingMetadata {
+    /// Converts grounding chunks into sources, numbering them from `first_id`.
+    fn sources(self, first_id: usize) -> Vec<Source> {
+        self.grounding_chunks
+            .into_iter()
+            .filter_map(|chunk| chunk.web.or(chunk.retrieved_context))
+            .enumerate()
+            .map(|(index, source)| Source {
+                id: format!("source-{}", first_id + index),
+                url: source.uri,
+                title: source.title,
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UsageMetadata {
+    #[serde(default)]
+    prompt_token_count: u64,
+    #[serde(default)]
+    candidates_token_count: u64,
+    #[serde(default)]
+    cached_content_token_count: u64,
+    #[serde(default)]
+    thoughts_token_count: u64,
+}
+
+impl From<UsageMetadata> for Usage {
+    /// Gemini counts thoughts separately from the candidates; both are billed
+    /// as output.
+    fn from(usage: UsageMetadata) -> Self {
+        Usage {
+            input_tokens: usage.prompt_token_count,
+            output_tokens: usage.candidates_token_count + usage.thoughts_token_count,
+            cached_input_tokens: usage.cached_content_token_count,
+            reasoning_tokens: usage.thoughts_token_count,
+            ..Default::default()
+        }
+    }
+}
+
+fn map_finish_reason(reason: &str, has_tool_calls: bool) -> FinishReason {
+    match reason {
+        "STOP" if has_tool_calls => FinishReason::ToolCalls,
+        "STOP" => FinishReason::Stop,
+        "MAX_TOKENS" => FinishReason::Length,
+        "SAFETY" | "RECITATION" | "BLOCKLIST" | "PROHIBITED_CONTENT" | "SPII" | "IMAGE_SAFETY" => {
+            FinishReason::ContentFilter
+        }
+        "MALFORMED_FUNCTION_CALL" => FinishReason::Error,
+        _ => FinishReason::Other,
+    }
+}
+
+/// Returns the block reason of a prompt rejected before generation.
+fn block_reason(response: &GenerateContentResponse) -> Option<&str> {
+    response.prompt_feedback.as_ref()?.block_reason.as_deref()
+}
+
+#[async_trait]
+impl LanguageModel for GoogleGenerativeAiModel {
+    fn provider(&self) -> &str {
+        self.provider.name()
+    }
+
+    fn model_id(&self) -> &str {
+        &self.model_id
+    }
+
+    fn capabilities(&self) -> ModelCapabilities {
+        ModelCapabilities {
+            tool_calling: true,
+            structured_outputs: true,
+            image_input: true,
+            file_input: true,
+            reasoning: self.model_id.starts_with("gemini-2.5")
+                || self.model_id.contains("thinking"),
+            streaming: true,
+            max_context_tokens: Some(1_048_576),
+        }
+    }
+
+    async fn do_generate(&self, options: CallOptions) -> Result<ModelResponse> {
+        let (body, warnings) = self.request_body(&options)?;
+        let response: GenerateContentResponse = self
+            .send(&options, "generateContent", &body)
+            .await?
+            .json()
+            .await?;
+
+        let usage = response.usage_metadata.map(Usage::from).unwrap_or_default();
+        if let Some(reason) = block_reason(&response) {
+            return Ok(ModelResponse {
+                finish_reason: FinishReason::PromptBlocked,
+                usage,
+                warnings,
+                provider_metadata: Some(metadata(json!({ "blockReason": reason }))),
+                ..Default::default()
+            });
+        }
+
+        let candidate = response.candidates.into_iter().next().unwrap_or_default();
+        let mut content = Vec::new();
+        for (index, part) in candidate
+            .content
+            .map(|content| content.parts)
+            .unwrap_or_default()
+            .into_iter()
+            .enumerate()
+        {
+            let provider_metadata = part.metadata();
+            if let Some(call) = part.function_call {
+                content.push(MessagePart::ToolCall(ToolCallPart {
+                    tool_call_id: format!("call_{index}"),
+                    tool_name: call.name,
+                    input: call.args,
+                    provider_metadata,
+                }));
+            } else if let Some(text) = part.text {
+                content.push(if part.thought {
+                    MessagePart::Reasoning(ReasoningPart {
+                        text,
+                        provider_metadata,
+                    })
+                } else {
+                    MessagePart::Text(TextPart {
+                        text,
+                        cache_control: None,
+                        provider_metadata,
+                    })
+                });
+            }
+        }
+
+        let has_tool_calls = content
+            .iter()
+            .any(|part| matches!(part, MessagePart::ToolCall(_)));
+        let finish_reason = candidate
+            .finish_reason
+            .as_deref()
+            .map_or(FinishReason::Unknown, |reason| {
+                map_finish_reason(reason, has_tool_calls)
+            });
+
+        Ok(ModelResponse {
+            content,
+            finish_reason,
+            usage,
+            sources: candidate
+                .grounding_metadata
+                .map(|grounding| grounding.sources(0))
+                .unwrap_or_default(),
+            warnings,
+            provider_metadata: None,
+        })
+    }
+
+    async fn do_stream(&self, options: CallOptions) -> Result<ModelStream> {
+        let (body, warnings) = self.request_body(&options)?;
+        let response = self
+            .send(&options, "streamGenerateContent?alt=sse", &body)
+            .await?;
+
+        let events = sse::decode(response.bytes_stream()).boxed();
+        let state = ChunkState::new(self.provider.name());
+        Ok(ModelStream {
+            stream: decode_events(events, state).boxed(),
+            warnings,
+        })
+    }
+}
+
+type Parts = Emitter<StreamPart, AiError>;
+
+/// Turns streamed `generateContent` chunks into [`StreamPart`]s.
+///
+/// Every chunk is a partial response; function calls always arrive whole,
+/// so each becomes a start, a single delta and an end event.
+struct ChunkState {
+    provider: String,
+    tool_calls: usize,
+    sources: usize,
+    usage: Usage,
+    finish_reason: Option<FinishReason>,
+}
+
+impl ChunkState {
+    fn new(provider: &str) -> Self {
+        Self {
+            provider: provider.to_string(),
+            tool_calls: 0,
+            sources: 0,
+            usage: Usage::default(),
+            finish_reason: None,
+        }
+    }
+}
+
+impl EventDecoder for ChunkState {
+    type Event = SseEvent;
+    type Item = StreamPart;
+    type Error = AiError;
+
+    fn decode(&mut self, event: SseEvent, out: &mut Parts) {
+        if let Ok(error) = serde_json::from_str::<ErrorEnvelope>(&event.data) {
+            return out.fail(error.into_error(&self.provider));
+        }
+        let chunk: GenerateContentResponse = match serde_json::from_str(&event.data) {
+            Ok(chunk) => chunk,
+            Err(error) => return out.fail(error.into()),
+        };
+
+        if let Some(usage) = chunk.usage_metadata {
+            self.usage = usage.into();
+        }
+        if let Some(reason) = block_reason(&chunk) {
+            let reason = reason.to_string();
+            out.push(StreamPart::Metadata {
+                provider_metadata: metadata(json!({ "blockReason": reason })),
+            });
+            self.finish_reason = Some(FinishReason::PromptBlocked);
+        }
+
+        let Some(candidate) = chunk.candidates.into_iter().next() else {
+            return;
+        };
+        for part in candidate.content.map(|c| c.parts).unwrap_or_default() {
+            if let Some(call) = part.function_call {
+                let id = format!("call_{}", self.tool_calls);
+                self.tool_calls += 1;
+                out.push(StreamPart::ToolCallStart {
+                    id: id.clone(),
+                    tool_name: call.name,
+                });
+                out.push(StreamPart::ToolCallDelta {
+                    id: id.clone(),
+                    input_delta: call.args.to_string(),
+                });
+                out.push(StreamPart::ToolCallEnd { id });
+            } else if let Some(text) = part.text.filter(|text| !text.is_empty()) {
+                out.push(if part.thought {
+                    StreamPart::ReasoningDelta { text }
+                } else {
+                    StreamPart::TextDelta { text }
+                });
+            }
+        }
+        if let Some(grounding) = candidate.grounding_metadata {
+            let sources = grounding.sources(self.sources);
+            self.sources += sources.len();
+            for source in sources {
+                out.push(StreamPart::Source(source));
+            }
+        }
+        if let Some(reason) = candidate.finish_reason {
+            self.finish_reason = Some(map_finish_reason(&reason, self.tool_calls > 0));
+        }
+    }
+
+    /// Handles the end of the event stream, which has no terminal event.
+    fn end(&mut self, out: &mut Parts) {
+        match self.finish_reason {
+            Some(finish_reason) => out.push(StreamPart::Finish {
+                finish_reason,
+                usage: self.usage,
+            }),
+            None => out.fail(AiError::Stream(
+                "stream ended without a finish reason".into(),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::safety::{HarmBlockThreshold, HarmCategory};
+    use ai_core::{
+        generate_text, stream_text, GenerateTextRequest, StreamTextRequest, ToolDefinition,
+    };
+    use mockito::Matcher;
+
+    const FUNCTION_CALL_RESPONSE: &str = include_str!("../fixtures/generate_function_call.json");
+    const GROUNDED_STREAM: &str = include_str!("../fixtures/stream_grounded.sse");
+
+    fn weather_tool() -> ToolDefinition {
+        ToolDefinition::new(
+            "weather",
+            "Current weather for a city",
+            json!({
+                "type": "object",
+                "additionalProperties": false,
+                "properties": { "city": { "type": "string" } },
+                "required": ["city"],
+            }),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_generate_with_function_call() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/models/gemini-2.5-flash:generateContent")
+            .match_header("x-goog-api-key", "test-key")
+            .match_body(Matcher::PartialJson(json!({
+                "systemInstruction": { "parts": [{ "text": "Be brief." }] },
+                "contents": [{ "role": "user", "parts": [{ "text": "Weather in Paris?" }] }],
+                "generationConfig": { "temperature": 0.5 },
+                "safetySettings": [{
+                    "category": "HARM_CATEGORY_DANGEROUS_CONTENT",
+                    "threshold": "BLOCK_ONLY_HIGH",
+                }],
+                "tools": [{ "functionDeclarations": [{
+                    "name": "weather",
+                    "parameters": {
+                        "type": "object",
+                        "properties": { "city": { "type": "string" } },
+                        "required": ["city"],
+                    },
+                }] }],
+                "toolConfig": { "functionCallingConfig": {
+                    "mode": "ANY",
+                    "allowedFunctionNames": ["weather"],
+                } },
+            })))
+            .with_header("content-type", "application/json")
+            .with_body(FUNCTION_CALL_RESPONSE)
+            .create_async()
+            .await;
+
+        let safety = SafetySetting::new(
+            HarmCategory::DangerousContent,
+            HarmBlockThreshold::BlockOnlyHigh,
+        );
+        let model = GoogleProvider::new("test-key")
+            .with_base_url(server.url())
+            .generative_model("models/gemini-2.5-flash");
+        let response = generate_text(
+            GenerateTextRequest::new(model, "Weather in Paris?")
+                .system("Be brief.")
+                .temperature(0.5)
+                .tool(weather_tool())
+                .tool_choice(ToolChoice::Tool {
+                    tool_name: "weather".into(),
+                })
+                .provider_options("google", json!({ "safetySettings": [safety] })),
+        )
+        .await
+        .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(
+            response.warnings,
+            vec![CallWarning::other(
+                "tool 'weather': removed JSON Schema keywords unsupported by Gemini: additionalProperties"
+            )]
+        );
+        assert_eq!(response.finish_reason, FinishReason::ToolCalls);
+        assert_eq!(response.usage.output_tokens, 64);
+        assert_eq!(response.usage.reasoning_tokens, 48);
+
+        let calls = response.tool_calls();
+        assert_eq!(calls[0].tool_name, "weather");
+        assert_eq!(calls[0].input, json!({ "city": "Paris" }));
+
+        // The thought signature travels back with the function call.
+        let (_, contents) = convert_messages(&[response.to_message()]).unwrap();
+        assert_eq!(contents[0]["role"], "model");
+        assert_eq!(contents[0]["parts"][0]["thoughtSignature"], "CiQB0e2Kb7dW");
+    }
+
+    #[tokio::test]
+    async fn test_blocked_prompt_finish_reason() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/models/gemini-2.0-flash:generateContent")
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"promptFeedback":{"blockReason":"SAFETY","safetyRatings":[{"category":"HARM_CATEGORY_DANGEROUS_CONTENT","probability":"HIGH"}]},
+                "usageMetadata":{"promptTokenCount":9,"totalTokenCount":9}}"#,
+            )
+            .create_async()
+            .await;
+
+        let model = GoogleProvider::new("test-key")
+            .with_base_url(server.url())
+            .generative_model("gemini-2.0-flash");
+        let response = generate_text(GenerateTextRequest::new(model, "..."))
+            .await
+            .unwrap();
+
+        assert_eq!(response.finish_reason, FinishReason::PromptBlocked);
+        assert!(response.content.is_empty());
+        assert_eq!(response.usage, Usage::new(9, 0));
+        assert_eq!(
+            response.provider_metadata.unwrap()["google"],
+            json!({ "blockReason": "SAFETY" })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stream_with_grounding_sources() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/models/gemini-2.0-flash:streamGenerateContent")
+            .match_query(Matcher::UrlEncoded("alt".into(), "sse".into()))
+            .match_body(Matcher::PartialJson(
+                json!({ "tools": [{ "googleSearch": {} }] }),
+            ))
+            .with_header("content-type", "text/event-stream")
+            .with_body(GROUNDED_STREAM)
+            .create_async()
+            .await;
+
+        let model = GoogleProvider::new("test-key")
+            .with_base_url(server.url())
+            .generative_model("gemini-2.0-flash");
+        let handle = stream_text(
+            StreamTextRequest::new(model, "Who won Euro 2024?")
+                .provider_options("google", json!({ "useSearchGrounding": true })),
+        )
+        .await
+        .unwrap();
+        let result = handle.result().await.unwrap();
+
+        assert_eq!(result.text, "Spain won Euro 2024, beating England 2-1.");
+        assert_eq!(result.finish_reason, FinishReason::Stop);
+        assert_eq!(result.usage, Usage::new(12, 14));
+        assert_eq!(
+            result.sources,
+            vec![
+                Source {
+                    id: "source-0".into(),
+                    url: "https://www.uefa.com/euro2024/".into(),
+                    title: Some("uefa.com".into()),
+                },
+                Source {
+                    id: "source-1".into(),
+                    url: "https://en.wikipedia.org/wiki/UEFA_Euro_2024_final".into(),
+                    title: Some("wikipedia.org".into()),
+                },
+            ]
+        );
+    }
+}
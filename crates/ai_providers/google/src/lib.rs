@@ -1,14 +1,31 @@
-pub fn add(left: usize, right: usize) -> usize {
-    left + right
-}
+//! Google Generative AI provider for the AI SDK.
+//!
+//! [`GoogleGenerativeAiModel`] implements the Gemini `generateContent` and
+//! `streamGenerateContent` APIs, including function calling, safety settings
+//! and Google Search grounding.
+//!
+//! ```no_run
+//! use ai_core::{generate_text, GenerateTextRequest};
+//! use ai_providers_google::GoogleProvider;
+//!
+//! # async fn run() -> ai_error::Result<()> {
+//! let provider = GoogleProvider::from_env()?;
+//! let model = provider.generative_model("gemini-2.5-flash");
+//! let response = generate_text(GenerateTextRequest::new(model, "Hello!")).await?;
+//! println!("{}", response.text);
+//! # Ok(())
+//! # }
+//! ```
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+#![forbid(unsafe_code)]
+#![warn(missing_docs, rust_2018_idioms)]
 
-    #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
-    }
-}
+mod error;
+mod generative;
+mod provider;
+mod safety;
+mod schema;
+
+pub use generative::GoogleGenerativeAiModel;
+pub use provider::GoogleProvider;
+pub use safety::{HarmBlockThreshold, HarmCategory, SafetySetting};
@@ -0,0 +1,108 @@
+//! Provider configuration shared by all Google Generative AI models.
+
+use std::collections::HashMap;
+
+use ai_core::{LanguageModel, ModelProvider};
+use ai_error::{AiError, Result};
+
+/// Default endpoint of the Google Generative AI API.
+const DEFAULT_BASE_URL: &str = "https://generativelanguage.googleapis.com/v1beta";
+
+/// Entry point for Google Generative AI models.
+///
+/// Holds the credentials, endpoint and HTTP client used by every model
+/// created from it.
+#[derive(Debug, Clone)]
+pub struct GoogleProvider {
+    api_key: String,
+    base_url: String,
+    headers: HashMap<String, String>,
+    client: reqwest::Client,
+}
+
+impl GoogleProvider {
+    /// Creates a provider that authenticates with `api_key`.
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            api_key: api_key.into(),
+            base_url: DEFAULT_BASE_URL.to_string(),
+            headers: HashMap::new(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Creates a provider from `GOOGLE_GENERATIVE_AI_API_KEY`, and optionally
+    /// `GOOGLE_GENERATIVE_AI_BASE_URL`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AiError::Config`] if `GOOGLE_GENERATIVE_AI_API_KEY` is not set.
+    pub fn from_env() -> Result<Self> {
+        let api_key = std::env::var("GOOGLE_GENERATIVE_AI_API_KEY")
+            .map_err(|_| AiError::Config("GOOGLE_GENERATIVE_AI_API_KEY is not set".into()))?;
+
+        let mut provider = Self::new(api_key);
+        if let Ok(base_url) = std::env::var("GOOGLE_GENERATIVE_AI_BASE_URL") {
+            provider = provider.with_base_url(base_url);
+        }
+        Ok(provider)
+    }
+
+    /// Sends requests to `base_url` instead of the default endpoint.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Adds a header sent with every request.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.insert(name.into(), value.into());
+        self
+    }
+
+    /// Replaces the HTTP client, e.g. to send requests through a proxy.
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Builds an authenticated `POST` request to `path`, applying provider
+    /// headers followed by the per-call `headers`.
+    pub(crate) fn post(
+        &self,
+        path: &str,
+        headers: &HashMap<String, String>,
+    ) -> reqwest::RequestBuilder {
+        let mut request = self
+            .client
+            .post(format!("{}{}", self.base_url, path))
+            .header("x-goog-api-key", &self.api_key);
+
+        for (name, value) in self.headers.iter().chain(headers) {
+            request = request.header(name, value);
+        }
+        request
+    }
+}
+
+impl ModelProvider for GoogleProvider {
+    fn name(&self) -> &str {
+        "google"
+    }
+
+    fn language_model(&self, model_id: &str) -> Result<Box<dyn LanguageModel>> {
+        Ok(Box::new(self.generative_model(model_id)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base_url_is_normalized() {
+        let provider = GoogleProvider::new("key").with_base_url("http://localhost:1234/v1/");
+        assert_eq!(provider.base_url, "http://localhost:1234/v1");
+        assert_eq!(GoogleProvider::new("key").base_url, DEFAULT_BASE_URL);
+    }
+}
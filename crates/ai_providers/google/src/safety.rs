@@ -0,0 +1,76 @@
+//! Typed safety settings for the `"google"` provider options.
+
+use serde::{Deserialize, Serialize};
+
+/// Blocking threshold for one harm category.
+///
+/// Passed in the `safetySettings` array of the `"google"` provider options:
+///
+/// ```
+/// use ai_providers_google::{HarmBlockThreshold, HarmCategory, SafetySetting};
+/// use serde_json::json;
+///
+/// let options = json!({
+///     "safetySettings": [
+///         SafetySetting::new(HarmCategory::Harassment, HarmBlockThreshold::BlockOnlyHigh),
+///     ],
+/// });
+/// assert_eq!(
+///     options["safetySettings"][0]["category"],
+///     "HARM_CATEGORY_HARASSMENT"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SafetySetting {
+    /// Category the threshold applies to.
+    pub category: HarmCategory,
+    /// Probability at and above which content is blocked.
+    pub threshold: HarmBlockThreshold,
+}
+
+impl SafetySetting {
+    /// Creates a safety setting.
+    pub fn new(category: HarmCategory, threshold: HarmBlockThreshold) -> Self {
+        Self {
+            category,
+            threshold,
+        }
+    }
+}
+
+/// Harm category rated by Gemini's safety filter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum HarmCategory {
+    /// Negative or harmful comments targeting identity or protected
+    /// attributes.
+    #[serde(rename = "HARM_CATEGORY_HARASSMENT")]
+    Harassment,
+    /// Rude, disrespectful or profane content.
+    #[serde(rename = "HARM_CATEGORY_HATE_SPEECH")]
+    HateSpeech,
+    /// References to sexual acts or other lewd content.
+    #[serde(rename = "HARM_CATEGORY_SEXUALLY_EXPLICIT")]
+    SexuallyExplicit,
+    /// Content that promotes or facilitates harmful acts.
+    #[serde(rename = "HARM_CATEGORY_DANGEROUS_CONTENT")]
+    DangerousContent,
+    /// Content that may be used to harm civic integrity.
+    #[serde(rename = "HARM_CATEGORY_CIVIC_INTEGRITY")]
+    CivicIntegrity,
+}
+
+/// Probability at and above which content is blocked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum HarmBlockThreshold {
+    /// Block content with a low or higher probability of harm.
+    BlockLowAndAbove,
+    /// Block content with a medium or higher probability of harm.
+    BlockMediumAndAbove,
+    /// Block only content with a high probability of harm.
+    BlockOnlyHigh,
+    /// Never block, but still report safety ratings.
+    BlockNone,
+    /// Turn the safety filter off for the category.
+    Off,
+}
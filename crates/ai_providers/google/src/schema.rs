@@ -0,0 +1,167 @@
+//! Conversion of JSON Schemas into the OpenAPI subset Gemini accepts.
+//!
+//! Function parameters are validated against a restricted schema dialect:
+//! keywords such as `additionalProperties`, `$schema` or `oneOf` are rejected
+//! outright. They are stripped here and reported so the caller can warn.
+
+use std::collections::BTreeSet;
+
+use serde_json::{Map, Value};
+
+/// Keywords Gemini accepts in function parameter schemas.
+const SUPPORTED_KEYWORDS: &[&str] = &[
+    "type",
+    "format",
+    "title",
+    "description",
+    "nullable",
+    "enum",
+    "default",
+    "properties",
+    "required",
+    "propertyOrdering",
+    "minProperties",
+    "maxProperties",
+    "items",
+    "minItems",
+    "maxItems",
+    "minimum",
+    "maximum",
+    "minLength",
+    "maxLength",
+    "pattern",
+    "anyOf",
+];
+
+/// Converts `schema`, adding the name of every keyword it strips to
+/// `removed`.
+///
+/// `"type": ["string", "null"]` becomes `"type": "string", "nullable": true`
+/// and `const` becomes a single-value `enum`; everything else Gemini does
+/// not understand is dropped.
+pub(crate) fn convert_schema(schema: &Value, removed: &mut BTreeSet<String>) -> Value {
+    let Value::Object(fields) = schema else {
+        return schema.clone();
+    };
+
+    let mut converted = Map::new();
+    for (keyword, value) in fields {
+        match keyword.as_str() {
+            "type" => convert_type(value, &mut converted, removed),
+            "const" => {
+                converted.insert("enum".into(), Value::Array(vec![value.clone()]));
+            }
+            "properties" => {
+                let properties = value
+                    .as_object()
+                    .into_iter()
+                    .flatten()
+                    .map(|(name, property)| (name.clone(), convert_schema(property, removed)))
+                    .collect();
+                converted.insert(keyword.clone(), Value::Object(properties));
+            }
+            "items" => {
+                converted.insert(keyword.clone(), convert_schema(value, removed));
+            }
+            "anyOf" => {
+                let variants = value
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .map(|variant| convert_schema(variant, removed))
+                    .collect();
+                converted.insert(keyword.clone(), Value::Array(variants));
+            }
+            _ if SUPPORTED_KEYWORDS.contains(&keyword.as_str()) => {
+                converted.insert(keyword.clone(), value.clone());
+            }
+            _ => {
+                removed.insert(keyword.clone());
+            }
+        }
+    }
+    Value::Object(converted)
+}
+
+/// Converts a `type` that may list several types, folding `"null"` into
+/// `nullable`.
+fn convert_type(value: &Value, converted: &mut Map<String, Value>, removed: &mut BTreeSet<String>) {
+    let Value::Array(types) = value else {
+        converted.insert("type".into(), value.clone());
+        return;
+    };
+
+    let non_null: Vec<&Value> = types
+        .iter()
+        .filter(|t| t.as_str() != Some("null"))
+        .collect();
+    if non_null.len() < types.len() {
+        converted.insert("nullable".into(), Value::Bool(true));
+    }
+    match non_null.as_slice() {
+        [single] => {
+            converted.insert("type".into(), (*single).clone());
+        }
+        // Gemini has no union types; leave the value unconstrained.
+        _ => {
+            removed.insert("type".into());
+        }
+    }
+}
+
+/// Returns whether `schema` is an object schema without any properties,
+/// which Gemini rejects as function parameters.
+pub(crate) fn is_empty_object(schema: &Value) -> bool {
+    schema.get("type").and_then(Value::as_str) == Some("object")
+        && schema
+            .get("properties")
+            .and_then(Value::as_object)
+            .map_or(true, Map::is_empty)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_convert_schema_strips_unsupported_keywords() {
+        let schema = json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "city": { "type": "string", "minLength": 1 },
+                "unit": { "type": ["string", "null"], "enum": ["celsius", "fahrenheit"] },
+                "days": { "type": "array", "items": { "type": "integer", "exclusiveMinimum": 0 } },
+                "mode": { "const": "fast" },
+            },
+            "required": ["city"],
+        });
+
+        let mut removed = BTreeSet::new();
+        let converted = convert_schema(&schema, &mut removed);
+
+        assert_eq!(
+            converted,
+            json!({
+                "type": "object",
+                "properties": {
+                    "city": { "type": "string", "minLength": 1 },
+                    "unit": { "type": "string", "nullable": true, "enum": ["celsius", "fahrenheit"] },
+                    "days": { "type": "array", "items": { "type": "integer" } },
+                    "mode": { "enum": ["fast"] },
+                },
+                "required": ["city"],
+            })
+        );
+        assert_eq!(
+            removed.into_iter().collect::<Vec<_>>(),
+            ["$schema", "additionalProperties", "exclusiveMinimum"]
+        );
+        assert!(is_empty_object(
+            &json!({ "type": "object", "properties": {} })
+        ));
+        assert!(!is_empty_object(&converted));
+    }
+}
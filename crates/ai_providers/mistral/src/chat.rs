@@ -0,0 +1,843 @@
+//! Text generation through Mistral's chat completions endpoint.
+
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use futures::stream::StreamExt;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::error::{map_error, ErrorBody};
+use crate::provider::MistralProvider;
+use ai_core::{
+    CallOptions, CallWarning, FinishReason, LanguageModel, Message, MessagePart, MessageRole,
+    ModelCapabilities, ModelProvider, ModelResponse, ModelStream, ReasoningPart, StreamPart,
+    ToolCallPart, ToolChoice, Usage,
+};
+use ai_error::{ensure_success, AiError, Result};
+use ai_stream::sse::{self, SseEvent};
+use ai_stream::{decode_events, Emitter, EventDecoder};
+
+/// Length of the tool call ids Mistral accepts.
+const TOOL_CALL_ID_LEN: usize = 9;
+
+/// A Mistral chat model such as `mistral-large-latest`, `pixtral-12b` or
+/// `magistral-medium-latest`.
+///
+/// Recognized `"mistral"` provider options:
+///
+/// - `safePrompt`: prepend Mistral's safety system prompt.
+/// - `parallelToolCalls`: allow several tool calls per turn.
+/// - `responseFormat`: `{ "type": "text" }`, `{ "type": "json_object" }` or
+///   `{ "type": "json_schema", "jsonSchema": { "name": …, "schema": …,
+///   "strict": … } }`. Other modes are ignored with a warning.
+///
+/// Mistral only accepts tool call ids of nine alphanumeric characters.
+/// Ids from other providers in the conversation history are mapped onto
+/// such ids deterministically, so calls and their results still match.
+#[derive(Debug, Clone)]
+pub struct MistralChatModel {
+    provider: MistralProvider,
+    model_id: String,
+}
+
+impl MistralProvider {
+    /// Creates a chat model with the given identifier.
+    pub fn chat_model(&self, model_id: impl Into<String>) -> MistralChatModel {
+        MistralChatModel {
+            provider: self.clone(),
+            model_id: model_id.into(),
+        }
+    }
+
+    /// Shorthand for [`chat_model`](Self::chat_model).
+    pub fn model(&self, model_id: impl Into<String>) -> MistralChatModel {
+        self.chat_model(model_id)
+    }
+}
+
+/// Typed view of the `"mistral"` provider options.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MistralOptions {
+    safe_prompt: Option<bool>,
+    parallel_tool_calls: Option<bool>,
+    response_format: Option<ResponseFormat>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ResponseFormat {
+    #[serde(rename = "type")]
+    kind: String,
+    json_schema: Option<JsonSchemaFormat>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonSchemaFormat {
+    name: String,
+    schema: Value,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    strict: Option<bool>,
+}
+
+impl ResponseFormat {
+    /// Converts the format into `response_format`. Modes Mistral does not
+    /// offer (e.g. `"grammar"` or `"regex"`) are dropped with a warning.
+    fn convert(self, warnings: &mut Vec<CallWarning>) -> Result<Option<Value>> {
+        match self.kind.as_str() {
+            "text" | "json_object" => Ok(Some(json!({ "type": self.kind }))),
+            "json_schema" => {
+                let format = self.json_schema.ok_or_else(|| {
+                    AiError::Validation("responseFormat json_schema requires jsonSchema".into())
+                })?;
+                let mut json_schema = json!({ "name": format.name, "schema": format.schema });
+                if let Some(description) = format.description {
+                    json_schema["description"] = json!(description);
+                }
+                if let Some(strict) = format.strict {
+                    json_schema["strict"] = json!(strict);
+                }
+                Ok(Some(
+                    json!({ "type": "json_schema", "json_schema": json_schema }),
+                ))
+            }
+            kind => {
+                warnings.push(CallWarning::UnsupportedSetting {
+                    setting: "response_format".into(),
+                    details: Some(format!(
+                        "response format '{kind}' is not supported by Mistral"
+                    )),
+                });
+                Ok(None)
+            }
+        }
+    }
+}
+
+impl MistralChatModel {
+    /// Builds the request body and the warnings for settings it drops.
+    fn request_body(
+        &self,
+        options: &CallOptions,
+        stream: bool,
+    ) -> Result<(Value, Vec<CallWarning>)> {
+        let settings = &options.settings;
+        let provider_options: MistralOptions = options.provider_options(self.provider.name())?;
+        let mut warnings = settings.unsupported_warnings(&[
+            "temperature",
+            "top_p",
+            "max_output_tokens",
+            "stop_sequences",
+            "seed",
+            "presence_penalty",
+            "frequency_penalty",
+        ]);
+        warnings.extend(options.cache_control_warning());
+
+        let mut body = json!({
+            "model": self.model_id,
+            "messages": convert_messages(&options.messages)?,
+            "temperature": settings.temperature,
+            "top_p": settings.top_p,
+            "max_tokens": settings.max_output_tokens,
+            "random_seed": settings.seed,
+            "presence_penalty": settings.presence_penalty,
+            "frequency_penalty": settings.frequency_penalty,
+            "safe_prompt": provider_options.safe_prompt,
+            "parallel_tool_calls": provider_options.parallel_tool_calls,
+        });
+        if !settings.stop_sequences.is_empty() {
+            body["stop"] = json!(settings.stop_sequences);
+        }
+
+        if !options.tools.is_empty() {
+            let tools: Vec<Value> = options
+                .tools
+                .iter()
+                .map(|tool| {
+                    json!({
+                        "type": "function",
+                        "function": {
+                            "name": tool.name,
+                            "description": tool.description,
+                            "parameters": tool.input_schema,
+                        },
+                    })
+                })
+                .collect();
+            body["tools"] = json!(tools);
+        }
+        if let Some(choice) = &options.tool_choice {
+            body["tool_choice"] = match choice {
+                ToolChoice::Auto => json!("auto"),
+                ToolChoice::None => json!("none"),
+                ToolChoice::Required => json!("any"),
+                ToolChoice::Tool { tool_name } => {
+                    json!({ "type": "function", "function": { "name": tool_name } })
+                }
+            };
+        }
+
+        if let Some(format) = provider_options.response_format {
+            body["response_format"] = json!(format.convert(&mut warnings)?);
+        }
+        if stream {
+            body["stream"] = json!(true);
+        }
+
+        // Send only the settings the caller actually set.
+        if let Value::Object(fields) = &mut body {
+            fields.retain(|_, value| !value.is_null());
+        }
+        Ok((body, warnings))
+    }
+
+    async fn send(&self, options: &CallOptions, body: &Value) -> Result<reqwest::Response> {
+        let response = self
+            .provider
+            .post("/chat/completions", &options.headers)
+            .json(body)
+            .send()
+            .await?;
+        ensure_success(self.provider.name(), response, map_error).await
+    }
+}
+
+/// Maps a tool call id onto the nine alphanumeric characters Mistral
+/// requires.
+///
+/// Conforming ids are kept. Others are hashed (64-bit FNV-1a) into base 62,
+/// so the same id always maps to the same replacement and a call still
+/// matches its result.
+fn normalize_tool_call_id(id: &str) -> String {
+    if id.len() == TOOL_CALL_ID_LEN && id.bytes().all(|b| b.is_ascii_alphanumeric()) {
+        return id.to_string();
+    }
+
+    const ALPHABET: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+    let mut hash = id.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    (0..TOOL_CALL_ID_LEN)
+        .map(|_| {
+            let digit = ALPHABET[(hash % 62) as usize];
+            hash /= 62;
+            digit as char
+        })
+        .collect()
+}
+
+/// Converts messages into Mistral's chat wire format.
+fn convert_messages(messages: &[Message]) -> Result<Vec<Value>> {
+    let mut converted = Vec::with_capacity(messages.len());
+    for message in messages {
+        match message.role {
+            MessageRole::System => {
+                converted.push(json!({ "role": "system", "content": message.text() }));
+            }
+            MessageRole::User => {
+                let text_only = message
+                    .parts
+                    .iter()
+                    .all(|part| matches!(part, MessagePart::Text(_)));
+                let content = if text_only {
+                    json!(message.text())
+                } else {
+                    let parts = message
+                        .parts
+                        .iter()
+                        .map(convert_user_part)
+                        .collect::<Result<Vec<_>>>()?;
+                    json!(parts)
+                };
+                converted.push(json!({ "role": "user", "content": content }));
+            }
+            MessageRole::Assistant => {
+                let tool_calls: Vec<Value> = message
+                    .parts
+                    .iter()
+                    .filter_map(|part| match part {
+                        MessagePart::ToolCall(call) => Some(json!({
+                            "id": normalize_tool_call_id(&call.tool_call_id),
+                            "type": "function",
+                            "function": {
+                                "name": call.tool_name,
+                                "arguments": call.input.to_string(),
+                            },
+                        })),
+                        _ => None,
+                    })
+                    .collect();
+
+                let mut assistant = json!({ "role": "assistant", "content": message.text() });
+                if !tool_calls.is_empty() {
+                    assistant["tool_calls"] = json!(tool_calls);
+                }
+                converted.push(assistant);
+            }
+            MessageRole::Tool => {
+                for part in &message.parts {
+                    if let MessagePart::ToolResult(result) = part {
+                        let content = match &result.output {
+                            Value::String(text) => text.clone(),
+                            output => output.to_string(),
+                        };
+                        converted.push(json!({
+                            "role": "tool",
+                            "tool_call_id": normalize_tool_call_id(&result.tool_call_id),
+                            "name": result.tool_name,
+                            "content": content,
+                        }));
+                    }
+                }
+            }
+        }
+    }
+    Ok(converted)
+}
+
+fn convert_user_part(part: &MessagePart) -> Result<Value> {
+    match part {
+        MessagePart::Text(text) => Ok(json!({ "type": "text", "text": text.text })),
+        MessagePart::Image(image) => {
+            let media_type = image.media_type.as_deref().unwrap_or("image/jpeg");
+            Ok(json!({ "type": "image_url", "image_url": image.image.to_url(media_type) }))
+        }
+        MessagePart::File(file) if file.media_type == "application/pdf" => Ok(json!({
+            "type": "document_url",
+            "document_url": file.data.to_url(&file.media_type),
+        })),
+        MessagePart::File(file) => Err(AiError::Validation(format!(
+            "unsupported file media type '{}'",
+            file.media_type
+        ))),
+        _ => Err(AiError::Validation(
+            "user messages may only contain text, image and file parts".into(),
+        )),
+    }
+}
+
+/// Splits message content into text and reasoning.
+///
+/// Content is a plain string, or for reasoning (Magistral) models a list of
+/// `text` and `thinking` chunks.
+fn split_content(content: Option<Value>) -> (String, String) {
+    let mut text = String::new();
+    let mut reasoning = String::new();
+    match content {
+        Some(Value::String(content)) => text = content,
+        Some(Value::Array(chunks)) => {
+            for chunk in chunks {
+                match chunk.get("type").and_then(Value::as_str) {
+                    Some("text") => text.push_str(chunk["text"].as_str().unwrap_or_default()),
+                    Some("thinking") => {
+                        for part in chunk["thinking"].as_array().into_iter().flatten() {
+                            reasoning.push_str(part["text"].as_str().unwrap_or_default());
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        _ => {}
+    }
+    (text, reasoning)
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatResponse {
+    choices: Vec<ChatChoice>,
+    #[serde(default)]
+    usage: Option<ChatUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChoice {
+    message: ChatMessage,
+    #[serde(default)]
+    finish_reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatMessage {
+    #[serde(default)]
+    content: Option<Value>,
+    #[serde(default)]
+    tool_calls: Option<Vec<ChatToolCall>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatToolCall {
+    id: String,
+    function: ChatFunction,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatFunction {
+    name: String,
+    arguments: Value,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ChatUsage {
+    #[serde(default)]
+    prompt_tokens: u64,
+    #[serde(default)]
+    completion_tokens: u64,
+}
+
+impl From<ChatUsage> for Usage {
+    fn from(usage: ChatUsage) -> Self {
+        Usage::new(usage.prompt_tokens, usage.completion_tokens)
+    }
+}
+
+fn map_finish_reason(reason: Option<&str>) -> FinishReason {
+    match reason {
+        Some("stop") => FinishReason::Stop,
+        Some("length" | "model_length") => FinishReason::Length,
+        Some("tool_calls") => FinishReason::ToolCalls,
+        Some("error") => FinishReason::Error,
+        Some(_) => FinishReason::Other,
+        None => FinishReason::Unknown,
+    }
+}
+
+/// Parses tool call arguments, which Mistral sends as a JSON string or,
+/// from some deployments, as an object.
+fn parse_arguments(arguments: Value) -> Value {
+    match arguments {
+        Value::String(arguments) if arguments.trim().is_empty() => {
+            Value::Object(Default::default())
+        }
+        Value::String(arguments) => {
+            serde_json::from_str(&arguments).unwrap_or(Value::String(arguments))
+        }
+        arguments => arguments,
+    }
+}
+
+#[async_trait]
+impl LanguageModel for MistralChatModel {
+    fn provider(&self) -> &str {
+        self.provider.name()
+    }
+
+    fn model_id(&self) -> &str {
+        &self.model_id
+    }
+
+    fn capabilities(&self) -> ModelCapabilities {
+        let vision = ["pixtral", "mistral-small", "mistral-medium"]
+            .iter()
+            .any(|prefix| self.model_id.starts_with(prefix));
+        ModelCapabilities {
+            tool_calling: true,
+            structured_outputs: true,
+            image_input: vision,
+            file_input: vision,
+            reasoning: self.model_id.starts_with("magistral"),
+            streaming: true,
+            max_context_tokens: None,
+        }
+    }
+
+    async fn do_generate(&self, options: CallOptions) -> Result<ModelResponse> {
+        let (body, warnings) = self.request_body(&options, false)?;
+        let response: ChatResponse = self.send(&options, &body).await?.json().await?;
+
+        let choice = response
+            .choices
+            .into_iter()
+            .next()
+            .ok_or_else(|| AiError::Provider {
+                provider: self.provider.name().to_string(),
+                message: "response contained no choices".into(),
+                code: None,
+                details: None,
+            })?;
+
+        let mut content = Vec::new();
+        let (text, reasoning) = split_content(choice.message.content);
+        if !reasoning.is_empty() {
+            content.push(MessagePart::Reasoning(ReasoningPart {
+                text: reasoning,
+                provider_metadata: None,
+            }));
+        }
+        if !text.is_empty() {
+            content.push(MessagePart::text(text));
+        }
+        for call in choice.message.tool_calls.unwrap_or_default() {
+            content.push(MessagePart::ToolCall(ToolCallPart {
+                tool_call_id: call.id,
+                tool_name: call.function.name,
+                input: parse_arguments(call.function.arguments),
+                provider_metadata: None,
+            }));
+        }
+
+        Ok(ModelResponse {
+            content,
+            finish_reason: map_finish_reason(choice.finish_reason.as_deref()),
+            usage: response.usage.map(Usage::from).unwrap_or_default(),
+            sources: Vec::new(),
+            warnings,
+            provider_metadata: None,
+        })
+    }
+
+    async fn do_stream(&self, options: CallOptions) -> Result<ModelStream> {
+        let (body, warnings) = self.request_body(&options, true)?;
+        let response = self.send(&options, &body).await?;
+
+        let events = sse::decode(response.bytes_stream()).boxed();
+        let state = ChunkState::new(self.provider.name());
+        Ok(ModelStream {
+            stream: decode_events(events, state).boxed(),
+            warnings,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChunk {
+    #[serde(default)]
+    object: Option<String>,
+    #[serde(default)]
+    choices: Vec<ChunkChoice>,
+    #[serde(default)]
+    usage: Option<ChatUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChunkChoice {
+    #[serde(default)]
+    delta: ChunkDelta,
+    #[serde(default)]
+    finish_reason: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ChunkDelta {
+    #[serde(default)]
+    content: Option<Value>,
+    #[serde(default)]
+    tool_calls: Option<Vec<ToolCallChunk>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ToolCallChunk {
+    #[serde(default)]
+    index: Option<usize>,
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    function: Option<FunctionChunk>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FunctionChunk {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    arguments: Option<Value>,
+}
+
+type Parts = Emitter<StreamPart, AiError>;
+
+/// Assembles chunks into [`StreamPart`]s.
+///
+/// Mistral usually sends each tool call whole in a single chunk, but calls
+/// are keyed by `index` so split arguments are appended as well.
+struct ChunkState {
+    provider: String,
+    tool_call_ids: BTreeMap<usize, String>,
+    finish_reason: FinishReason,
+    usage: Usage,
+}
+
+impl ChunkState {
+    fn new(provider: &str) -> Self {
+        Self {
+            provider: provider.to_string(),
+            tool_call_ids: BTreeMap::new(),
+            finish_reason: FinishReason::Unknown,
+            usage: Usage::default(),
+        }
+    }
+
+    /// Handles one tool call chunk. A chunk without `index` always starts a
+    /// new call.
+    fn process_tool_call(&mut self, call: ToolCallChunk, out: &mut Parts) {
+        let function = call.function.unwrap_or(FunctionChunk {
+            name: None,
+            arguments: None,
+        });
+        let index = call.index.unwrap_or(self.tool_call_ids.len());
+
+        let id = match self.tool_call_ids.get(&index) {
+            Some(id) if call.index.is_some() => id.clone(),
+            _ => {
+                let (Some(id), Some(tool_name)) = (call.id, function.name) else {
+                    return out.fail(AiError::Provider {
+                        provider: self.provider.clone(),
+                        message: format!("tool call {index} started without id or name"),
+                        code: None,
+                        details: None,
+                    });
+                };
+                self.tool_call_ids.insert(index, id.clone());
+                out.push(StreamPart::ToolCallStart {
+                    id: id.clone(),
+                    tool_name,
+                });
+                id
+            }
+        };
+
+        let input_delta = match function.arguments {
+            Some(Value::String(arguments)) => arguments,
+            Some(Value::Null) | None => String::new(),
+            Some(arguments) => arguments.to_string(),
+        };
+        if !input_delta.is_empty() {
+            out.push(StreamPart::ToolCallDelta { id, input_delta });
+        }
+    }
+}
+
+impl EventDecoder for ChunkState {
+    type Event = SseEvent;
+    type Item = StreamPart;
+    type Error = AiError;
+
+    fn decode(&mut self, event: SseEvent, out: &mut Parts) {
+        if event.data == "[DONE]" {
+            self.end(out);
+            return out.close();
+        }
+
+        let chunk: ChatChunk = match serde_json::from_str(&event.data) {
+            Ok(chunk) => chunk,
+            Err(error) => return out.fail(error.into()),
+        };
+        if chunk.object.as_deref() == Some("error") {
+            let error = match serde_json::from_str::<ErrorBody>(&event.data) {
+                Ok(body) => body.into_error(&self.provider),
+                Err(error) => error.into(),
+            };
+            return out.fail(error);
+        }
+        if let Some(usage) = chunk.usage {
+            self.usage = usage.into();
+        }
+
+        for choice in chunk.choices {
+            let delta = choice.delta;
+            let (text, reasoning) = split_content(delta.content);
+            if !reasoning.is_empty() {
+                out.push(StreamPart::ReasoningDelta { text: reasoning });
+            }
+            if !text.is_empty() {
+                out.push(StreamPart::TextDelta { text });
+            }
+            for call in delta.tool_calls.into_iter().flatten() {
+                self.process_tool_call(call, out);
+            }
+            if let Some(reason) = choice.finish_reason {
+                self.finish_reason = map_finish_reason(Some(&reason));
+            }
+        }
+    }
+
+    /// Closes open tool calls and emits the final event. Self-hosted servers
+    /// may close the stream without `[DONE]`, so this also runs then.
+    fn end(&mut self, out: &mut Parts) {
+        for id in std::mem::take(&mut self.tool_call_ids).into_values() {
+            out.push(StreamPart::ToolCallEnd { id });
+        }
+        out.push(StreamPart::Finish {
+            finish_reason: self.finish_reason,
+            usage: self.usage,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ai_core::{
+        generate_text, stream_text, GenerateTextRequest, StreamTextRequest, ToolDefinition,
+        ToolResultPart,
+    };
+    use mockito::Matcher;
+
+    fn weather_tool() -> ToolDefinition {
+        ToolDefinition::new(
+            "weather",
+            "Current weather for a city",
+            json!({ "type": "object", "properties": { "city": { "type": "string" } } }),
+        )
+    }
+
+    #[test]
+    fn test_foreign_tool_call_ids_are_normalized_consistently() {
+        assert_eq!(normalize_tool_call_id("D681PevKs"), "D681PevKs");
+
+        let id = normalize_tool_call_id("toolu_01T1x1fJ34qAmk2tNTrN7Up6");
+        assert_eq!(id.len(), TOOL_CALL_ID_LEN);
+        assert!(id.bytes().all(|b| b.is_ascii_alphanumeric()));
+        assert_ne!(id, normalize_tool_call_id("call_abc123"));
+
+        let messages = vec![
+            Message::user("Weather in Paris?"),
+            Message::new(
+                MessageRole::Assistant,
+                vec![MessagePart::ToolCall(ToolCallPart {
+                    tool_call_id: "toolu_01T1x1fJ34qAmk2tNTrN7Up6".into(),
+                    tool_name: "weather".into(),
+                    input: json!({ "city": "Paris" }),
+                    provider_metadata: None,
+                })],
+            ),
+            Message::tool([ToolResultPart {
+                tool_call_id: "toolu_01T1x1fJ34qAmk2tNTrN7Up6".into(),
+                tool_name: "weather".into(),
+                output: json!({ "temperature": 21 }),
+                is_error: false,
+                cache_control: None,
+                provider_metadata: None,
+            }]),
+        ];
+        let converted = convert_messages(&messages).unwrap();
+        assert_eq!(converted[1]["tool_calls"][0]["id"], id);
+        assert_eq!(converted[2]["tool_call_id"], id);
+        assert_eq!(converted[2]["name"], "weather");
+    }
+
+    #[tokio::test]
+    async fn test_generate_with_safe_prompt_and_response_format() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/v1/chat/completions")
+            .match_header("authorization", Matcher::Missing)
+            .match_body(Matcher::Json(json!({
+                "model": "mistral-small-latest",
+                "messages": [{ "role": "user", "content": "List two colors as JSON." }],
+                "max_tokens": 64,
+                "random_seed": 7,
+                "safe_prompt": true,
+                "response_format": { "type": "json_object" },
+            })))
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "id": "cmpl-e5cc70bb28c444948073e77776eb30ef",
+                    "object": "chat.completion",
+                    "model": "mistral-small-latest",
+                    "choices": [{
+                        "index": 0,
+                        "message": { "role": "assistant", "content": "{\"colors\":[\"red\",\"blue\"]}", "tool_calls": null },
+                        "finish_reason": "stop",
+                    }],
+                    "usage": { "prompt_tokens": 14, "completion_tokens": 11, "total_tokens": 25 },
+                })
+                .to_string(),
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let model = MistralProvider::self_hosted(format!("{}/v1", server.url()))
+            .chat_model("mistral-small-latest");
+        let mut request = GenerateTextRequest::new(model, "List two colors as JSON.")
+            .max_output_tokens(64)
+            .provider_options(
+                "mistral",
+                json!({ "safePrompt": true, "responseFormat": { "type": "json_object" } }),
+            );
+        request.settings.seed = Some(7);
+        request.settings.top_k = Some(40);
+        let response = generate_text(request).await.unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(response.text, "{\"colors\":[\"red\",\"blue\"]}");
+        assert_eq!(response.finish_reason, FinishReason::Stop);
+        assert_eq!(response.usage, Usage::new(14, 11));
+        assert_eq!(
+            response.warnings,
+            vec![CallWarning::unsupported_setting("top_k")]
+        );
+
+        let model = MistralProvider::new("key").chat_model("mistral-small-latest");
+        let mut options = CallOptions {
+            messages: vec![Message::user("Hi")],
+            ..Default::default()
+        };
+        options.provider_options.insert(
+            "mistral".into(),
+            json!({ "responseFormat": { "type": "grammar" } }),
+        );
+        let (body, warnings) = model.request_body(&options, false).unwrap();
+        assert!(body.get("response_format").is_none());
+        assert!(matches!(
+            &warnings[..],
+            [CallWarning::UnsupportedSetting { setting, details: Some(_) }] if setting == "response_format"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_stream_text_and_tool_call() {
+        let chunks = [
+            json!({ "choices": [{ "index": 0, "delta": { "role": "assistant", "content": "" } }] }),
+            json!({ "choices": [{ "index": 0, "delta": { "content": "Checking." } }] }),
+            json!({ "choices": [{
+                "index": 0,
+                "delta": { "tool_calls": [{ "id": "D681PevKs", "function": { "name": "weather", "arguments": "{\"city\": \"Paris\"}" }, "index": 0 }] },
+                "finish_reason": "tool_calls",
+            }], "usage": { "prompt_tokens": 86, "completion_tokens": 23, "total_tokens": 109 } }),
+        ];
+        let mut body = String::new();
+        for chunk in &chunks {
+            body.push_str(&format!("data: {chunk}\n\n"));
+        }
+        body.push_str("data: [DONE]\n\n");
+
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/chat/completions")
+            .match_header("authorization", "Bearer test-key")
+            .match_body(Matcher::PartialJson(json!({
+                "stream": true,
+                "tool_choice": "any",
+                "tools": [{ "type": "function", "function": { "name": "weather" } }],
+            })))
+            .with_header("content-type", "text/event-stream")
+            .with_body(body)
+            .create_async()
+            .await;
+
+        let model = MistralProvider::new("test-key")
+            .with_base_url(server.url())
+            .chat_model("mistral-large-latest");
+        let handle = stream_text(
+            StreamTextRequest::new(model, "Weather in Paris?")
+                .tool(weather_tool())
+                .tool_choice(ToolChoice::Required),
+        )
+        .await
+        .unwrap();
+        let result = handle.result().await.unwrap();
+
+        assert_eq!(result.text, "Checking.");
+        assert_eq!(result.tool_calls.len(), 1);
+        assert_eq!(result.tool_calls[0].tool_call_id, "D681PevKs");
+        assert_eq!(result.tool_calls[0].input, json!({ "city": "Paris" }));
+        assert_eq!(result.finish_reason, FinishReason::ToolCalls);
+        assert_eq!(result.usage, Usage::new(86, 23));
+    }
+}
@@ -0,0 +1,168 @@
+//! Embeddings through Mistral's `/embeddings` endpoint.
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::error::map_error;
+use crate::provider::MistralProvider;
+use ai_core::settings::parse_provider_options;
+use ai_core::{EmbedOptions, Embedding, EmbeddingModel, EmbeddingResponse, ModelProvider, Usage};
+use ai_error::{ensure_success, AiError, Result};
+
+/// An embedding model such as `mistral-embed` or `codestral-embed`.
+///
+/// `codestral-embed` reads `outputDimension` and `outputDtype` from the
+/// `"mistral"` provider options.
+#[derive(Debug, Clone)]
+pub struct MistralEmbeddingModel {
+    provider: MistralProvider,
+    model_id: String,
+}
+
+impl MistralProvider {
+    /// Creates an embedding model with the given identifier.
+    pub fn embedding_model(&self, model_id: impl Into<String>) -> MistralEmbeddingModel {
+        MistralEmbeddingModel {
+            provider: self.clone(),
+            model_id: model_id.into(),
+        }
+    }
+}
+
+/// Typed view of the `"mistral"` provider options.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EmbedProviderOptions {
+    output_dimension: Option<u32>,
+    output_dtype: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbedResponse {
+    data: Vec<EmbeddingData>,
+    #[serde(default)]
+    usage: Option<EmbedUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingData {
+    embedding: Embedding,
+    #[serde(default)]
+    index: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbedUsage {
+    #[serde(default)]
+    prompt_tokens: u64,
+}
+
+#[async_trait]
+impl EmbeddingModel for MistralEmbeddingModel {
+    fn provider(&self) -> &str {
+        self.provider.name()
+    }
+
+    fn model_id(&self) -> &str {
+        &self.model_id
+    }
+
+    fn max_embeddings_per_call(&self) -> Option<usize> {
+        None
+    }
+
+    async fn do_embed(&self, options: EmbedOptions) -> Result<EmbeddingResponse> {
+        let provider_options: EmbedProviderOptions =
+            parse_provider_options(&options.provider_options, self.provider.name())?;
+
+        let mut body = json!({
+            "model": self.model_id,
+            "input": options.values,
+            "output_dimension": provider_options.output_dimension,
+            "output_dtype": provider_options.output_dtype,
+        });
+        if let Value::Object(fields) = &mut body {
+            fields.retain(|_, value| !value.is_null());
+        }
+
+        let response = self
+            .provider
+            .post("/embeddings", &options.headers)
+            .json(&body)
+            .send()
+            .await?;
+        let mut response: EmbedResponse = ensure_success(self.provider.name(), response, map_error)
+            .await?
+            .json()
+            .await?;
+
+        if response.data.len() != options.values.len() {
+            return Err(AiError::Provider {
+                provider: self.provider.name().to_string(),
+                message: format!(
+                    "expected {} embeddings, got {}",
+                    options.values.len(),
+                    response.data.len()
+                ),
+                code: None,
+                details: None,
+            });
+        }
+        response.data.sort_by_key(|data| data.index);
+        Ok(EmbeddingResponse {
+            embeddings: response
+                .data
+                .into_iter()
+                .map(|data| data.embedding)
+                .collect(),
+            usage: Usage::new(response.usage.map_or(0, |usage| usage.prompt_tokens), 0),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ai_core::{embed_many, EmbedManyRequest};
+    use mockito::Matcher;
+
+    #[tokio::test]
+    async fn test_embed_many_in_input_order() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/embeddings")
+            .match_header("authorization", "Bearer test-key")
+            .match_body(Matcher::Json(json!({
+                "model": "codestral-embed",
+                "input": ["fn main() {}", "def main(): pass"],
+                "output_dimension": 2,
+            })))
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"id":"embd-1","object":"list","model":"codestral-embed","data":[
+                {"object":"embedding","embedding":[0.3,0.4],"index":1},
+                {"object":"embedding","embedding":[0.1,0.2],"index":0}],
+                "usage":{"prompt_tokens":12,"total_tokens":12,"completion_tokens":0}}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let model = MistralProvider::new("test-key")
+            .with_base_url(server.url())
+            .embedding_model("codestral-embed");
+        let mut request = EmbedManyRequest::new(
+            model,
+            vec!["fn main() {}".into(), "def main(): pass".into()],
+        );
+        request
+            .provider_options
+            .insert("mistral".into(), json!({ "outputDimension": 2 }));
+        let response = embed_many(request).await.unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(response.embeddings, vec![vec![0.1, 0.2], vec![0.3, 0.4]]);
+        assert_eq!(response.usage, Usage::new(12, 0));
+    }
+}
@@ -0,0 +1,109 @@
+//! Mapping of Mistral HTTP errors onto [`AiError`].
+
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use serde_json::Value;
+
+use ai_error::{retry_after, AiError};
+
+/// Error body returned by the API, also sent as a chunk mid-stream.
+///
+/// `message` is usually a string, but request validation errors (422) nest
+/// the offending fields in it, and some gateways reply with `detail` only.
+#[derive(Debug, Deserialize)]
+pub(crate) struct ErrorBody {
+    #[serde(default)]
+    message: Option<Value>,
+    #[serde(default)]
+    detail: Option<Value>,
+    #[serde(default, rename = "type")]
+    kind: Option<String>,
+    #[serde(default)]
+    code: Option<Value>,
+}
+
+impl ErrorBody {
+    fn message(&self) -> Option<String> {
+        match self.message.as_ref().or(self.detail.as_ref())? {
+            Value::String(message) => Some(message.clone()),
+            message => Some(message.to_string()),
+        }
+    }
+
+    /// Converts the error into [`AiError::Provider`], preferring `code` over
+    /// `type` as the error code.
+    pub(crate) fn into_error(self, provider: &str) -> AiError {
+        let message = self.message().unwrap_or_else(|| "unknown error".into());
+        let code = match self.code {
+            Some(Value::String(code)) => Some(code),
+            Some(Value::Number(code)) => Some(code.to_string()),
+            _ => None,
+        };
+        AiError::Provider {
+            provider: provider.to_string(),
+            message,
+            code: code.or(self.kind),
+            details: None,
+        }
+    }
+}
+
+/// Maps a non-success response onto the matching [`AiError`] variant.
+pub(crate) fn map_error(
+    provider: &str,
+    status: StatusCode,
+    headers: &HeaderMap,
+    body: &str,
+) -> AiError {
+    let parsed = serde_json::from_str::<ErrorBody>(body)
+        .ok()
+        .filter(|parsed| parsed.message().is_some());
+
+    match (status, parsed) {
+        (StatusCode::TOO_MANY_REQUESTS, _) => AiError::RateLimit {
+            retry_after: retry_after(headers),
+        },
+        (StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN, parsed) => AiError::Auth(
+            parsed
+                .and_then(|body| body.message())
+                .unwrap_or_else(|| format!("HTTP {status}: {body}")),
+        ),
+        (_, Some(parsed)) => parsed.into_error(provider),
+        (_, None) => AiError::Provider {
+            provider: provider.to_string(),
+            message: format!("HTTP {status}: {body}"),
+            code: None,
+            details: None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_error_by_status_and_body_shape() {
+        let body = r#"{"message":"Unauthorized","request_id":"5b1f0c4e"}"#;
+        let error = map_error("mistral", StatusCode::UNAUTHORIZED, &HeaderMap::new(), body);
+        assert!(matches!(error, AiError::Auth(message) if message == "Unauthorized"));
+
+        let body = r#"{"object":"error","message":"Invalid model: mistral-huge","type":"invalid_model","param":null,"code":"1500"}"#;
+        let error = map_error("mistral", StatusCode::BAD_REQUEST, &HeaderMap::new(), body);
+        assert!(matches!(
+            error,
+            AiError::Provider { code: Some(code), message, .. }
+                if code == "1500" && message == "Invalid model: mistral-huge"
+        ));
+
+        let body = r#"{"object":"error","message":{"detail":[{"type":"missing","loc":["body","messages"]}]},"type":"invalid_request_message_error","param":null,"code":null}"#;
+        let status = StatusCode::UNPROCESSABLE_ENTITY;
+        let error = map_error("mistral", status, &HeaderMap::new(), body);
+        assert!(matches!(
+            error,
+            AiError::Provider { code: Some(code), message, .. }
+                if code == "invalid_request_message_error" && message.contains("missing")
+        ));
+    }
+}
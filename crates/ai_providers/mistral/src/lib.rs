@@ -0,0 +1,35 @@
+//! Mistral provider for the AI SDK.
+//!
+//! [`MistralChatModel`] and [`MistralEmbeddingModel`] talk to La Plateforme
+//! (`api.mistral.ai`) or to a self-hosted deployment serving the same API,
+//! such as an on-premise install behind a private URL.
+//!
+//! ```no_run
+//! use ai_core::{generate_text, GenerateTextRequest};
+//! use ai_providers_mistral::MistralProvider;
+//!
+//! # async fn run() -> ai_error::Result<()> {
+//! // La Plateforme, or `MISTRAL_BASE_URL` for a self-hosted deployment.
+//! let model = MistralProvider::from_env()?.chat_model("mistral-large-latest");
+//! let request = GenerateTextRequest::new(model, "Hello!")
+//!     .provider_options("mistral", serde_json::json!({ "safePrompt": true }));
+//! let response = generate_text(request).await?;
+//! println!("{}", response.text);
+//!
+//! let on_prem = MistralProvider::self_hosted("http://mistral.internal:8080/v1");
+//! let model = on_prem.chat_model("mistral-small");
+//! # Ok(())
+//! # }
+//! ```
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs, rust_2018_idioms)]
+
+mod chat;
+mod embedding;
+mod error;
+mod provider;
+
+pub use chat::MistralChatModel;
+pub use embedding::MistralEmbeddingModel;
+pub use provider::MistralProvider;
@@ -0,0 +1,139 @@
+//! Provider configuration shared by all Mistral models.
+
+use std::collections::HashMap;
+
+use ai_core::{EmbeddingModel, LanguageModel, ModelProvider};
+use ai_error::{AiError, Result};
+
+/// Default endpoint of La Plateforme, Mistral's hosted API.
+const DEFAULT_BASE_URL: &str = "https://api.mistral.ai/v1";
+
+/// Entry point for Mistral models.
+///
+/// Holds the credentials, endpoint and HTTP client used by every model
+/// created from it. Self-hosted deployments serve the same API under their
+/// own base URL, often without an API key; see
+/// [`self_hosted`](Self::self_hosted).
+#[derive(Debug, Clone)]
+pub struct MistralProvider {
+    api_key: Option<String>,
+    base_url: String,
+    headers: HashMap<String, String>,
+    client: reqwest::Client,
+}
+
+impl MistralProvider {
+    /// Creates a provider for La Plateforme that authenticates with
+    /// `api_key`.
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            api_key: Some(api_key.into()),
+            base_url: DEFAULT_BASE_URL.to_string(),
+            headers: HashMap::new(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Creates an unauthenticated provider for a self-hosted deployment at
+    /// `base_url` (the URL that `/chat/completions` is appended to). Add a
+    /// key with [`with_api_key`](Self::with_api_key) if the deployment
+    /// requires one.
+    pub fn self_hosted(base_url: impl Into<String>) -> Self {
+        Self {
+            api_key: None,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            headers: HashMap::new(),
+            client: reqwest::Client::new(),
+        }
+        .with_base_url(base_url)
+    }
+
+    /// Creates a provider from `MISTRAL_API_KEY` and `MISTRAL_BASE_URL`.
+    ///
+    /// Either may be omitted, but not both: without a base URL the key is
+    /// required for La Plateforme, and without a key the base URL is treated
+    /// as an unauthenticated self-hosted deployment.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AiError::Config`] if neither variable is set.
+    pub fn from_env() -> Result<Self> {
+        match (
+            std::env::var("MISTRAL_API_KEY"),
+            std::env::var("MISTRAL_BASE_URL"),
+        ) {
+            (Ok(api_key), Ok(base_url)) => Ok(Self::new(api_key).with_base_url(base_url)),
+            (Ok(api_key), Err(_)) => Ok(Self::new(api_key)),
+            (Err(_), Ok(base_url)) => Ok(Self::self_hosted(base_url)),
+            (Err(_), Err(_)) => Err(AiError::Config("MISTRAL_API_KEY is not set".into())),
+        }
+    }
+
+    /// Sets the API key, sent as a bearer token.
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Sends requests to `base_url` instead of the default endpoint.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Adds a header sent with every request.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.insert(name.into(), value.into());
+        self
+    }
+
+    /// Replaces the HTTP client, e.g. to set a timeout or a user agent.
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Builds a `POST` request to `path`, authenticated if a key is set,
+    /// applying provider headers followed by the per-call `headers`.
+    pub(crate) fn post(
+        &self,
+        path: &str,
+        headers: &HashMap<String, String>,
+    ) -> reqwest::RequestBuilder {
+        let mut request = self.client.post(format!("{}{}", self.base_url, path));
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+        for (name, value) in self.headers.iter().chain(headers) {
+            request = request.header(name, value);
+        }
+        request
+    }
+}
+
+impl ModelProvider for MistralProvider {
+    fn name(&self) -> &str {
+        "mistral"
+    }
+
+    fn language_model(&self, model_id: &str) -> Result<Box<dyn LanguageModel>> {
+        Ok(Box::new(self.chat_model(model_id)))
+    }
+
+    fn embedding_model(&self, model_id: &str) -> Result<Box<dyn EmbeddingModel>> {
+        Ok(Box::new(self.embedding_model(model_id)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base_url_is_normalized() {
+        let provider = MistralProvider::self_hosted("http://mistral.internal:8080/v1/");
+        assert_eq!(provider.base_url, "http://mistral.internal:8080/v1");
+        assert_eq!(provider.api_key, None);
+        assert_eq!(MistralProvider::new("key").base_url, DEFAULT_BASE_URL);
+    }
+}
@@ -0,0 +1,552 @@
+//! Text generation through Ollama's `/api/chat` endpoint.
+
+use async_trait::async_trait;
+use futures::stream::StreamExt;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+use crate::error::{map_error, ErrorBody};
+use crate::provider::OllamaProvider;
+use ai_core::{
+    CallOptions, CallWarning, FinishReason, LanguageModel, Message, MessagePart, MessageRole,
+    ModelCapabilities, ModelProvider, ModelResponse, ModelStream, StreamPart, ToolCallPart,
+    ToolChoice, Usage,
+};
+use ai_error::{ensure_success, AiError, Result};
+use ai_stream::{decode_events, ndjson, Emitter, EventDecoder};
+
+/// A model served by Ollama, such as `llama3.2`.
+///
+/// Provider options are read from the `"ollama"` entry:
+///
+/// - `options` — model parameters passed through as Ollama's `options`
+///   (e.g. `{ "num_ctx": 8192 }`). They take precedence over the call
+///   settings.
+/// - `keepAlive` — how long the model stays loaded after the call (e.g.
+///   `"10m"`, or `0` to unload it immediately).
+/// - `think` — enables thinking for models that support it; the thoughts
+///   are returned as reasoning.
+#[derive(Debug, Clone)]
+pub struct OllamaChatModel {
+    provider: OllamaProvider,
+    model_id: String,
+}
+
+impl OllamaProvider {
+    /// Creates a chat model with the given identifier.
+    pub fn chat_model(&self, model_id: impl Into<String>) -> OllamaChatModel {
+        OllamaChatModel {
+            provider: self.clone(),
+            model_id: model_id.into(),
+        }
+    }
+
+    /// Shorthand for [`chat_model`](Self::chat_model).
+    pub fn model(&self, model_id: impl Into<String>) -> OllamaChatModel {
+        self.chat_model(model_id)
+    }
+}
+
+/// Typed view of the `"ollama"` provider options.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OllamaOptions {
+    #[serde(default)]
+    options: Map<String, Value>,
+    keep_alive: Option<Value>,
+    think: Option<bool>,
+}
+
+impl OllamaChatModel {
+    /// Builds the request body and the warnings for settings it drops.
+    fn request_body(
+        &self,
+        options: &CallOptions,
+        stream: bool,
+    ) -> Result<(Value, Vec<CallWarning>)> {
+        let settings = &options.settings;
+        let provider_options: OllamaOptions = options.provider_options(self.provider.name())?;
+        let mut warnings = settings.unsupported_warnings(&[
+            "temperature",
+            "top_p",
+            "top_k",
+            "max_output_tokens",
+            "stop_sequences",
+            "seed",
+            "presence_penalty",
+            "frequency_penalty",
+        ]);
+        warnings.extend(options.cache_control_warning());
+
+        let mut model_options = json!({
+            "temperature": settings.temperature,
+            "top_p": settings.top_p,
+            "top_k": settings.top_k,
+            "num_predict": settings.max_output_tokens,
+            "seed": settings.seed,
+            "presence_penalty": settings.presence_penalty,
+            "frequency_penalty": settings.frequency_penalty,
+        });
+        if !settings.stop_sequences.is_empty() {
+            model_options["stop"] = json!(settings.stop_sequences);
+        }
+        if let Value::Object(fields) = &mut model_options {
+            fields.retain(|_, value| !value.is_null());
+            fields.extend(provider_options.options);
+        }
+
+        let mut body = json!({
+            "model": self.model_id,
+            "messages": convert_messages(&options.messages)?,
+            "stream": stream,
+            "options": model_options,
+            "keep_alive": provider_options.keep_alive,
+            "think": provider_options.think,
+        });
+
+        // Ollama has no tool choice; `None` is emulated by not offering tools.
+        let offer_tools = match &options.tool_choice {
+            None | Some(ToolChoice::Auto) => true,
+            Some(ToolChoice::None) => false,
+            Some(ToolChoice::Required | ToolChoice::Tool { .. }) => {
+                warnings.push(CallWarning::unsupported_setting("tool_choice"));
+                true
+            }
+        };
+        if offer_tools && !options.tools.is_empty() {
+            let tools: Vec<Value> = options
+                .tools
+                .iter()
+                .map(|tool| {
+                    json!({
+                        "type": "function",
+                        "function": {
+                            "name": tool.name,
+                            "description": tool.description,
+                            "parameters": tool.input_schema,
+                        }
+                    })
+                })
+                .collect();
+            body["tools"] = json!(tools);
+        }
+
+        // Ollama fills in its own defaults for fields that are absent, so
+        // unset settings must not reach it as `null`.
+        if let Value::Object(fields) = &mut body {
+            fields.retain(|_, value| !value.is_null());
+        }
+        Ok((body, warnings))
+    }
+
+    async fn send(&self, options: &CallOptions, body: &Value) -> Result<reqwest::Response> {
+        let response = self
+            .provider
+            .post("/chat", &options.headers)
+            .json(body)
+            .send()
+            .await?;
+        ensure_success(self.provider.name(), response, map_error).await
+    }
+}
+
+/// Converts messages into Ollama chat messages.
+///
+/// Each message carries its text as a single `content` string; images are
+/// sent as base64 in `images`, and every tool result becomes its own `tool`
+/// message.
+fn convert_messages(messages: &[Message]) -> Result<Vec<Value>> {
+    let mut converted = Vec::new();
+    for message in messages {
+        match message.role {
+            MessageRole::System => {
+                converted.push(json!({ "role": "system", "content": message.text() }));
+            }
+            MessageRole::User => {
+                let mut images = Vec::new();
+                for part in &message.parts {
+                    match part {
+                        MessagePart::Text(_) => {}
+                        MessagePart::Image(image) => {
+                            images.push(image.image.to_base64().ok_or_else(|| {
+                                AiError::Validation(
+                                    "Ollama only accepts inline image data, not URLs".into(),
+                                )
+                            })?);
+                        }
+                        _ => {
+                            return Err(AiError::Validation(
+                                "user messages may only contain text and image parts".into(),
+                            ))
+                        }
+                    }
+                }
+                let mut user = json!({ "role": "user", "content": message.text() });
+                if !images.is_empty() {
+                    user["images"] = json!(images);
+                }
+                converted.push(user);
+            }
+            MessageRole::Assistant => {
+                let mut assistant = json!({ "role": "assistant", "content": message.text() });
+                let mut thinking = String::new();
+                let mut tool_calls = Vec::new();
+                for part in &message.parts {
+                    match part {
+                        MessagePart::Reasoning(reasoning) => thinking.push_str(&reasoning.text),
+                        MessagePart::ToolCall(call) => tool_calls.push(json!({
+                            "function": { "name": call.tool_name, "arguments": call.input }
+                        })),
+                        _ => {}
+                    }
+                }
+                if !thinking.is_empty() {
+                    assistant["thinking"] = json!(thinking);
+                }
+                if !tool_calls.is_empty() {
+                    assistant["tool_calls"] = json!(tool_calls);
+                }
+                converted.push(assistant);
+            }
+            MessageRole::Tool => {
+                for part in &message.parts {
+                    if let MessagePart::ToolResult(result) = part {
+                        let content = match &result.output {
+                            Value::String(text) => text.clone(),
+                            output => output.to_string(),
+                        };
+                        converted.push(json!({
+                            "role": "tool",
+                            "content": content,
+                            "tool_name": result.tool_name,
+                        }));
+                    }
+                }
+            }
+        }
+    }
+    Ok(converted)
+}
+
+/// A chat response, also the shape of each streamed line.
+#[derive(Debug, Default, Deserialize)]
+struct ChatResponse {
+    #[serde(default)]
+    message: Option<ChatMessage>,
+    #[serde(default)]
+    done: bool,
+    #[serde(default)]
+    done_reason: Option<String>,
+    #[serde(default)]
+    prompt_eval_count: u64,
+    #[serde(default)]
+    eval_count: u64,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ChatMessage {
+    #[serde(default)]
+    content: String,
+    #[serde(default)]
+    thinking: Option<String>,
+    #[serde(default)]
+    tool_calls: Vec<ChatToolCall>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatToolCall {
+    function: ChatFunction,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatFunction {
+    name: String,
+    #[serde(default)]
+    arguments: Value,
+}
+
+impl ChatResponse {
+    fn usage(&self) -> Usage {
+        Usage::new(self.prompt_eval_count, self.eval_count)
+    }
+}
+
+fn map_finish_reason(reason: Option<&str>, has_tool_calls: bool) -> FinishReason {
+    match reason {
+        Some("stop") if has_tool_calls => FinishReason::ToolCalls,
+        Some("stop") => FinishReason::Stop,
+        Some("length") => FinishReason::Length,
+        Some(_) => FinishReason::Other,
+        None => FinishReason::Unknown,
+    }
+}
+
+#[async_trait]
+impl LanguageModel for OllamaChatModel {
+    fn provider(&self) -> &str {
+        self.provider.name()
+    }
+
+    fn model_id(&self) -> &str {
+        &self.model_id
+    }
+
+    /// Support depends on the model pulled, which is not known up front, so
+    /// everything Ollama can express is reported as supported.
+    fn capabilities(&self) -> ModelCapabilities {
+        ModelCapabilities {
+            tool_calling: true,
+            structured_outputs: true,
+            image_input: true,
+            file_input: false,
+            reasoning: true,
+            streaming: true,
+            max_context_tokens: None,
+        }
+    }
+
+    async fn do_generate(&self, options: CallOptions) -> Result<ModelResponse> {
+        let (body, warnings) = self.request_body(&options, false)?;
+        let response: ChatResponse = self.send(&options, &body).await?.json().await?;
+
+        let usage = response.usage();
+        let message = response.message.unwrap_or_default();
+        let mut content = Vec::new();
+        if let Some(thinking) = message.thinking.filter(|text| !text.is_empty()) {
+            content.push(MessagePart::reasoning(thinking));
+        }
+        if !message.content.is_empty() {
+            content.push(MessagePart::text(message.content));
+        }
+        let has_tool_calls = !message.tool_calls.is_empty();
+        for (index, call) in message.tool_calls.into_iter().enumerate() {
+            content.push(MessagePart::ToolCall(ToolCallPart {
+                tool_call_id: format!("call_{index}"),
+                tool_name: call.function.name,
+                input: call.function.arguments,
+                provider_metadata: None,
+            }));
+        }
+
+        Ok(ModelResponse {
+            content,
+            finish_reason: map_finish_reason(response.done_reason.as_deref(), has_tool_calls),
+            usage,
+            sources: Vec::new(),
+            warnings,
+            provider_metadata: None,
+        })
+    }
+
+    async fn do_stream(&self, options: CallOptions) -> Result<ModelStream> {
+        let (body, warnings) = self.request_body(&options, true)?;
+        let response = self.send(&options, &body).await?;
+
+        let lines = ndjson::decode(response.bytes_stream()).boxed();
+        let state = LineState::new(self.provider.name());
+        Ok(ModelStream {
+            stream: decode_events(lines, state).boxed(),
+            warnings,
+        })
+    }
+}
+
+type Parts = Emitter<StreamPart, AiError>;
+
+/// Turns streamed chat lines into [`StreamPart`]s.
+///
+/// Tool calls arrive whole in a single line, so each becomes a start, a
+/// single delta and an end event. The last line has `done: true` and carries
+/// the usage.
+struct LineState {
+    provider: String,
+    tool_calls: usize,
+}
+
+impl LineState {
+    fn new(provider: &str) -> Self {
+        Self {
+            provider: provider.to_string(),
+            tool_calls: 0,
+        }
+    }
+}
+
+impl EventDecoder for LineState {
+    type Event = String;
+    type Item = StreamPart;
+    type Error = AiError;
+
+    fn decode(&mut self, line: String, out: &mut Parts) {
+        if let Ok(error) = serde_json::from_str::<ErrorBody>(&line) {
+            return out.fail(error.into_error(&self.provider));
+        }
+        let chunk: ChatResponse = match serde_json::from_str(&line) {
+            Ok(chunk) => chunk,
+            Err(error) => return out.fail(error.into()),
+        };
+
+        if let Some(message) = &chunk.message {
+            if let Some(thinking) = message.thinking.as_ref().filter(|text| !text.is_empty()) {
+                out.push(StreamPart::ReasoningDelta {
+                    text: thinking.clone(),
+                });
+            }
+            if !message.content.is_empty() {
+                out.push(StreamPart::TextDelta {
+                    text: message.content.clone(),
+                });
+            }
+            for call in &message.tool_calls {
+                let id = format!("call_{}", self.tool_calls);
+                self.tool_calls += 1;
+                out.push(StreamPart::ToolCallStart {
+                    id: id.clone(),
+                    tool_name: call.function.name.clone(),
+                });
+                out.push(StreamPart::ToolCallDelta {
+                    id: id.clone(),
+                    input_delta: call.function.arguments.to_string(),
+                });
+                out.push(StreamPart::ToolCallEnd { id });
+            }
+        }
+
+        if chunk.done {
+            out.push(StreamPart::Finish {
+                finish_reason: map_finish_reason(chunk.done_reason.as_deref(), self.tool_calls > 0),
+                usage: chunk.usage(),
+            });
+            out.close();
+        }
+    }
+
+    fn end(&mut self, out: &mut Parts) {
+        out.fail(AiError::Stream("stream ended before done".into()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ai_core::{
+        generate_text, stream_text, GenerateTextRequest, StreamTextRequest, ToolDefinition,
+    };
+    use mockito::Matcher;
+
+    fn weather_tool() -> ToolDefinition {
+        ToolDefinition::new(
+            "weather",
+            "Current weather for a city",
+            json!({ "type": "object", "properties": { "city": { "type": "string" } } }),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_generate_passes_options_through() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/chat")
+            .match_body(Matcher::Json(json!({
+                "model": "llama3.2",
+                "messages": [
+                    { "role": "system", "content": "Be brief." },
+                    { "role": "user", "content": "Weather in Paris?" },
+                ],
+                "stream": false,
+                "options": { "temperature": 0.5, "num_predict": 128, "num_ctx": 8192 },
+                "keep_alive": "10m",
+                "tools": [{ "type": "function", "function": {
+                    "name": "weather",
+                    "description": "Current weather for a city",
+                    "parameters": { "type": "object", "properties": { "city": { "type": "string" } } },
+                } }],
+            })))
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"model":"llama3.2","created_at":"2025-01-01T00:00:00Z","message":{"role":"assistant","content":"","tool_calls":[{"function":{"name":"weather","arguments":{"city":"Paris"}}}]},"done_reason":"stop","done":true,"prompt_eval_count":120,"eval_count":18}"#,
+            )
+            .create_async()
+            .await;
+
+        let model = OllamaProvider::new()
+            .with_base_url(format!("{}/api", server.url()))
+            .chat_model("llama3.2");
+        let response = generate_text(
+            GenerateTextRequest::new(model, "Weather in Paris?")
+                .system("Be brief.")
+                .temperature(0.5)
+                .max_output_tokens(128)
+                .tool(weather_tool())
+                .provider_options(
+                    "ollama",
+                    json!({ "options": { "num_ctx": 8192 }, "keepAlive": "10m" }),
+                ),
+        )
+        .await
+        .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(response.finish_reason, FinishReason::ToolCalls);
+        assert_eq!(response.usage, Usage::new(120, 18));
+        assert_eq!(response.tool_calls()[0].input, json!({ "city": "Paris" }));
+    }
+
+    #[tokio::test]
+    async fn test_stream_parses_ndjson() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/api/chat")
+            .match_body(Matcher::PartialJson(json!({ "stream": true })))
+            .with_header("content-type", "application/x-ndjson")
+            .with_body(concat!(
+                r#"{"model":"qwen3","message":{"role":"assistant","content":"","thinking":"Simple greeting."},"done":false}"#,
+                "\n",
+                r#"{"model":"qwen3","message":{"role":"assistant","content":"Hel"},"done":false}"#,
+                "\n",
+                r#"{"model":"qwen3","message":{"role":"assistant","content":"lo!"},"done":false}"#,
+                "\n",
+                r#"{"model":"qwen3","message":{"role":"assistant","content":""},"done_reason":"stop","done":true,"prompt_eval_count":11,"eval_count":7}"#,
+                "\n",
+            ))
+            .create_async()
+            .await;
+
+        let model = OllamaProvider::new()
+            .with_base_url(format!("{}/api", server.url()))
+            .chat_model("qwen3");
+        let handle = stream_text(StreamTextRequest::new(model, "Hi"))
+            .await
+            .unwrap();
+        let result = handle.result().await.unwrap();
+
+        assert_eq!(result.reasoning, "Simple greeting.");
+        assert_eq!(result.text, "Hello!");
+        assert_eq!(result.finish_reason, FinishReason::Stop);
+        assert_eq!(result.usage, Usage::new(11, 7));
+    }
+
+    #[tokio::test]
+    async fn test_missing_model_error() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/api/chat")
+            .with_status(404)
+            .with_body(r#"{"error":"model \"llama9\" not found, try pulling it first"}"#)
+            .create_async()
+            .await;
+
+        let model = OllamaProvider::new()
+            .with_base_url(format!("{}/api", server.url()))
+            .chat_model("llama9");
+        let error = generate_text(GenerateTextRequest::new(model, "Hi"))
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            error,
+            AiError::Provider { code: Some(code), message, .. }
+                if code == "model_not_found" && message.contains("try pulling it first")
+        ));
+    }
+}
@@ -0,0 +1,151 @@
+//! Embeddings through Ollama's `/api/embed` endpoint.
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+use crate::error::map_error;
+use crate::provider::OllamaProvider;
+use ai_core::settings::parse_provider_options;
+use ai_core::{EmbedOptions, Embedding, EmbeddingModel, EmbeddingResponse, ModelProvider, Usage};
+use ai_error::{ensure_success, AiError, Result};
+
+/// An embedding model served by Ollama, such as `nomic-embed-text`.
+///
+/// Reads `options` and `keepAlive` from the `"ollama"` provider options like
+/// [`OllamaChatModel`](crate::OllamaChatModel), plus `truncate`: set it to
+/// `false` to fail instead of truncating inputs longer than the context.
+#[derive(Debug, Clone)]
+pub struct OllamaEmbeddingModel {
+    provider: OllamaProvider,
+    model_id: String,
+}
+
+impl OllamaProvider {
+    /// Creates an embedding model with the given identifier.
+    pub fn embedding_model(&self, model_id: impl Into<String>) -> OllamaEmbeddingModel {
+        OllamaEmbeddingModel {
+            provider: self.clone(),
+            model_id: model_id.into(),
+        }
+    }
+}
+
+/// Typed view of the `"ollama"` provider options.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EmbedProviderOptions {
+    #[serde(default)]
+    options: Map<String, Value>,
+    keep_alive: Option<Value>,
+    truncate: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbedResponse {
+    embeddings: Vec<Embedding>,
+    #[serde(default)]
+    prompt_eval_count: u64,
+}
+
+#[async_trait]
+impl EmbeddingModel for OllamaEmbeddingModel {
+    fn provider(&self) -> &str {
+        self.provider.name()
+    }
+
+    fn model_id(&self) -> &str {
+        &self.model_id
+    }
+
+    fn max_embeddings_per_call(&self) -> Option<usize> {
+        None
+    }
+
+    async fn do_embed(&self, options: EmbedOptions) -> Result<EmbeddingResponse> {
+        let provider_options: EmbedProviderOptions =
+            parse_provider_options(&options.provider_options, self.provider.name())?;
+
+        let mut body = json!({
+            "model": self.model_id,
+            "input": options.values,
+            "keep_alive": provider_options.keep_alive,
+            "truncate": provider_options.truncate,
+        });
+        if !provider_options.options.is_empty() {
+            body["options"] = Value::Object(provider_options.options);
+        }
+        if let Value::Object(fields) = &mut body {
+            fields.retain(|_, value| !value.is_null());
+        }
+
+        let response = self
+            .provider
+            .post("/embed", &options.headers)
+            .json(&body)
+            .send()
+            .await?;
+        let response: EmbedResponse = ensure_success(self.provider.name(), response, map_error)
+            .await?
+            .json()
+            .await?;
+
+        if response.embeddings.len() != options.values.len() {
+            return Err(AiError::Provider {
+                provider: self.provider.name().to_string(),
+                message: format!(
+                    "expected {} embeddings, got {}",
+                    options.values.len(),
+                    response.embeddings.len()
+                ),
+                code: None,
+                details: None,
+            });
+        }
+        Ok(EmbeddingResponse {
+            embeddings: response.embeddings,
+            usage: Usage::new(response.prompt_eval_count, 0),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ai_core::{embed_many, EmbedManyRequest};
+    use mockito::Matcher;
+
+    #[tokio::test]
+    async fn test_embed_many_in_one_call() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/embed")
+            .match_body(Matcher::Json(json!({
+                "model": "nomic-embed-text",
+                "input": ["cat", "dog"],
+                "keep_alive": 0,
+                "options": { "num_ctx": 2048 },
+            })))
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"model":"nomic-embed-text","embeddings":[[0.1,0.2],[0.3,0.4]],"total_duration":14143917,"prompt_eval_count":4}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let model = crate::OllamaProvider::new()
+            .with_base_url(format!("{}/api", server.url()))
+            .embedding_model("nomic-embed-text");
+        let mut request = EmbedManyRequest::new(model, vec!["cat".into(), "dog".into()]);
+        request.provider_options.insert(
+            "ollama".into(),
+            json!({ "keepAlive": 0, "options": { "num_ctx": 2048 } }),
+        );
+        let response = embed_many(request).await.unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(response.embeddings, vec![vec![0.1, 0.2], vec![0.3, 0.4]]);
+        assert_eq!(response.usage, Usage::new(4, 0));
+    }
+}
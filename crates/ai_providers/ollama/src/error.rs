@@ -0,0 +1,76 @@
+//! Mapping of Ollama HTTP errors onto [`AiError`].
+
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+use ai_error::{retry_after, AiError};
+
+/// Error body returned by the API, also sent as a line mid-stream.
+#[derive(Debug, Deserialize)]
+pub(crate) struct ErrorBody {
+    pub(crate) error: String,
+}
+
+impl ErrorBody {
+    pub(crate) fn into_error(self, provider: &str) -> AiError {
+        AiError::Provider {
+            provider: provider.to_string(),
+            message: self.error,
+            code: None,
+            details: None,
+        }
+    }
+}
+
+/// Maps a non-success response onto the matching [`AiError`] variant.
+///
+/// Ollama itself has no authentication; 401 and 403 come from a proxy in
+/// front of it. A missing model is reported as a 404 with an error body and
+/// gets the code `"model_not_found"`.
+pub(crate) fn map_error(
+    provider: &str,
+    status: StatusCode,
+    headers: &HeaderMap,
+    body: &str,
+) -> AiError {
+    let parsed = serde_json::from_str::<ErrorBody>(body).ok();
+    let code = (status == StatusCode::NOT_FOUND && parsed.is_some())
+        .then(|| "model_not_found".to_string());
+    let message = parsed.map_or_else(|| format!("HTTP {status}: {body}"), |body| body.error);
+
+    match status {
+        StatusCode::TOO_MANY_REQUESTS => AiError::RateLimit {
+            retry_after: retry_after(headers),
+        },
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => AiError::Auth(message),
+        _ => AiError::Provider {
+            provider: provider.to_string(),
+            message,
+            code,
+            details: None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::{HeaderValue, RETRY_AFTER};
+    use std::time::Duration;
+
+    #[test]
+    fn test_map_error_by_status() {
+        let body = r#"{"error":"model \"llama9\" not found, try pulling it first"}"#;
+        let error = map_error("ollama", StatusCode::NOT_FOUND, &HeaderMap::new(), body);
+        assert!(matches!(
+            error,
+            AiError::Provider { code: Some(code), .. } if code == "model_not_found"
+        ));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("2"));
+        let error = map_error("ollama", StatusCode::TOO_MANY_REQUESTS, &headers, "");
+        assert_eq!(error.retry_after(), Some(Duration::from_secs(2)));
+    }
+}
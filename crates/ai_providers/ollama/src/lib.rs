@@ -1,14 +1,34 @@
-pub fn add(left: usize, right: usize) -> usize {
-    left + right
-}
+//! Ollama provider for the AI SDK.
+//!
+//! [`OllamaChatModel`] and [`OllamaEmbeddingModel`] talk to a local Ollama
+//! server through `/api/chat` and `/api/embed`. Streaming responses are
+//! newline-delimited JSON rather than server-sent events.
+//!
+//! ```no_run
+//! use ai_core::{generate_text, GenerateTextRequest};
+//! use ai_providers_ollama::OllamaProvider;
+//!
+//! # async fn run() -> ai_error::Result<()> {
+//! let provider = OllamaProvider::from_env();
+//! for model in provider.list_models().await? {
+//!     println!("{}", model.name);
+//! }
+//!
+//! let model = provider.chat_model("llama3.2");
+//! let response = generate_text(GenerateTextRequest::new(model, "Hello!")).await?;
+//! println!("{}", response.text);
+//! # Ok(())
+//! # }
+//! ```
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+#![forbid(unsafe_code)]
+#![warn(missing_docs, rust_2018_idioms)]
 
-    #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
-    }
-}
+mod chat;
+mod embedding;
+mod error;
+mod provider;
+
+pub use chat::OllamaChatModel;
+pub use embedding::OllamaEmbeddingModel;
+pub use provider::{LocalModel, LocalModelDetails, OllamaProvider};
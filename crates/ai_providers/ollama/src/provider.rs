@@ -0,0 +1,184 @@
+//! Provider configuration shared by all Ollama models.
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use crate::error::map_error;
+use ai_core::{EmbeddingModel, LanguageModel, ModelProvider};
+use ai_error::{ensure_success, Result};
+
+/// Default endpoint of the Ollama API.
+const DEFAULT_BASE_URL: &str = "http://localhost:11434/api";
+
+/// Entry point for Ollama models.
+///
+/// Holds the endpoint and HTTP client used by every model created from
+/// it. No credentials are needed for a local server.
+#[derive(Debug, Clone)]
+pub struct OllamaProvider {
+    base_url: String,
+    headers: HashMap<String, String>,
+    client: reqwest::Client,
+}
+
+impl OllamaProvider {
+    /// Creates a provider for a local server at the default address.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a provider, reading the server address from
+    /// `OLLAMA_BASE_URL` if set.
+    pub fn from_env() -> Self {
+        match std::env::var("OLLAMA_BASE_URL") {
+            Ok(base_url) => Self::new().with_base_url(base_url),
+            Err(_) => Self::new(),
+        }
+    }
+
+    /// Sends requests to `base_url` instead of the default endpoint.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Adds a header sent with every request.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.insert(name.into(), value.into());
+        self
+    }
+
+    /// Replaces the HTTP client. Local models can take a while to load, so
+    /// this is where to set a longer timeout.
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Lists the models available on the server (`/api/tags`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AiError::Network`](ai_error::AiError::Network) if the server is
+    /// unreachable, or the mapped error for a non-success response.
+    pub async fn list_models(&self) -> Result<Vec<LocalModel>> {
+        #[derive(Deserialize)]
+        struct Tags {
+            #[serde(default)]
+            models: Vec<LocalModel>,
+        }
+
+        let mut request = self.client.get(format!("{}/tags", self.base_url));
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+        let response = ensure_success(self.name(), request.send().await?, map_error).await?;
+        Ok(response.json::<Tags>().await?.models)
+    }
+
+    /// Builds a `POST` request to `path`, applying provider headers followed
+    /// by the per-call `headers`.
+    pub(crate) fn post(
+        &self,
+        path: &str,
+        headers: &HashMap<String, String>,
+    ) -> reqwest::RequestBuilder {
+        let mut request = self.client.post(format!("{}{}", self.base_url, path));
+        for (name, value) in self.headers.iter().chain(headers) {
+            request = request.header(name, value);
+        }
+        request
+    }
+}
+
+/// A model installed on an Ollama server, as returned by
+/// [`OllamaProvider::list_models`].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct LocalModel {
+    /// Name to pass as the model id (e.g. `"llama3.2:latest"`).
+    pub name: String,
+    /// Size on disk in bytes.
+    #[serde(default)]
+    pub size: u64,
+    /// Digest of the model weights.
+    #[serde(default)]
+    pub digest: String,
+    /// Last modification time as an RFC 3339 timestamp.
+    #[serde(default)]
+    pub modified_at: Option<String>,
+    /// Architecture and quantization details.
+    #[serde(default)]
+    pub details: LocalModelDetails,
+}
+
+/// Architecture and quantization details of a [`LocalModel`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct LocalModelDetails {
+    /// Model family (e.g. `"llama"`).
+    #[serde(default)]
+    pub family: Option<String>,
+    /// Parameter count as reported by the server (e.g. `"3.2B"`).
+    #[serde(default)]
+    pub parameter_size: Option<String>,
+    /// Quantization level (e.g. `"Q4_K_M"`).
+    #[serde(default)]
+    pub quantization_level: Option<String>,
+}
+
+impl Default for OllamaProvider {
+    fn default() -> Self {
+        Self {
+            base_url: DEFAULT_BASE_URL.to_string(),
+            headers: HashMap::new(),
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+impl ModelProvider for OllamaProvider {
+    fn name(&self) -> &str {
+        "ollama"
+    }
+
+    fn language_model(&self, model_id: &str) -> Result<Box<dyn LanguageModel>> {
+        Ok(Box::new(self.chat_model(model_id)))
+    }
+
+    fn embedding_model(&self, model_id: &str) -> Result<Box<dyn EmbeddingModel>> {
+        Ok(Box::new(self.embedding_model(model_id)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base_url_is_normalized() {
+        let provider = OllamaProvider::new().with_base_url("http://localhost:1234/v1/");
+        assert_eq!(provider.base_url, "http://localhost:1234/v1");
+        assert_eq!(OllamaProvider::new().base_url, DEFAULT_BASE_URL);
+    }
+
+    #[tokio::test]
+    async fn test_list_models() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/api/tags")
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"models":[{"name":"llama3.2:latest","model":"llama3.2:latest","modified_at":"2025-05-04T17:37:44.706015396-07:00","size":2019393189,"digest":"a80c4f17acd5","details":{"parent_model":"","format":"gguf","family":"llama","families":["llama"],"parameter_size":"3.2B","quantization_level":"Q4_K_M"}}]}"#,
+            )
+            .create_async()
+            .await;
+
+        let provider = OllamaProvider::new().with_base_url(format!("{}/api", server.url()));
+        let models = provider.list_models().await.unwrap();
+
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0].name, "llama3.2:latest");
+        assert_eq!(models[0].size, 2_019_393_189);
+        assert_eq!(models[0].details.parameter_size.as_deref(), Some("3.2B"));
+    }
+}
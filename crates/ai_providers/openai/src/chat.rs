@@ -0,0 +1,867 @@
+//! Text generation through the OpenAI Chat Completions API.
+
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use futures::stream::StreamExt;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::client::ApiClient;
+use crate::error::ErrorBody;
+use crate::provider::OpenAiProvider;
+use ai_core::{
+    CallOptions, CallWarning, FinishReason, LanguageModel, Message, MessagePart, MessageRole,
+    ModelCapabilities, ModelResponse, ModelStream, ReasoningPart, StreamPart, TextPart,
+    ToolCallPart, ToolChoice, Usage,
+};
+use ai_error::{AiError, Result};
+use ai_stream::sse::{self, SseEvent};
+use ai_stream::{decode_events, Emitter, EventDecoder};
+
+/// An OpenAI chat model such as `gpt-4.1-mini` or `o4-mini`, or a model of
+/// an [`OpenAiCompatibleProvider`](crate::OpenAiCompatibleProvider).
+///
+/// Recognized options under the provider's key (`"openai"` for OpenAI) of
+/// [`CallOptions::provider_options`]: `reasoningEffort`, `user` and
+/// `parallelToolCalls`.
+#[derive(Debug, Clone)]
+pub struct OpenAiChatModel {
+    client: ApiClient,
+    model_id: String,
+}
+
+impl OpenAiChatModel {
+    pub(crate) fn new(client: ApiClient, model_id: String) -> Self {
+        Self { client, model_id }
+    }
+}
+
+impl OpenAiProvider {
+    /// Creates a chat model with the given identifier.
+    pub fn chat_model(&self, model_id: impl Into<String>) -> OpenAiChatModel {
+        OpenAiChatModel::new(self.client.clone(), model_id.into())
+    }
+
+    /// Shorthand for [`chat_model`](Self::chat_model).
+    pub fn model(&self, model_id: impl Into<String>) -> OpenAiChatModel {
+        self.chat_model(model_id)
+    }
+}
+
+/// Typed view of the `"openai"` provider options.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OpenAiChatOptions {
+    reasoning_effort: Option<String>,
+    user: Option<String>,
+    parallel_tool_calls: Option<bool>,
+}
+
+/// o-series and `gpt-5` models reason before answering and reject sampling
+/// settings.
+pub(crate) fn is_reasoning_model(model_id: &str) -> bool {
+    ["o1", "o3", "o4", "gpt-5"]
+        .iter()
+        .any(|prefix| model_id.starts_with(prefix))
+}
+
+/// Capabilities of an OpenAI model, shared by both text APIs.
+pub(crate) fn model_capabilities(model_id: &str) -> ModelCapabilities {
+    let vision = is_reasoning_model(model_id)
+        || ["gpt-4o", "gpt-4.1", "gpt-4-turbo"]
+            .iter()
+            .any(|prefix| model_id.starts_with(prefix));
+    ModelCapabilities {
+        tool_calling: true,
+        structured_outputs: true,
+        image_input: vision,
+        file_input: vision,
+        reasoning: is_reasoning_model(model_id),
+        streaming: true,
+        max_context_tokens: None,
+    }
+}
+
+impl OpenAiChatModel {
+    /// Builds the request body and the warnings for settings it drops.
+    fn request_body(
+        &self,
+        options: &CallOptions,
+        stream: bool,
+    ) -> Result<(Value, Vec<CallWarning>)> {
+        let settings = &options.settings;
+        // Follow the declared capabilities rather than the id, which for
+        // Azure is a deployment name.
+        let reasoning = self.capabilities().reasoning;
+        let supported: &[&str] = if reasoning {
+            &["max_output_tokens", "stop_sequences", "seed"]
+        } else {
+            &[
+                "temperature",
+                "top_p",
+                "max_output_tokens",
+                "stop_sequences",
+                "seed",
+                "presence_penalty",
+                "frequency_penalty",
+            ]
+        };
+        let mut warnings = settings.unsupported_warnings(supported);
+        warnings.extend(options.cache_control_warning());
+        let quirks = &self.client.quirks;
+
+        let mut body = json!({
+            "model": self.model_id,
+            "messages": convert_messages(&options.messages)?,
+        });
+        if !reasoning {
+            body["temperature"] = json!(settings.temperature);
+            body["top_p"] = json!(settings.top_p);
+            body["presence_penalty"] = json!(settings.presence_penalty);
+            body["frequency_penalty"] = json!(settings.frequency_penalty);
+        }
+        let max_tokens_key = if reasoning {
+            "max_completion_tokens"
+        } else {
+            "max_tokens"
+        };
+        body[max_tokens_key] = json!(settings.max_output_tokens);
+        if !settings.stop_sequences.is_empty() {
+            body["stop"] = json!(settings.stop_sequences);
+        }
+        body["seed"] = json!(settings.seed);
+
+        if !options.tools.is_empty() {
+            let tools: Vec<Value> = options
+                .tools
+                .iter()
+                .map(|tool| {
+                    json!({
+                        "type": "function",
+                        "function": {
+                            "name": tool.name,
+                            "description": tool.description,
+                            "parameters": tool.input_schema,
+                        },
+                    })
+                })
+                .collect();
+            body["tools"] = json!(tools);
+        }
+        if let Some(choice) = &options.tool_choice {
+            body["tool_choice"] = match choice {
+                ToolChoice::Auto => json!("auto"),
+                ToolChoice::None => json!("none"),
+                ToolChoice::Required => json!("required"),
+                ToolChoice::Tool { tool_name } => {
+                    json!({ "type": "function", "function": { "name": tool_name } })
+                }
+            };
+        }
+
+        let provider_options: OpenAiChatOptions = options.provider_options(self.client.name())?;
+        body["reasoning_effort"] = json!(provider_options.reasoning_effort);
+        body["user"] = json!(provider_options.user);
+        if quirks.supports_parallel_tool_calls {
+            body["parallel_tool_calls"] = json!(provider_options.parallel_tool_calls);
+        } else if provider_options.parallel_tool_calls.is_some() {
+            warnings.push(CallWarning::unsupported_setting("parallel_tool_calls"));
+        }
+
+        if stream {
+            body["stream"] = json!(true);
+            if quirks.supports_stream_options {
+                body["stream_options"] = json!({ "include_usage": true });
+            }
+        }
+
+        // Unset settings were written as `null`; leave them out entirely.
+        if let Value::Object(fields) = &mut body {
+            fields.retain(|_, value| !value.is_null());
+        }
+        Ok((body, warnings))
+    }
+
+    async fn send(&self, options: &CallOptions, body: &Value) -> Result<reqwest::Response> {
+        let response = self
+            .client
+            .post("/chat/completions", &options.headers)
+            .json(body)
+            .send()
+            .await?;
+        self.client.ensure_success(response).await
+    }
+}
+
+/// Converts messages into the Chat Completions wire format.
+fn convert_messages(messages: &[Message]) -> Result<Vec<Value>> {
+    let mut converted = Vec::with_capacity(messages.len());
+    for message in messages {
+        match message.role {
+            MessageRole::System => {
+                converted.push(json!({ "role": "system", "content": message.text() }));
+            }
+            MessageRole::User => {
+                let text_only = message
+                    .parts
+                    .iter()
+                    .all(|part| matches!(part, MessagePart::Text(_)));
+                let content = if text_only {
+                    json!(message.text())
+                } else {
+                    let parts = message
+                        .parts
+                        .iter()
+                        .map(convert_user_part)
+                        .collect::<Result<Vec<_>>>()?;
+                    json!(parts)
+                };
+                converted.push(json!({ "role": "user", "content": content }));
+            }
+            MessageRole::Assistant => {
+                let tool_calls: Vec<Value> = message
+                    .parts
+                    .iter()
+                    .filter_map(|part| match part {
+                        MessagePart::ToolCall(call) => Some(json!({
+                            "id": call.tool_call_id,
+                            "type": "function",
+                            "function": {
+                                "name": call.tool_name,
+                                "arguments": call.input.to_string(),
+                            },
+                        })),
+                        _ => None,
+                    })
+                    .collect();
+                let text = message.text();
+
+                let mut assistant = json!({ "role": "assistant" });
+                assistant["content"] = if text.is_empty() && !tool_calls.is_empty() {
+                    Value::Null
+                } else {
+                    json!(text)
+                };
+                if !tool_calls.is_empty() {
+                    assistant["tool_calls"] = json!(tool_calls);
+                }
+                converted.push(assistant);
+            }
+            MessageRole::Tool => {
+                for part in &message.parts {
+                    if let MessagePart::ToolResult(result) = part {
+                        let content = match &result.output {
+                            Value::String(text) => text.clone(),
+                            output => output.to_string(),
+                        };
+                        converted.push(json!({
+                            "role": "tool",
+                            "tool_call_id": result.tool_call_id,
+                            "content": content,
+                        }));
+                    }
+                }
+            }
+        }
+    }
+    Ok(converted)
+}
+
+fn convert_user_part(part: &MessagePart) -> Result<Value> {
+    match part {
+        MessagePart::Text(TextPart { text, .. }) => Ok(json!({ "type": "text", "text": text })),
+        MessagePart::Image(image) => {
+            let media_type = image.media_type.as_deref().unwrap_or("image/jpeg");
+            Ok(json!({
+                "type": "image_url",
+                "image_url": { "url": image.image.to_url(media_type) },
+            }))
+        }
+        MessagePart::File(file) if file.media_type == "application/pdf" => Ok(json!({
+            "type": "file",
+            "file": {
+                "filename": file.filename.as_deref().unwrap_or("document.pdf"),
+                "file_data": file.data.to_url(&file.media_type),
+            },
+        })),
+        MessagePart::File(file) => Err(AiError::Validation(format!(
+            "unsupported file media type '{}'",
+            file.media_type
+        ))),
+        _ => Err(AiError::Validation(
+            "user messages may only contain text, image and file parts".into(),
+        )),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatResponse {
+    choices: Vec<ChatChoice>,
+    #[serde(default)]
+    usage: Option<ChatUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChoice {
+    message: ChatMessage,
+    #[serde(default)]
+    finish_reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatMessage {
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    reasoning_content: Option<String>,
+    #[serde(default)]
+    tool_calls: Vec<ChatToolCall>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatToolCall {
+    id: String,
+    function: ChatFunction,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatFunction {
+    name: String,
+    arguments: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ChatUsage {
+    #[serde(default)]
+    prompt_tokens: u64,
+    #[serde(default)]
+    completion_tokens: u64,
+    #[serde(default)]
+    prompt_tokens_details: Option<PromptTokensDetails>,
+    #[serde(default)]
+    completion_tokens_details: Option<CompletionTokensDetails>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PromptTokensDetails {
+    #[serde(default)]
+    cached_tokens: u64,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct CompletionTokensDetails {
+    #[serde(default)]
+    reasoning_tokens: u64,
+}
+
+impl From<ChatUsage> for Usage {
+    fn from(usage: ChatUsage) -> Self {
+        Usage {
+            input_tokens: usage.prompt_tokens,
+            output_tokens: usage.completion_tokens,
+            cached_input_tokens: usage.prompt_tokens_details.map_or(0, |d| d.cached_tokens),
+            reasoning_tokens: usage
+                .completion_tokens_details
+                .map_or(0, |d| d.reasoning_tokens),
+            ..Default::default()
+        }
+    }
+}
+
+fn map_finish_reason(reason: Option<&str>) -> FinishReason {
+    match reason {
+        Some("stop") => FinishReason::Stop,
+        Some("length") => FinishReason::Length,
+        Some("content_filter") => FinishReason::ContentFilter,
+        Some("tool_calls" | "function_call") => FinishReason::ToolCalls,
+        Some(_) => FinishReason::Other,
+        None => FinishReason::Unknown,
+    }
+}
+
+/// Parses tool call arguments, keeping invalid JSON as a string.
+pub(crate) fn parse_arguments(arguments: &str) -> Value {
+    if arguments.trim().is_empty() {
+        return Value::Object(Default::default());
+    }
+    serde_json::from_str(arguments).unwrap_or_else(|_| Value::String(arguments.to_string()))
+}
+
+#[async_trait]
+impl LanguageModel for OpenAiChatModel {
+    fn provider(&self) -> &str {
+        self.client.name()
+    }
+
+    fn model_id(&self) -> &str {
+        &self.model_id
+    }
+
+    fn capabilities(&self) -> ModelCapabilities {
+        self.client
+            .capabilities
+            .unwrap_or_else(|| model_capabilities(&self.model_id))
+    }
+
+    async fn do_generate(&self, options: CallOptions) -> Result<ModelResponse> {
+        let (body, warnings) = self.request_body(&options, false)?;
+        let response: ChatResponse = self.send(&options, &body).await?.json().await?;
+
+        let choice = response
+            .choices
+            .into_iter()
+            .next()
+            .ok_or_else(|| AiError::Provider {
+                provider: self.client.name().to_string(),
+                message: "response contained no choices".into(),
+                code: None,
+                details: None,
+            })?;
+
+        let mut content = Vec::new();
+        if let Some(text) = choice.message.reasoning_content.filter(|t| !t.is_empty()) {
+            content.push(MessagePart::Reasoning(ReasoningPart {
+                text,
+                provider_metadata: None,
+            }));
+        }
+        if let Some(text) = choice.message.content.filter(|t| !t.is_empty()) {
+            content.push(MessagePart::text(text));
+        }
+        for call in choice.message.tool_calls {
+            content.push(MessagePart::ToolCall(ToolCallPart {
+                tool_call_id: call.id,
+                tool_name: call.function.name,
+                input: parse_arguments(&call.function.arguments),
+                provider_metadata: None,
+            }));
+        }
+
+        Ok(ModelResponse {
+            content,
+            finish_reason: map_finish_reason(choice.finish_reason.as_deref()),
+            usage: response.usage.map(Usage::from).unwrap_or_default(),
+            sources: Vec::new(),
+            warnings,
+            provider_metadata: None,
+        })
+    }
+
+    async fn do_stream(&self, options: CallOptions) -> Result<ModelStream> {
+        let (body, warnings) = self.request_body(&options, true)?;
+        let response = self.send(&options, &body).await?;
+
+        let events = sse::decode(response.bytes_stream()).boxed();
+        let state = ChunkState::new(self.client.name());
+        Ok(ModelStream {
+            stream: decode_events(events, state).boxed(),
+            warnings,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChunk {
+    #[serde(default)]
+    choices: Vec<ChunkChoice>,
+    #[serde(default)]
+    usage: Option<ChatUsage>,
+    #[serde(default)]
+    error: Option<ErrorBody>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChunkChoice {
+    #[serde(default)]
+    delta: ChunkDelta,
+    #[serde(default)]
+    finish_reason: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ChunkDelta {
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    reasoning_content: Option<String>,
+    #[serde(default)]
+    tool_calls: Vec<ToolCallChunk>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ToolCallChunk {
+    index: usize,
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    function: Option<FunctionChunk>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FunctionChunk {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    arguments: Option<String>,
+}
+
+/// Assembles chunks into [`StreamPart`]s.
+///
+/// Tool calls are keyed by their `index`; only the first chunk of a call
+/// carries its id and name, later chunks append to the arguments.
+struct ChunkState {
+    provider: String,
+    tool_call_ids: BTreeMap<usize, String>,
+    finish_reason: FinishReason,
+    usage: Usage,
+}
+
+type Parts = Emitter<StreamPart, AiError>;
+
+impl ChunkState {
+    fn new(provider: &str) -> Self {
+        Self {
+            provider: provider.to_string(),
+            tool_call_ids: BTreeMap::new(),
+            finish_reason: FinishReason::Unknown,
+            usage: Usage::default(),
+        }
+    }
+
+    fn process_tool_call(&mut self, call: ToolCallChunk, out: &mut Parts) {
+        let function = call.function.unwrap_or(FunctionChunk {
+            name: None,
+            arguments: None,
+        });
+
+        let id = match self.tool_call_ids.get(&call.index) {
+            Some(id) => id.clone(),
+            None => {
+                let (Some(id), Some(tool_name)) = (call.id, function.name) else {
+                    return out.fail(AiError::Provider {
+                        provider: self.provider.clone(),
+                        message: format!("tool call {} started without id or name", call.index),
+                        code: None,
+                        details: None,
+                    });
+                };
+                self.tool_call_ids.insert(call.index, id.clone());
+                out.push(StreamPart::ToolCallStart {
+                    id: id.clone(),
+                    tool_name,
+                });
+                id
+            }
+        };
+
+        if let Some(input_delta) = function.arguments.filter(|a| !a.is_empty()) {
+            out.push(StreamPart::ToolCallDelta { id, input_delta });
+        }
+    }
+}
+
+impl EventDecoder for ChunkState {
+    type Event = SseEvent;
+    type Item = StreamPart;
+    type Error = AiError;
+
+    fn decode(&mut self, event: SseEvent, out: &mut Parts) {
+        if event.data == "[DONE]" {
+            self.end(out);
+            return out.close();
+        }
+
+        let chunk: ChatChunk = match serde_json::from_str(&event.data) {
+            Ok(chunk) => chunk,
+            Err(error) => return out.fail(error.into()),
+        };
+        if let Some(error) = chunk.error {
+            return out.fail(error.into_error(&self.provider));
+        }
+        if let Some(usage) = chunk.usage {
+            self.usage = usage.into();
+        }
+
+        for choice in chunk.choices {
+            let delta = choice.delta;
+            if let Some(text) = delta.reasoning_content.filter(|t| !t.is_empty()) {
+                out.push(StreamPart::ReasoningDelta { text });
+            }
+            if let Some(text) = delta.content.filter(|t| !t.is_empty()) {
+                out.push(StreamPart::TextDelta { text });
+            }
+            for call in delta.tool_calls {
+                self.process_tool_call(call, out);
+            }
+            if let Some(reason) = choice.finish_reason {
+                self.finish_reason = map_finish_reason(Some(&reason));
+            }
+        }
+    }
+
+    /// Closes open tool calls and emits the final event. Some compatible
+    /// servers close the stream without `[DONE]`, so this also runs then.
+    fn end(&mut self, out: &mut Parts) {
+        for id in std::mem::take(&mut self.tool_call_ids).into_values() {
+            out.push(StreamPart::ToolCallEnd { id });
+        }
+        out.push(StreamPart::Finish {
+            finish_reason: self.finish_reason,
+            usage: self.usage,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ai_core::{
+        generate_text, stream_text, GenerateTextRequest, StreamTextRequest, ToolDefinition,
+    };
+    use mockito::Matcher;
+    use std::time::Duration;
+
+    fn weather_tool() -> ToolDefinition {
+        ToolDefinition::new(
+            "weather",
+            "Current weather for a city",
+            json!({ "type": "object", "properties": { "city": { "type": "string" } } }),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_generate_text_with_tool_calls() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/chat/completions")
+            .match_header("authorization", "Bearer test-key")
+            .match_header("openai-organization", "org-1")
+            .match_body(Matcher::PartialJson(json!({
+                "model": "gpt-4.1-mini",
+                "messages": [
+                    { "role": "system", "content": "Be brief." },
+                    { "role": "user", "content": "Weather in Paris?" },
+                ],
+                "max_tokens": 100,
+                "tools": [{ "type": "function", "function": { "name": "weather" } }],
+                "tool_choice": "required",
+                "user": "user-42",
+            })))
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "choices": [{
+                        "message": {
+                            "role": "assistant",
+                            "content": null,
+                            "tool_calls": [{
+                                "id": "call_1",
+                                "type": "function",
+                                "function": { "name": "weather", "arguments": "{\"city\":\"Paris\"}" },
+                            }],
+                        },
+                        "finish_reason": "tool_calls",
+                    }],
+                    "usage": {
+                        "prompt_tokens": 20,
+                        "completion_tokens": 5,
+                        "prompt_tokens_details": { "cached_tokens": 8 },
+                    },
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let model = OpenAiProvider::new("test-key")
+            .with_base_url(server.url())
+            .with_organization("org-1")
+            .chat_model("gpt-4.1-mini");
+        let response = generate_text(
+            GenerateTextRequest::new(model, "Weather in Paris?")
+                .system("Be brief.")
+                .max_output_tokens(100)
+                .tool(weather_tool())
+                .tool_choice(ToolChoice::Required)
+                .provider_options("openai", json!({ "user": "user-42" })),
+        )
+        .await
+        .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(response.finish_reason, FinishReason::ToolCalls);
+        let calls = response.tool_calls();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].tool_call_id, "call_1");
+        assert_eq!(calls[0].input, json!({ "city": "Paris" }));
+        assert_eq!(response.usage.input_tokens, 20);
+        assert_eq!(response.usage.cached_input_tokens, 8);
+    }
+
+    #[tokio::test]
+    async fn test_stream_text_assembles_tool_call_deltas() {
+        let chunks = [
+            json!({ "choices": [{ "delta": { "role": "assistant", "content": "Let me " } }] }),
+            json!({ "choices": [{ "delta": { "content": "check." } }] }),
+            json!({ "choices": [{ "delta": { "tool_calls": [{
+                "index": 0, "id": "call_1", "type": "function",
+                "function": { "name": "weather", "arguments": "" },
+            }] } }] }),
+            json!({ "choices": [{ "delta": { "tool_calls": [{
+                "index": 0, "function": { "arguments": "{\"city\":" },
+            }] } }] }),
+            json!({ "choices": [{ "delta": { "tool_calls": [{
+                "index": 0, "function": { "arguments": "\"Paris\"}" },
+            }] } }] }),
+            json!({ "choices": [{ "delta": {}, "finish_reason": "tool_calls" }] }),
+            json!({ "choices": [], "usage": {
+                "prompt_tokens": 12,
+                "completion_tokens": 9,
+                "completion_tokens_details": { "reasoning_tokens": 0 },
+            } }),
+        ];
+        let mut body = String::new();
+        for chunk in &chunks {
+            body.push_str(&format!("data: {chunk}\n\n"));
+        }
+        body.push_str("data: [DONE]\n\n");
+
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/chat/completions")
+            .match_body(Matcher::PartialJson(json!({
+                "stream": true,
+                "stream_options": { "include_usage": true },
+            })))
+            .with_header("content-type", "text/event-stream")
+            .with_body(body)
+            .create_async()
+            .await;
+
+        let model = OpenAiProvider::new("test-key")
+            .with_base_url(server.url())
+            .chat_model("gpt-4o");
+        let handle =
+            stream_text(StreamTextRequest::new(model, "Weather in Paris?").tool(weather_tool()))
+                .await
+                .unwrap();
+        let result = handle.result().await.unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(result.text, "Let me check.");
+        assert_eq!(result.tool_calls.len(), 1);
+        assert_eq!(result.tool_calls[0].tool_name, "weather");
+        assert_eq!(result.tool_calls[0].input, json!({ "city": "Paris" }));
+        assert_eq!(result.finish_reason, FinishReason::ToolCalls);
+        assert_eq!(result.usage, Usage::new(12, 9));
+    }
+
+    #[tokio::test]
+    async fn test_maps_http_errors() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/chat/completions")
+            .match_header("authorization", "Bearer bad-key")
+            .with_status(401)
+            .with_body(r#"{"error":{"message":"Incorrect API key provided","type":"invalid_request_error"}}"#)
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/chat/completions")
+            .match_header("authorization", "Bearer limited-key")
+            .with_status(429)
+            .with_header("retry-after", "3")
+            .with_body(r#"{"error":{"message":"Rate limit reached","type":"requests"}}"#)
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/chat/completions")
+            .match_header("authorization", "Bearer test-key")
+            .with_status(503)
+            .with_header("x-request-id", "req_503")
+            .with_body(r#"{"error":{"message":"The engine is currently overloaded","type":"server_error"}}"#)
+            .create_async()
+            .await;
+
+        let model = |key: &str| {
+            OpenAiProvider::new(key)
+                .with_base_url(server.url())
+                .chat_model("gpt-4o")
+        };
+
+        let error = generate_text(GenerateTextRequest::new(model("bad-key"), "hi"))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, AiError::Auth(message) if message.contains("Incorrect API key")));
+
+        let error = stream_text(StreamTextRequest::new(model("limited-key"), "hi"))
+            .await
+            .unwrap_err();
+        assert_eq!(error.retry_after(), Some(Duration::from_secs(3)));
+
+        let error = generate_text(GenerateTextRequest::new(model("test-key"), "hi"))
+            .await
+            .unwrap_err();
+        assert_eq!(error.request_id(), Some("req_503"));
+        assert_eq!(error.details().unwrap().status, Some(503));
+        assert!(matches!(
+            error,
+            AiError::Provider { code: Some(code), .. } if code == "server_error"
+        ));
+    }
+
+    #[test]
+    fn test_reasoning_models_drop_sampling_settings() {
+        let provider = OpenAiProvider::new("key");
+        let options = CallOptions {
+            messages: vec![Message::user("hi")],
+            settings: ai_core::CallSettings {
+                temperature: Some(0.3),
+                max_output_tokens: Some(50),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let (body, warnings) = provider
+            .chat_model("o4-mini")
+            .request_body(&options, false)
+            .unwrap();
+        assert_eq!(body["max_completion_tokens"], 50);
+        assert!(body.get("temperature").is_none());
+        assert_eq!(
+            warnings,
+            vec![CallWarning::unsupported_setting("temperature")]
+        );
+
+        let (body, warnings) = provider
+            .chat_model("gpt-4o")
+            .request_body(&options, false)
+            .unwrap();
+        assert_eq!(body["max_tokens"], 50);
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_cache_control_is_ignored_with_warning() {
+        let options = CallOptions {
+            messages: vec![Message::system("Long context.")
+                .with_cache_control(ai_core::CacheControl::Ephemeral)],
+            ..Default::default()
+        };
+
+        let (body, warnings) = OpenAiProvider::new("key")
+            .chat_model("gpt-4o")
+            .request_body(&options, false)
+            .unwrap();
+        assert_eq!(
+            body["messages"][0],
+            json!({ "role": "system", "content": "Long context." })
+        );
+        assert!(matches!(
+            &warnings[..],
+            [CallWarning::UnsupportedSetting { setting, .. }] if setting == "cache_control"
+        ));
+    }
+}
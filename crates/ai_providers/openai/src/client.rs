@@ -0,0 +1,100 @@
+//! HTTP configuration shared by OpenAI and OpenAI-compatible providers.
+
+use std::collections::HashMap;
+
+use ai_core::ModelCapabilities;
+
+use crate::compatible::{AuthStyle, CompatibilityQuirks};
+use crate::error::{map_error, ErrorMapper};
+use ai_error::Result;
+
+/// Endpoint, credentials and wire-format quirks of one backend.
+///
+/// Models keep a clone, so the connection pool of `http` is shared by every
+/// model created from the same provider.
+#[derive(Debug, Clone)]
+pub(crate) struct ApiClient {
+    pub(crate) name: String,
+    pub(crate) base_url: String,
+    pub(crate) api_key: Option<String>,
+    pub(crate) auth: AuthStyle,
+    pub(crate) headers: HashMap<String, String>,
+    /// Query parameters appended to every request URL.
+    pub(crate) query: Vec<(String, String)>,
+    pub(crate) error_mapper: Option<ErrorMapper>,
+    pub(crate) quirks: CompatibilityQuirks,
+    /// Overrides the capabilities derived from OpenAI model ids.
+    pub(crate) capabilities: Option<ModelCapabilities>,
+    pub(crate) http: reqwest::Client,
+}
+
+impl ApiClient {
+    pub(crate) fn new(name: impl Into<String>, base_url: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            base_url: normalize_base_url(base_url.into()),
+            api_key: None,
+            auth: AuthStyle::Bearer,
+            headers: HashMap::new(),
+            query: Vec::new(),
+            error_mapper: None,
+            quirks: CompatibilityQuirks::default(),
+            capabilities: None,
+            http: reqwest::Client::new(),
+        }
+    }
+
+    /// Provider name used in errors, model metadata and as the
+    /// provider-options key.
+    pub(crate) fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub(crate) fn set_base_url(&mut self, base_url: impl Into<String>) {
+        self.base_url = normalize_base_url(base_url.into());
+    }
+
+    /// Builds an authenticated `POST` request to `path`, applying provider
+    /// headers followed by the per-call `headers`.
+    pub(crate) fn post(
+        &self,
+        path: &str,
+        headers: &HashMap<String, String>,
+    ) -> reqwest::RequestBuilder {
+        let mut request = self.http.post(format!("{}{}", self.base_url, path));
+        if !self.query.is_empty() {
+            request = request.query(&self.query);
+        }
+
+        if let Some(api_key) = &self.api_key {
+            request = match &self.auth {
+                AuthStyle::Bearer => request.bearer_auth(api_key),
+                AuthStyle::Header(name) => request.header(name.as_str(), api_key),
+                AuthStyle::None => request,
+            };
+        }
+        for (name, value) in self.headers.iter().chain(headers) {
+            request = request.header(name, value);
+        }
+        request
+    }
+
+    /// Returns `response` unchanged if it succeeded, or the mapped error.
+    ///
+    /// The backend's own mapper, if any, gets the first go at the error.
+    pub(crate) async fn ensure_success(
+        &self,
+        response: reqwest::Response,
+    ) -> Result<reqwest::Response> {
+        ai_error::ensure_success(&self.name, response, |provider, status, headers, body| {
+            self.error_mapper
+                .and_then(|mapper| mapper(provider, status, body))
+                .unwrap_or_else(|| map_error(provider, status, headers, body))
+        })
+        .await
+    }
+}
+
+fn normalize_base_url(base_url: String) -> String {
+    base_url.trim_end_matches('/').to_string()
+}
@@ -0,0 +1,227 @@
+//! Providers that speak the OpenAI Chat Completions wire format.
+
+use ai_core::{LanguageModel, ModelCapabilities, ModelProvider};
+use ai_error::Result;
+
+use crate::chat::OpenAiChatModel;
+use crate::client::ApiClient;
+use crate::error::ErrorMapper;
+
+/// How an OpenAI-compatible backend expects the API key.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum AuthStyle {
+    /// `Authorization: Bearer <key>`.
+    #[default]
+    Bearer,
+    /// The raw key in the named header, e.g. `api-key` or `x-api-key`.
+    Header(String),
+    /// No authentication header, e.g. for local servers.
+    None,
+}
+
+/// Deviations of a backend from the OpenAI API.
+///
+/// The default describes a backend that behaves exactly like OpenAI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompatibilityQuirks {
+    /// Accepts `stream_options: { include_usage: true }`. Backends that
+    /// reject it may still report usage in their final chunk.
+    pub supports_stream_options: bool,
+    /// Accepts the `parallel_tool_calls` flag. When unsupported, the
+    /// `parallelToolCalls` provider option is dropped with a warning.
+    pub supports_parallel_tool_calls: bool,
+}
+
+impl Default for CompatibilityQuirks {
+    fn default() -> Self {
+        Self {
+            supports_stream_options: true,
+            supports_parallel_tool_calls: true,
+        }
+    }
+}
+
+/// A provider for any backend that implements the OpenAI Chat Completions
+/// API, such as Groq, OpenRouter, Together, vLLM or LM Studio.
+///
+/// Models are [`OpenAiChatModel`]s that report this provider's name, which
+/// is also the key for their provider options and the `provider` of their
+/// errors.
+///
+/// ```
+/// use ai_providers_openai::{CompatibilityQuirks, OpenAiCompatibleProvider};
+///
+/// let provider = OpenAiCompatibleProvider::new("my-vllm", "http://gpu-box:8000/v1")
+///     .with_quirks(CompatibilityQuirks {
+///         supports_parallel_tool_calls: false,
+///         ..Default::default()
+///     });
+/// let model = provider.chat_model("meta-llama/Llama-3.1-8B-Instruct");
+/// ```
+#[derive(Debug, Clone)]
+pub struct OpenAiCompatibleProvider {
+    client: ApiClient,
+}
+
+impl OpenAiCompatibleProvider {
+    /// Creates an unauthenticated provider called `name` for the API at
+    /// `base_url` (the URL that `/chat/completions` is appended to).
+    pub fn new(name: impl Into<String>, base_url: impl Into<String>) -> Self {
+        Self {
+            client: ApiClient::new(name, base_url),
+        }
+    }
+
+    /// [Groq](https://groq.com), authenticating with `api_key`.
+    pub fn groq(api_key: impl Into<String>) -> Self {
+        Self::new("groq", "https://api.groq.com/openai/v1").with_api_key(api_key)
+    }
+
+    /// [OpenRouter](https://openrouter.ai), authenticating with `api_key`.
+    pub fn openrouter(api_key: impl Into<String>) -> Self {
+        Self::new("openrouter", "https://openrouter.ai/api/v1").with_api_key(api_key)
+    }
+
+    /// [Together AI](https://together.ai), authenticating with `api_key`.
+    pub fn together(api_key: impl Into<String>) -> Self {
+        Self::new("together", "https://api.together.xyz/v1").with_api_key(api_key)
+    }
+
+    /// A local [LM Studio](https://lmstudio.ai) server on its default port.
+    pub fn lm_studio() -> Self {
+        Self::new("lmstudio", "http://localhost:1234/v1")
+    }
+
+    /// A [vLLM](https://docs.vllm.ai) server at `base_url`.
+    pub fn vllm(base_url: impl Into<String>) -> Self {
+        Self::new("vllm", base_url)
+    }
+
+    /// Sets the API key, sent according to the [`AuthStyle`].
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.client.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Sets how the API key is sent. Defaults to [`AuthStyle::Bearer`].
+    pub fn with_auth_style(mut self, auth: AuthStyle) -> Self {
+        self.client.auth = auth;
+        self
+    }
+
+    /// Adds a header sent with every request, e.g. OpenRouter's
+    /// `HTTP-Referer`.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.client.headers.insert(name.into(), value.into());
+        self
+    }
+
+    /// Appends a query parameter to every request URL, e.g. an API version.
+    pub fn with_query_param(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.client.query.push((name.into(), value.into()));
+        self
+    }
+
+    /// Maps the backend's own error payloads before the OpenAI mapping is
+    /// applied.
+    pub fn with_error_mapper(mut self, mapper: ErrorMapper) -> Self {
+        self.client.error_mapper = Some(mapper);
+        self
+    }
+
+    /// Declares where the backend deviates from the OpenAI API.
+    pub fn with_quirks(mut self, quirks: CompatibilityQuirks) -> Self {
+        self.client.quirks = quirks;
+        self
+    }
+
+    /// Reports `capabilities` for every model instead of deriving them from
+    /// OpenAI model ids, which rarely match other vendors' models.
+    ///
+    /// `reasoning` also shapes requests the way OpenAI reasoning models
+    /// expect: `max_completion_tokens` instead of `max_tokens`, and no
+    /// sampling settings.
+    pub fn with_capabilities(mut self, capabilities: ModelCapabilities) -> Self {
+        self.client.capabilities = Some(capabilities);
+        self
+    }
+
+    /// Replaces the HTTP client, e.g. with one that trusts the self-signed
+    /// certificate of a local server.
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client.http = client;
+        self
+    }
+
+    /// Creates a chat model with the given identifier.
+    pub fn chat_model(&self, model_id: impl Into<String>) -> OpenAiChatModel {
+        OpenAiChatModel::new(self.client.clone(), model_id.into())
+    }
+}
+
+impl ModelProvider for OpenAiCompatibleProvider {
+    fn name(&self) -> &str {
+        self.client.name()
+    }
+
+    fn language_model(&self, model_id: &str) -> Result<Box<dyn LanguageModel>> {
+        Ok(Box::new(self.chat_model(model_id)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ai_core::{stream_text, CallWarning, StreamTextRequest};
+    use mockito::Matcher;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_applies_auth_style_and_quirks() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/v1/chat/completions")
+            .match_header("x-api-key", "secret")
+            .match_header("authorization", Matcher::Missing)
+            // An exact match: neither quirk-gated field may be sent.
+            .match_body(Matcher::Json(json!({
+                "model": "llama-3.1-8b",
+                "messages": [{ "role": "user", "content": "hello" }],
+                "stream": true,
+            })))
+            .with_header("content-type", "text/event-stream")
+            .with_body(concat!(
+                "data: {\"choices\":[{\"delta\":{\"content\":\"Hi\"},\"finish_reason\":\"stop\"}],",
+                "\"usage\":{\"prompt_tokens\":3,\"completion_tokens\":1}}\n\n",
+                "data: [DONE]\n\n",
+            ))
+            .create_async()
+            .await;
+
+        let provider = OpenAiCompatibleProvider::new("local", format!("{}/v1/", server.url()))
+            .with_api_key("secret")
+            .with_auth_style(AuthStyle::Header("x-api-key".into()))
+            .with_quirks(CompatibilityQuirks {
+                supports_stream_options: false,
+                supports_parallel_tool_calls: false,
+            });
+        let model = provider.chat_model("llama-3.1-8b");
+        assert_eq!(model.provider(), "local");
+
+        let handle = stream_text(
+            StreamTextRequest::new(model, "hello")
+                .provider_options("local", json!({ "parallelToolCalls": false })),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            handle.warnings(),
+            &[CallWarning::unsupported_setting("parallel_tool_calls")]
+        );
+
+        let result = handle.result().await.unwrap();
+        mock.assert_async().await;
+        assert_eq!(result.text, "Hi");
+        assert_eq!(result.usage.input_tokens, 3);
+    }
+}
@@ -0,0 +1,101 @@
+//! Mapping of OpenAI HTTP errors onto [`AiError`].
+
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+use ai_error::{retry_after, AiError};
+
+/// Maps an error response of an OpenAI-compatible backend onto an
+/// [`AiError`], or returns `None` to fall back to the OpenAI mapping.
+///
+/// Receives the provider name, the HTTP status and the response body.
+pub type ErrorMapper = fn(provider: &str, status: StatusCode, body: &str) -> Option<AiError>;
+
+/// Error envelope returned by the OpenAI API.
+#[derive(Debug, Deserialize)]
+struct ErrorEnvelope {
+    error: ErrorBody,
+}
+
+/// Error object, also sent as an `error` chunk mid-stream.
+#[derive(Debug, Deserialize)]
+pub(crate) struct ErrorBody {
+    message: String,
+    #[serde(default)]
+    code: Option<serde_json::Value>,
+    #[serde(default, rename = "type")]
+    kind: Option<String>,
+}
+
+impl ErrorBody {
+    /// Converts the error into [`AiError::Provider`], preferring `code` over
+    /// `type` as the error code.
+    pub(crate) fn into_error(self, provider: &str) -> AiError {
+        let code = match self.code {
+            Some(serde_json::Value::String(code)) => Some(code),
+            Some(serde_json::Value::Number(code)) => Some(code.to_string()),
+            _ => None,
+        };
+        AiError::Provider {
+            provider: provider.to_string(),
+            message: self.message,
+            code: code.or(self.kind),
+            details: None,
+        }
+    }
+}
+
+/// Maps a non-success response onto the matching [`AiError`] variant.
+pub(crate) fn map_error(
+    provider: &str,
+    status: StatusCode,
+    headers: &HeaderMap,
+    body: &str,
+) -> AiError {
+    let parsed = serde_json::from_str::<ErrorEnvelope>(body).ok();
+
+    match (status, parsed) {
+        (StatusCode::TOO_MANY_REQUESTS, _) => AiError::RateLimit {
+            retry_after: retry_after(headers),
+        },
+        (StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN, parsed) => AiError::Auth(
+            parsed.map_or_else(|| format!("HTTP {status}: {body}"), |e| e.error.message),
+        ),
+        (_, Some(envelope)) => envelope.error.into_error(provider),
+        (_, None) => AiError::Provider {
+            provider: provider.to_string(),
+            message: format!("HTTP {status}: {body}"),
+            code: None,
+            details: None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::{HeaderValue, RETRY_AFTER};
+    use std::time::Duration;
+
+    #[test]
+    fn test_map_error_by_status() {
+        let body = r#"{"error":{"message":"Incorrect API key","type":"invalid_request_error","code":"invalid_api_key"}}"#;
+        let error = map_error("openai", StatusCode::UNAUTHORIZED, &HeaderMap::new(), body);
+        assert!(matches!(error, AiError::Auth(message) if message == "Incorrect API key"));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("2"));
+        let error = map_error("openai", StatusCode::TOO_MANY_REQUESTS, &headers, "");
+        assert_eq!(error.retry_after(), Some(Duration::from_secs(2)));
+
+        let error = map_error("openai", StatusCode::BAD_REQUEST, &HeaderMap::new(), body);
+        assert!(matches!(
+            error,
+            AiError::Provider { code: Some(code), .. } if code == "invalid_api_key"
+        ));
+
+        let error = map_error("openai", StatusCode::BAD_GATEWAY, &HeaderMap::new(), "oops");
+        assert!(matches!(error, AiError::Provider { message, .. } if message.contains("oops")));
+    }
+}
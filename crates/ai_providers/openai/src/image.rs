@@ -0,0 +1,209 @@
+//! Image generation through the OpenAI Images API.
+
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::error::map_error;
+use crate::provider::OpenAiProvider;
+use ai_core::{
+    CallWarning, GeneratedImage, ImageModel, ImageModelResponse, ImageOptions, ModelProvider,
+};
+use ai_error::{ensure_success, AiError, Result};
+
+/// An OpenAI image model such as `dall-e-3` or `gpt-image-1`.
+///
+/// Options under the `"openai"` key of
+/// [`ImageOptions::provider_options`] (e.g. `quality`, `style`) are copied
+/// into the request body as-is.
+#[derive(Debug, Clone)]
+pub struct OpenAiImageModel {
+    provider: OpenAiProvider,
+    model_id: String,
+}
+
+impl OpenAiProvider {
+    /// Creates an image model with the given identifier.
+    pub fn image_model(&self, model_id: impl Into<String>) -> OpenAiImageModel {
+        OpenAiImageModel {
+            provider: self.clone(),
+            model_id: model_id.into(),
+        }
+    }
+}
+
+impl OpenAiImageModel {
+    /// `gpt-image-*` models always return base64 and reject `response_format`.
+    fn is_gpt_image(&self) -> bool {
+        self.model_id.starts_with("gpt-image")
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ImagesResponse {
+    data: Vec<ImageData>,
+    #[serde(default)]
+    output_format: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ImageData {
+    b64_json: String,
+}
+
+#[async_trait]
+impl ImageModel for OpenAiImageModel {
+    fn provider(&self) -> &str {
+        self.provider.name()
+    }
+
+    fn model_id(&self) -> &str {
+        &self.model_id
+    }
+
+    fn max_images_per_call(&self) -> Option<u32> {
+        match self.model_id.as_str() {
+            "dall-e-2" => Some(10),
+            _ if self.is_gpt_image() => Some(10),
+            _ => Some(1),
+        }
+    }
+
+    async fn do_generate(&self, options: ImageOptions) -> Result<ImageModelResponse> {
+        let mut warnings = Vec::new();
+        if options.aspect_ratio.is_some() {
+            warnings.push(CallWarning::UnsupportedSetting {
+                setting: "aspect_ratio".into(),
+                details: Some("use size instead".into()),
+            });
+        }
+        if options.seed.is_some() {
+            warnings.push(CallWarning::unsupported_setting("seed"));
+        }
+
+        let mut body = json!({
+            "model": self.model_id,
+            "prompt": options.prompt,
+            "n": options.n,
+        });
+        if let Some(size) = &options.size {
+            body["size"] = json!(size);
+        }
+        if !self.is_gpt_image() {
+            body["response_format"] = json!("b64_json");
+        }
+        if let Some(Value::Object(extra)) = options.provider_options.get(self.provider.name()) {
+            for (key, value) in extra {
+                body[key] = value.clone();
+            }
+        }
+
+        let response = self
+            .provider
+            .post("/images/generations", &options.headers)
+            .json(&body)
+            .send()
+            .await?;
+        let response: ImagesResponse = ensure_success(self.provider.name(), response, map_error)
+            .await?
+            .json()
+            .await?;
+
+        let media_type = match response.output_format.as_deref() {
+            Some("jpeg") => "image/jpeg",
+            Some("webp") => "image/webp",
+            _ => "image/png",
+        };
+        let images = response
+            .data
+            .into_iter()
+            .map(|image| {
+                STANDARD
+                    .decode(image.b64_json)
+                    .map(|data| GeneratedImage::new(data, media_type))
+                    .map_err(|error| AiError::Provider {
+                        provider: self.provider.name().to_string(),
+                        message: format!("invalid base64 image data: {error}"),
+                        code: None,
+                        details: None,
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(ImageModelResponse { images, warnings })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ai_core::{generate_image, GenerateImageRequest};
+    use mockito::Matcher;
+
+    #[tokio::test]
+    async fn test_generate_image_against_mock_server() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/images/generations")
+            .match_header("authorization", "Bearer test-key")
+            .match_body(Matcher::PartialJson(json!({
+                "model": "dall-e-3",
+                "n": 1,
+                "size": "1024x1024",
+                "response_format": "b64_json",
+                "quality": "hd",
+            })))
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"created":1,"data":[{"b64_json":"iVBORw=="}]}"#)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let model = OpenAiProvider::new("test-key")
+            .with_base_url(server.url())
+            .image_model("dall-e-3");
+        let response = generate_image(
+            GenerateImageRequest::new(model, "a lighthouse at dusk")
+                .n(2)
+                .size("1024x1024")
+                .seed(7)
+                .provider_options("openai", json!({ "quality": "hd" })),
+        )
+        .await
+        .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(response.images.len(), 2);
+        assert_eq!(response.images[0].data, vec![0x89, b'P', b'N', b'G']);
+        assert_eq!(response.images[0].media_type, "image/png");
+        assert_eq!(
+            response.warnings,
+            vec![CallWarning::unsupported_setting("seed")]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_generate_image_maps_errors() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/images/generations")
+            .with_status(400)
+            .with_body(r#"{"error":{"message":"Your request was rejected","code":"content_policy_violation"}}"#)
+            .create_async()
+            .await;
+
+        let model = OpenAiProvider::new("test-key")
+            .with_base_url(server.url())
+            .image_model("gpt-image-1");
+        let error = generate_image(GenerateImageRequest::new(model, "something"))
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            error,
+            AiError::Provider { code: Some(code), .. } if code == "content_policy_violation"
+        ));
+    }
+}
@@ -1,14 +1,50 @@
-pub fn add(left: usize, right: usize) -> usize {
-    left + right
-}
+//! OpenAI provider for the AI SDK.
+//!
+//! ```no_run
+//! use ai_core::{generate_text, GenerateTextRequest};
+//! use ai_providers_openai::OpenAiProvider;
+//!
+//! # async fn run() -> ai_error::Result<()> {
+//! let provider = OpenAiProvider::from_env()?.with_organization("org-123");
+//! let response = generate_text(GenerateTextRequest::new(
+//!     provider.chat_model("gpt-4.1-mini"),
+//!     "Write a haiku about Rust.",
+//! ))
+//! .await?;
+//! println!("{}", response.text);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`OpenAiChatModel`] speaks the Chat Completions API, including streaming
+//! and tool calls. [`OpenAiResponsesModel`] uses the Responses API instead,
+//! which adds reasoning summaries and `previous_response_id` chaining. Through
+//! a `ProviderRegistry`, select it with a `responses:` prefix, e.g.
+//! `"openai:responses:gpt-5"`.
+//!
+//! [`OpenAiCompatibleProvider`] reuses the chat model for other backends that
+//! speak the same wire format, such as Groq, OpenRouter or a local vLLM.
+//!
+//! # Features
+//!
+//! - `image` — [`OpenAiImageModel`] for the Images API.
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+#![forbid(unsafe_code)]
+#![warn(missing_docs, rust_2018_idioms)]
 
-    #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
-    }
-}
+mod chat;
+mod client;
+mod compatible;
+mod error;
+#[cfg(feature = "image")]
+mod image;
+mod provider;
+mod responses;
+
+pub use chat::OpenAiChatModel;
+pub use compatible::{AuthStyle, CompatibilityQuirks, OpenAiCompatibleProvider};
+pub use error::ErrorMapper;
+#[cfg(feature = "image")]
+pub use image::OpenAiImageModel;
+pub use provider::OpenAiProvider;
+pub use responses::OpenAiResponsesModel;
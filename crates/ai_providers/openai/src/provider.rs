@@ -0,0 +1,142 @@
+//! Provider configuration shared by all OpenAI models.
+
+use std::collections::HashMap;
+
+use ai_core::{LanguageModel, ModelProvider};
+use ai_error::{AiError, Result};
+
+use crate::client::ApiClient;
+
+/// Default endpoint of the OpenAI API.
+const DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
+
+/// Model id prefix that selects the Responses API in
+/// [`ModelProvider::language_model`].
+const RESPONSES_PREFIX: &str = "responses:";
+
+/// Entry point for OpenAI models.
+///
+/// Holds the credentials, endpoint and HTTP client used by every model
+/// created from it. Cloning is cheap and shares the connection pool.
+#[derive(Debug, Clone)]
+pub struct OpenAiProvider {
+    pub(crate) client: ApiClient,
+}
+
+impl OpenAiProvider {
+    /// Creates a provider that authenticates with `api_key`.
+    pub fn new(api_key: impl Into<String>) -> Self {
+        let mut client = ApiClient::new("openai", DEFAULT_BASE_URL);
+        client.api_key = Some(api_key.into());
+        Self { client }
+    }
+
+    /// Creates a provider from `OPENAI_API_KEY`, and optionally
+    /// `OPENAI_BASE_URL` and `OPENAI_ORGANIZATION`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AiError::Config`] if `OPENAI_API_KEY` is not set.
+    pub fn from_env() -> Result<Self> {
+        let api_key = std::env::var("OPENAI_API_KEY")
+            .map_err(|_| AiError::Config("OPENAI_API_KEY is not set".into()))?;
+
+        let mut provider = Self::new(api_key);
+        if let Ok(base_url) = std::env::var("OPENAI_BASE_URL") {
+            provider = provider.with_base_url(base_url);
+        }
+        if let Ok(organization) = std::env::var("OPENAI_ORGANIZATION") {
+            provider = provider.with_organization(organization);
+        }
+        Ok(provider)
+    }
+
+    /// Sends requests to `base_url` instead of the public API, e.g. a proxy
+    /// or a local mock server.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.client.set_base_url(base_url);
+        self
+    }
+
+    /// Sets the `OpenAI-Organization` header.
+    pub fn with_organization(self, organization: impl Into<String>) -> Self {
+        self.with_header("OpenAI-Organization", organization)
+    }
+
+    /// Adds a header sent with every request.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.client.headers.insert(name.into(), value.into());
+        self
+    }
+
+    /// Sends requests through `client` instead of a default `reqwest::Client`.
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client.http = client;
+        self
+    }
+
+    /// Builds an authenticated `POST` request to `path`, applying provider
+    /// headers followed by the per-call `headers`.
+    pub(crate) fn post(
+        &self,
+        path: &str,
+        headers: &HashMap<String, String>,
+    ) -> reqwest::RequestBuilder {
+        self.client.post(path, headers)
+    }
+}
+
+impl ModelProvider for OpenAiProvider {
+    fn name(&self) -> &str {
+        self.client.name()
+    }
+
+    /// Returns a chat model, or a Responses API model if `model_id` starts
+    /// with `responses:` (e.g. `"responses:gpt-5"`).
+    fn language_model(&self, model_id: &str) -> Result<Box<dyn LanguageModel>> {
+        match model_id.strip_prefix(RESPONSES_PREFIX) {
+            Some(model_id) => Ok(Box::new(self.responses_model(model_id))),
+            None => Ok(Box::new(self.chat_model(model_id))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base_url_is_normalized() {
+        let provider = OpenAiProvider::new("key").with_base_url("http://localhost:1234/v1/");
+        assert_eq!(provider.client.base_url, "http://localhost:1234/v1");
+        assert_eq!(OpenAiProvider::new("key").client.base_url, DEFAULT_BASE_URL);
+    }
+
+    #[tokio::test]
+    async fn test_language_model_selects_responses_api_by_prefix() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/responses")
+            .match_body(mockito::Matcher::PartialJson(
+                serde_json::json!({ "model": "gpt-5" }),
+            ))
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"id":"resp_1","status":"completed","output":[{"type":"message","content":[{"type":"output_text","text":"Hi"}]}]}"#,
+            )
+            .create_async()
+            .await;
+
+        let provider = OpenAiProvider::new("key").with_base_url(server.url());
+        let model = provider.language_model("responses:gpt-5").unwrap();
+        assert_eq!(model.model_id(), "gpt-5");
+        let response = model.do_generate(Default::default()).await.unwrap();
+        assert_eq!(response.text(), "Hi");
+        mock.assert_async().await;
+
+        assert_eq!(
+            provider.language_model("gpt-4.1").unwrap().model_id(),
+            "gpt-4.1"
+        );
+    }
+}
@@ -0,0 +1,838 @@
+//! Text generation through the OpenAI Responses API.
+//!
+//! Unlike Chat Completions, the Responses API returns reasoning summaries and
+//! (for stateless use) encrypted reasoning items, and can continue a stored
+//! response through `previous_response_id` instead of resending the
+//! conversation.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use futures::stream::StreamExt;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+use crate::chat::{is_reasoning_model, model_capabilities, parse_arguments};
+use crate::error::{map_error, ErrorBody};
+use crate::provider::OpenAiProvider;
+use ai_core::{
+    CallOptions, CallWarning, FinishReason, LanguageModel, Message, MessagePart, MessageRole,
+    ModelCapabilities, ModelProvider, ModelResponse, ModelStream, ProviderMetadata, ReasoningPart,
+    StreamPart, TextPart, ToolCallPart, ToolChoice, Usage,
+};
+use ai_error::{ensure_success, AiError, Result};
+use ai_stream::sse::{self, SseEvent};
+use ai_stream::{decode_events, Emitter, EventDecoder};
+
+/// An OpenAI model served through the Responses API.
+///
+/// Recognized options under the `"openai"` key of
+/// [`CallOptions::provider_options`]:
+///
+/// - `previousResponseId` — continue a stored response; the messages then
+///   only need to contain the new turn.
+/// - `store` — set to `false` for stateless use. Reasoning items are then
+///   returned encrypted so they can be sent back with the next call.
+/// - `reasoningEffort`, `reasoningSummary` (`"auto"`, `"concise"` or
+///   `"detailed"`), `instructions`, `user` and `parallelToolCalls`.
+///
+/// The response id is returned as `responseId` in the `"openai"` entry of the
+/// response's provider metadata. Reasoning parts carry their `itemId` (and
+/// `encryptedContent`, if any) the same way; when streaming, these arrive in
+/// [`StreamPart::ReasoningEnd`], so [`StreamTextResult::to_message`] can be
+/// sent back just like a generated response.
+///
+/// Selected through [`ModelProvider::language_model`] (and so a
+/// `ProviderRegistry`) with a `responses:` prefix, e.g.
+/// `"openai:responses:gpt-5"`.
+///
+/// [`StreamTextResult::to_message`]: ai_core::StreamTextResult::to_message
+#[derive(Debug, Clone)]
+pub struct OpenAiResponsesModel {
+    provider: OpenAiProvider,
+    model_id: String,
+}
+
+impl OpenAiProvider {
+    /// Creates a Responses API model with the given identifier.
+    pub fn responses_model(&self, model_id: impl Into<String>) -> OpenAiResponsesModel {
+        OpenAiResponsesModel {
+            provider: self.clone(),
+            model_id: model_id.into(),
+        }
+    }
+}
+
+/// Typed view of the `"openai"` provider options.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OpenAiResponsesOptions {
+    previous_response_id: Option<String>,
+    store: Option<bool>,
+    reasoning_effort: Option<String>,
+    reasoning_summary: Option<String>,
+    instructions: Option<String>,
+    user: Option<String>,
+    parallel_tool_calls: Option<bool>,
+}
+
+impl OpenAiResponsesModel {
+    /// Builds the request body and the warnings for settings it drops.
+    fn request_body(
+        &self,
+        options: &CallOptions,
+        stream: bool,
+    ) -> Result<(Value, Vec<CallWarning>)> {
+        let settings = &options.settings;
+        let reasoning = is_reasoning_model(&self.model_id);
+        let supported: &[&str] = if reasoning {
+            &["max_output_tokens"]
+        } else {
+            &["temperature", "top_p", "max_output_tokens"]
+        };
+        let mut warnings = settings.unsupported_warnings(supported);
+        warnings.extend(options.cache_control_warning());
+        let provider_options: OpenAiResponsesOptions =
+            options.provider_options(self.provider.name())?;
+
+        let mut body = json!({
+            "model": self.model_id,
+            "input": convert_input(&options.messages)?,
+            "max_output_tokens": settings.max_output_tokens,
+            "previous_response_id": provider_options.previous_response_id,
+            "store": provider_options.store,
+            "instructions": provider_options.instructions,
+            "user": provider_options.user,
+            "parallel_tool_calls": provider_options.parallel_tool_calls,
+        });
+        if reasoning {
+            let mut config = Map::new();
+            if let Some(effort) = provider_options.reasoning_effort {
+                config.insert("effort".into(), json!(effort));
+            }
+            if let Some(summary) = provider_options.reasoning_summary {
+                config.insert("summary".into(), json!(summary));
+            }
+            if !config.is_empty() {
+                body["reasoning"] = Value::Object(config);
+            }
+            if provider_options.store == Some(false) {
+                body["include"] = json!(["reasoning.encrypted_content"]);
+            }
+        } else {
+            body["temperature"] = json!(settings.temperature);
+            body["top_p"] = json!(settings.top_p);
+        }
+
+        if !options.tools.is_empty() {
+            let tools: Vec<Value> = options
+                .tools
+                .iter()
+                .map(|tool| {
+                    json!({
+                        "type": "function",
+                        "name": tool.name,
+                        "description": tool.description,
+                        "parameters": tool.input_schema,
+                    })
+                })
+                .collect();
+            body["tools"] = json!(tools);
+        }
+        if let Some(choice) = &options.tool_choice {
+            body["tool_choice"] = match choice {
+                ToolChoice::Auto => json!("auto"),
+                ToolChoice::None => json!("none"),
+                ToolChoice::Required => json!("required"),
+                ToolChoice::Tool { tool_name } => json!({ "type": "function", "name": tool_name }),
+            };
+        }
+        if stream {
+            body["stream"] = json!(true);
+        }
+
+        // Options that were not set are `null` here; send only the ones that were.
+        if let Value::Object(fields) = &mut body {
+            fields.retain(|_, value| !value.is_null());
+        }
+        Ok((body, warnings))
+    }
+
+    async fn send(&self, options: &CallOptions, body: &Value) -> Result<reqwest::Response> {
+        let response = self
+            .provider
+            .post("/responses", &options.headers)
+            .json(body)
+            .send()
+            .await?;
+        ensure_success(self.provider.name(), response, map_error).await
+    }
+}
+
+/// Returns the `"openai"` metadata entry of a part, if any.
+fn openai_metadata(metadata: Option<&ProviderMetadata>) -> Option<&Map<String, Value>> {
+    metadata?.get("openai")?.as_object()
+}
+
+fn metadata(fields: Value) -> ProviderMetadata {
+    HashMap::from([("openai".to_string(), fields)])
+}
+
+/// Metadata that lets a reasoning part be sent back as its item.
+fn reasoning_metadata(id: &str, encrypted_content: Option<&str>) -> ProviderMetadata {
+    let mut fields = json!({ "itemId": id });
+    if let Some(encrypted) = encrypted_content {
+        fields["encryptedContent"] = json!(encrypted);
+    }
+    metadata(fields)
+}
+
+/// Converts messages into Responses API input items.
+fn convert_input(messages: &[Message]) -> Result<Vec<Value>> {
+    let mut items = Vec::new();
+    for message in messages {
+        match message.role {
+            MessageRole::System => {
+                items.push(json!({ "role": "system", "content": message.text() }));
+            }
+            MessageRole::User => {
+                let content = message
+                    .parts
+                    .iter()
+                    .map(convert_user_part)
+                    .collect::<Result<Vec<_>>>()?;
+                items.push(json!({ "role": "user", "content": content }));
+            }
+            MessageRole::Assistant => {
+                for part in &message.parts {
+                    match part {
+                        MessagePart::Text(TextPart { text, .. }) => items.push(json!({
+                            "role": "assistant",
+                            "content": [{ "type": "output_text", "text": text }],
+                        })),
+                        MessagePart::ToolCall(call) => items.push(json!({
+                            "type": "function_call",
+                            "call_id": call.tool_call_id,
+                            "name": call.tool_name,
+                            "arguments": call.input.to_string(),
+                        })),
+                        MessagePart::Reasoning(reasoning) => push_reasoning(&mut items, reasoning),
+                        _ => {}
+                    }
+                }
+            }
+            MessageRole::Tool => {
+                for part in &message.parts {
+                    if let MessagePart::ToolResult(result) = part {
+                        let output = match &result.output {
+                            Value::String(text) => text.clone(),
+                            output => output.to_string(),
+                        };
+                        items.push(json!({
+                            "type": "function_call_output",
+                            "call_id": result.tool_call_id,
+                            "output": output,
+                        }));
+                    }
+                }
+            }
+        }
+    }
+    Ok(items)
+}
+
+/// Sends a reasoning part back as a reasoning item.
+///
+/// Parts without an `itemId` did not come from this API and are dropped.
+/// Consecutive parts of the same item are merged into one item with several
+/// summary entries.
+fn push_reasoning(items: &mut Vec<Value>, reasoning: &ReasoningPart) {
+    let Some(fields) = openai_metadata(reasoning.provider_metadata.as_ref()) else {
+        return;
+    };
+    let Some(item_id) = fields.get("itemId").and_then(Value::as_str) else {
+        return;
+    };
+    let summary = json!({ "type": "summary_text", "text": reasoning.text });
+
+    if let Some(last) = items.last_mut() {
+        if last["type"] == "reasoning" && last["id"] == item_id {
+            if let Some(entries) = last["summary"].as_array_mut() {
+                entries.push(summary);
+            }
+            return;
+        }
+    }
+
+    let mut item = json!({ "type": "reasoning", "id": item_id, "summary": [summary] });
+    if let Some(encrypted) = fields.get("encryptedContent").filter(|v| !v.is_null()) {
+        item["encrypted_content"] = encrypted.clone();
+    }
+    items.push(item);
+}
+
+fn convert_user_part(part: &MessagePart) -> Result<Value> {
+    match part {
+        MessagePart::Text(TextPart { text, .. }) => {
+            Ok(json!({ "type": "input_text", "text": text }))
+        }
+        MessagePart::Image(image) => {
+            let media_type = image.media_type.as_deref().unwrap_or("image/jpeg");
+            Ok(json!({ "type": "input_image", "image_url": image.image.to_url(media_type) }))
+        }
+        MessagePart::File(file) if file.media_type == "application/pdf" => Ok(json!({
+            "type": "input_file",
+            "filename": file.filename.as_deref().unwrap_or("document.pdf"),
+            "file_data": file.data.to_url(&file.media_type),
+        })),
+        MessagePart::File(file) => Err(AiError::Validation(format!(
+            "unsupported file media type '{}'",
+            file.media_type
+        ))),
+        _ => Err(AiError::Validation(
+            "user messages may only contain text, image and file parts".into(),
+        )),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ResponseObject {
+    id: String,
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    incomplete_details: Option<IncompleteDetails>,
+    #[serde(default)]
+    output: Vec<OutputItem>,
+    #[serde(default)]
+    usage: Option<ResponseUsage>,
+    #[serde(default)]
+    error: Option<ErrorBody>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IncompleteDetails {
+    reason: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum OutputItem {
+    Message {
+        #[serde(default)]
+        content: Vec<OutputContent>,
+    },
+    Reasoning {
+        id: String,
+        #[serde(default)]
+        summary: Vec<SummaryText>,
+        #[serde(default)]
+        encrypted_content: Option<String>,
+    },
+    FunctionCall {
+        #[serde(default)]
+        id: Option<String>,
+        call_id: String,
+        name: String,
+        #[serde(default)]
+        arguments: String,
+    },
+    /// Built-in tool calls and future item types.
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum OutputContent {
+    OutputText {
+        text: String,
+    },
+    Refusal {
+        refusal: String,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+struct SummaryText {
+    text: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ResponseUsage {
+    #[serde(default)]
+    input_tokens: u64,
+    #[serde(default)]
+    output_tokens: u64,
+    #[serde(default)]
+    input_tokens_details: Option<InputTokensDetails>,
+    #[serde(default)]
+    output_tokens_details: Option<OutputTokensDetails>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct InputTokensDetails {
+    #[serde(default)]
+    cached_tokens: u64,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct OutputTokensDetails {
+    #[serde(default)]
+    reasoning_tokens: u64,
+}
+
+impl From<ResponseUsage> for Usage {
+    fn from(usage: ResponseUsage) -> Self {
+        Usage {
+            input_tokens: usage.input_tokens,
+            output_tokens: usage.output_tokens,
+            cached_input_tokens: usage.input_tokens_details.map_or(0, |d| d.cached_tokens),
+            reasoning_tokens: usage
+                .output_tokens_details
+                .map_or(0, |d| d.reasoning_tokens),
+            ..Default::default()
+        }
+    }
+}
+
+/// Maps the response status onto a finish reason.
+fn map_finish_reason(
+    status: Option<&str>,
+    incomplete: Option<&IncompleteDetails>,
+    has_tool_calls: bool,
+) -> FinishReason {
+    match (status, incomplete.map(|details| details.reason.as_str())) {
+        (_, Some("max_output_tokens")) => FinishReason::Length,
+        (_, Some("content_filter")) => FinishReason::ContentFilter,
+        (Some("completed"), _) if has_tool_calls => FinishReason::ToolCalls,
+        (Some("completed"), _) => FinishReason::Stop,
+        (Some("failed"), _) => FinishReason::Error,
+        (Some(_), _) => FinishReason::Other,
+        (None, _) => FinishReason::Unknown,
+    }
+}
+
+#[async_trait]
+impl LanguageModel for OpenAiResponsesModel {
+    fn provider(&self) -> &str {
+        self.provider.name()
+    }
+
+    fn model_id(&self) -> &str {
+        &self.model_id
+    }
+
+    fn capabilities(&self) -> ModelCapabilities {
+        model_capabilities(&self.model_id)
+    }
+
+    async fn do_generate(&self, options: CallOptions) -> Result<ModelResponse> {
+        let (body, warnings) = self.request_body(&options, false)?;
+        let response: ResponseObject = self.send(&options, &body).await?.json().await?;
+        if let Some(error) = response.error {
+            return Err(error.into_error(self.provider.name()));
+        }
+
+        let mut content = Vec::new();
+        for item in response.output {
+            match item {
+                OutputItem::Message { content: parts } => {
+                    for part in parts {
+                        match part {
+                            OutputContent::OutputText { text } => {
+                                content.push(MessagePart::text(text));
+                            }
+                            OutputContent::Refusal { refusal } => {
+                                content.push(MessagePart::text(refusal));
+                            }
+                            OutputContent::Other => {}
+                        }
+                    }
+                }
+                OutputItem::Reasoning {
+                    id,
+                    summary,
+                    encrypted_content,
+                } => {
+                    let fields = reasoning_metadata(&id, encrypted_content.as_deref());
+                    // Keep the item even without a summary so encrypted
+                    // reasoning can be sent back.
+                    let texts = if summary.is_empty() {
+                        vec![String::new()]
+                    } else {
+                        summary.into_iter().map(|s| s.text).collect()
+                    };
+                    for text in texts {
+                        content.push(MessagePart::Reasoning(ReasoningPart {
+                            text,
+                            provider_metadata: Some(fields.clone()),
+                        }));
+                    }
+                }
+                OutputItem::FunctionCall {
+                    id,
+                    call_id,
+                    name,
+                    arguments,
+                } => content.push(MessagePart::ToolCall(ToolCallPart {
+                    tool_call_id: call_id,
+                    tool_name: name,
+                    input: parse_arguments(&arguments),
+                    provider_metadata: id.map(|id| metadata(json!({ "itemId": id }))),
+                })),
+                OutputItem::Other => {}
+            }
+        }
+
+        let has_tool_calls = content
+            .iter()
+            .any(|part| matches!(part, MessagePart::ToolCall(_)));
+        Ok(ModelResponse {
+            content,
+            finish_reason: map_finish_reason(
+                response.status.as_deref(),
+                response.incomplete_details.as_ref(),
+                has_tool_calls,
+            ),
+            usage: response.usage.map(Usage::from).unwrap_or_default(),
+            sources: Vec::new(),
+            warnings,
+            provider_metadata: Some(metadata(json!({ "responseId": response.id }))),
+        })
+    }
+
+    async fn do_stream(&self, options: CallOptions) -> Result<ModelStream> {
+        let (body, warnings) = self.request_body(&options, true)?;
+        let response = self.send(&options, &body).await?;
+
+        let events = sse::decode(response.bytes_stream()).boxed();
+        let state = EventState::new(self.provider.name());
+        Ok(ModelStream {
+            stream: decode_events(events, state).boxed(),
+            warnings,
+        })
+    }
+}
+
+/// A streaming event, identified by its `type` field.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type")]
+enum ResponseEvent {
+    #[serde(rename = "response.created")]
+    Created { response: ResponseObject },
+    #[serde(rename = "response.output_text.delta")]
+    TextDelta { delta: String },
+    #[serde(rename = "response.reasoning_summary_text.delta")]
+    ReasoningDelta { delta: String },
+    #[serde(rename = "response.output_item.added")]
+    ItemAdded { item: OutputItem },
+    #[serde(rename = "response.function_call_arguments.delta")]
+    ArgumentsDelta { item_id: String, delta: String },
+    #[serde(rename = "response.output_item.done")]
+    ItemDone { item: OutputItem },
+    #[serde(rename = "response.completed", alias = "response.incomplete")]
+    Completed { response: ResponseObject },
+    #[serde(rename = "response.failed")]
+    Failed { response: ResponseObject },
+    #[serde(rename = "error")]
+    Error {
+        #[serde(flatten)]
+        error: ErrorBody,
+    },
+    #[serde(other)]
+    Other,
+}
+
+type Parts = Emitter<StreamPart, AiError>;
+
+/// Turns Responses API events into [`StreamPart`]s.
+///
+/// Argument deltas refer to the output item id, while tool calls are
+/// identified by their `call_id`; `call_ids` maps one to the other.
+struct EventState {
+    provider: String,
+    call_ids: HashMap<String, String>,
+    has_tool_calls: bool,
+}
+
+impl EventState {
+    fn new(provider: &str) -> Self {
+        Self {
+            provider: provider.to_string(),
+            call_ids: HashMap::new(),
+            has_tool_calls: false,
+        }
+    }
+}
+
+impl EventDecoder for EventState {
+    type Event = SseEvent;
+    type Item = StreamPart;
+    type Error = AiError;
+
+    fn decode(&mut self, event: SseEvent, out: &mut Parts) {
+        let event: ResponseEvent = match serde_json::from_str(&event.data) {
+            Ok(event) => event,
+            Err(error) => return out.fail(error.into()),
+        };
+
+        match event {
+            ResponseEvent::Created { response } => out.push(StreamPart::Metadata {
+                provider_metadata: metadata(json!({ "responseId": response.id })),
+            }),
+            ResponseEvent::TextDelta { delta } => out.push(StreamPart::TextDelta { text: delta }),
+            ResponseEvent::ReasoningDelta { delta } => {
+                out.push(StreamPart::ReasoningDelta { text: delta });
+            }
+            ResponseEvent::ItemAdded {
+                item:
+                    OutputItem::FunctionCall {
+                        id, call_id, name, ..
+                    },
+            } => {
+                self.call_ids
+                    .insert(id.unwrap_or_else(|| call_id.clone()), call_id.clone());
+                self.has_tool_calls = true;
+                out.push(StreamPart::ToolCallStart {
+                    id: call_id,
+                    tool_name: name,
+                });
+            }
+            ResponseEvent::ArgumentsDelta { item_id, delta } => {
+                if let Some(id) = self.call_ids.get(&item_id).cloned() {
+                    out.push(StreamPart::ToolCallDelta {
+                        id,
+                        input_delta: delta,
+                    });
+                }
+            }
+            ResponseEvent::ItemDone {
+                item: OutputItem::FunctionCall { call_id, .. },
+            } => out.push(StreamPart::ToolCallEnd { id: call_id }),
+            ResponseEvent::ItemDone {
+                item:
+                    OutputItem::Reasoning {
+                        id,
+                        encrypted_content,
+                        ..
+                    },
+            } => out.push(StreamPart::ReasoningEnd {
+                provider_metadata: Some(reasoning_metadata(&id, encrypted_content.as_deref())),
+            }),
+            ResponseEvent::Completed { response } => {
+                let finish_reason = map_finish_reason(
+                    response.status.as_deref(),
+                    response.incomplete_details.as_ref(),
+                    self.has_tool_calls,
+                );
+                out.push(StreamPart::Finish {
+                    finish_reason,
+                    usage: response.usage.map(Usage::from).unwrap_or_default(),
+                });
+                out.close();
+            }
+            ResponseEvent::Failed { response } => {
+                let error = match response.error {
+                    Some(error) => error.into_error(&self.provider),
+                    None => AiError::Provider {
+                        provider: self.provider.clone(),
+                        message: "response failed".into(),
+                        code: None,
+                        details: None,
+                    },
+                };
+                out.fail(error);
+            }
+            ResponseEvent::Error { error } => out.fail(error.into_error(&self.provider)),
+            ResponseEvent::ItemAdded { .. }
+            | ResponseEvent::ItemDone { .. }
+            | ResponseEvent::Other => {}
+        }
+    }
+
+    fn end(&mut self, out: &mut Parts) {
+        out.fail(AiError::Stream(
+            "response stream ended before completion".into(),
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ai_core::{
+        generate_text, stream_text, GenerateTextRequest, StreamTextRequest, ToolDefinition,
+    };
+    use mockito::Matcher;
+
+    #[tokio::test]
+    async fn test_generate_maps_reasoning_and_chains_responses() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/responses")
+            .match_header("authorization", "Bearer test-key")
+            .match_body(Matcher::PartialJson(json!({
+                "model": "o4-mini",
+                "previous_response_id": "resp_0",
+                "reasoning": { "effort": "low", "summary": "auto" },
+                "input": [{ "role": "user", "content": [{ "type": "input_text", "text": "And in Oslo?" }] }],
+            })))
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "id": "resp_1",
+                    "status": "completed",
+                    "output": [
+                        {
+                            "type": "reasoning",
+                            "id": "rs_1",
+                            "summary": [{ "type": "summary_text", "text": "Compare climates." }],
+                        },
+                        {
+                            "type": "message",
+                            "role": "assistant",
+                            "content": [{ "type": "output_text", "text": "Colder." }],
+                        },
+                    ],
+                    "usage": {
+                        "input_tokens": 30,
+                        "output_tokens": 12,
+                        "output_tokens_details": { "reasoning_tokens": 8 },
+                    },
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let model = OpenAiProvider::new("test-key")
+            .with_base_url(server.url())
+            .responses_model("o4-mini");
+        let response = generate_text(GenerateTextRequest::new(model, "And in Oslo?").provider_options(
+            "openai",
+            json!({ "previousResponseId": "resp_0", "reasoningEffort": "low", "reasoningSummary": "auto" }),
+        ))
+        .await
+        .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(response.text, "Colder.");
+        assert_eq!(response.finish_reason, FinishReason::Stop);
+        assert_eq!(response.usage.reasoning_tokens, 8);
+        assert_eq!(
+            response.provider_metadata.as_ref().unwrap()["openai"]["responseId"],
+            "resp_1"
+        );
+
+        let MessagePart::Reasoning(reasoning) = &response.content[0] else {
+            panic!("expected a reasoning part first");
+        };
+        assert_eq!(reasoning.text, "Compare climates.");
+        assert_eq!(
+            reasoning.provider_metadata.as_ref().unwrap()["openai"]["itemId"],
+            "rs_1"
+        );
+
+        // Reasoning parts round-trip as reasoning items.
+        let input = convert_input(&[response.to_message()]).unwrap();
+        assert_eq!(input[0]["type"], "reasoning");
+        assert_eq!(input[0]["id"], "rs_1");
+        assert_eq!(input[1]["content"][0]["text"], "Colder.");
+    }
+
+    #[tokio::test]
+    async fn test_stream_emits_tool_calls_and_response_id() {
+        let events = [
+            json!({ "type": "response.created", "response": { "id": "resp_2", "status": "in_progress", "output": [] } }),
+            json!({ "type": "response.reasoning_summary_text.delta", "item_id": "rs_1", "delta": "Need weather." }),
+            json!({ "type": "response.output_item.done", "item": {
+                "type": "reasoning", "id": "rs_1", "encrypted_content": "gAAA",
+                "summary": [{ "type": "summary_text", "text": "Need weather." }],
+            } }),
+            json!({ "type": "response.output_item.added", "item": {
+                "type": "function_call", "id": "fc_1", "call_id": "call_1", "name": "weather", "arguments": "",
+            } }),
+            json!({ "type": "response.function_call_arguments.delta", "item_id": "fc_1", "delta": "{\"city\":" }),
+            json!({ "type": "response.function_call_arguments.delta", "item_id": "fc_1", "delta": "\"Oslo\"}" }),
+            json!({ "type": "response.output_item.done", "item": {
+                "type": "function_call", "id": "fc_1", "call_id": "call_1", "name": "weather", "arguments": "{\"city\":\"Oslo\"}",
+            } }),
+            json!({ "type": "response.completed", "response": {
+                "id": "resp_2", "status": "completed", "output": [],
+                "usage": { "input_tokens": 10, "output_tokens": 6 },
+            } }),
+        ];
+        let mut body = String::new();
+        for event in &events {
+            body.push_str(&format!(
+                "event: {}\ndata: {event}\n\n",
+                event["type"].as_str().unwrap()
+            ));
+        }
+
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/responses")
+            .match_body(Matcher::PartialJson(json!({
+                "stream": true,
+                "tools": [{ "type": "function", "name": "weather" }],
+            })))
+            .with_header("content-type", "text/event-stream")
+            .with_body(body)
+            .create_async()
+            .await;
+
+        let model = OpenAiProvider::new("test-key")
+            .with_base_url(server.url())
+            .responses_model("gpt-4.1");
+        let tool = ToolDefinition::new("weather", "Current weather", json!({ "type": "object" }));
+        let handle = stream_text(StreamTextRequest::new(model, "Weather in Oslo?").tool(tool))
+            .await
+            .unwrap();
+        let result = handle.result().await.unwrap();
+
+        assert_eq!(result.reasoning, "Need weather.");
+        assert_eq!(result.tool_calls.len(), 1);
+        assert_eq!(result.tool_calls[0].tool_call_id, "call_1");
+        assert_eq!(result.tool_calls[0].input, json!({ "city": "Oslo" }));
+        assert_eq!(result.finish_reason, FinishReason::ToolCalls);
+        assert_eq!(result.usage, Usage::new(10, 6));
+        assert_eq!(
+            result.provider_metadata.as_ref().unwrap()["openai"]["responseId"],
+            "resp_2"
+        );
+
+        // Streamed reasoning round-trips as a reasoning item.
+        let input = convert_input(&[result.to_message()]).unwrap();
+        assert_eq!(input[0]["type"], "reasoning");
+        assert_eq!(input[0]["id"], "rs_1");
+        assert_eq!(input[0]["encrypted_content"], "gAAA");
+        assert_eq!(input[1]["type"], "function_call");
+    }
+
+    #[tokio::test]
+    async fn test_maps_http_errors_like_chat() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/responses")
+            .with_status(400)
+            .with_body(r#"{"error":{"message":"Previous response not found","type":"invalid_request_error","code":"previous_response_not_found"}}"#)
+            .create_async()
+            .await;
+
+        let model = OpenAiProvider::new("test-key")
+            .with_base_url(server.url())
+            .responses_model("gpt-4.1");
+        let error = generate_text(GenerateTextRequest::new(model, "hi"))
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            error,
+            AiError::Provider { code: Some(code), .. } if code == "previous_response_not_found"
+        ));
+    }
+}
@@ -0,0 +1,490 @@
+//! Routing across an ordered list of models.
+
+use async_trait::async_trait;
+use futures::stream::{self, StreamExt};
+use serde_json::{json, Value};
+
+use ai_core::{
+    CallOptions, LanguageModel, ModelCapabilities, ModelResponse, ModelStream, ProviderMetadata,
+    StreamPart,
+};
+use ai_error::{AiError, Result};
+
+/// Key under which the serving backend is recorded in the provider metadata.
+const METADATA_KEY: &str = "fallback";
+
+/// Serves each call from the first model in a list that succeeds.
+///
+/// A call moves on to the next model when the current one fails with an
+/// error that [`AiError::is_retryable`] accepts (rate limits, network
+/// failures, timeouts), or with a code configured through
+/// [`fallback_on`](Self::fallback_on). Any other error, and the error of the
+/// last model, is returned as is.
+///
+/// The model that served the call is recorded in the provider metadata
+/// under `"fallback"`, together with the errors of the models tried before
+/// it. [`served_by`](LanguageModel::served_by) reads it back, so usage is
+/// priced against that model rather than the first one:
+///
+/// ```json
+/// { "provider": "anthropic", "modelId": "claude-sonnet-4-5", "index": 1,
+///   "errors": [{ "provider": "openai", "modelId": "gpt-4.1",
+///                "code": "RATE_LIMIT_ERROR", "message": "..." }] }
+/// ```
+///
+/// When streaming, a model is only abandoned until it emits its first delta.
+/// Once output has reached the caller, later errors end the stream instead,
+/// since the next model would start the answer over.
+///
+/// ```no_run
+/// use ai_providers::{FallbackModel, ProviderRegistry};
+///
+/// # fn run() -> ai_error::Result<()> {
+/// let registry = ProviderRegistry::from_env();
+/// let model = FallbackModel::new(vec![
+///     registry.language_model("openai:gpt-4.1")?,
+///     registry.language_model("anthropic:claude-sonnet-4-5")?,
+/// ])
+/// .fallback_on("overloaded_error");
+/// # Ok(())
+/// # }
+/// ```
+pub struct FallbackModel {
+    models: Vec<Box<dyn LanguageModel>>,
+    codes: Vec<String>,
+}
+
+impl FallbackModel {
+    /// Creates a model that tries `models` in order.
+    ///
+    /// Calls fail with [`AiError::Config`] while the list is empty.
+    pub fn new(models: Vec<Box<dyn LanguageModel>>) -> Self {
+        Self {
+            models,
+            codes: Vec::new(),
+        }
+    }
+
+    /// Appends `model` to the end of the list.
+    pub fn with_model(mut self, model: Box<dyn LanguageModel>) -> Self {
+        self.models.push(model);
+        self
+    }
+
+    /// Also falls back on errors with `code`, matched against both
+    /// [`AiError::error_code`] (e.g. `"AUTH_ERROR"`) and the vendor code of
+    /// [`AiError::Provider`] (e.g. `"overloaded_error"`).
+    pub fn fallback_on(mut self, code: impl Into<String>) -> Self {
+        self.codes.push(code.into());
+        self
+    }
+
+    /// The wrapped models, in the order they are tried.
+    pub fn models(&self) -> &[Box<dyn LanguageModel>] {
+        &self.models
+    }
+
+    fn first(&self) -> Result<&dyn LanguageModel> {
+        self.models.first().map(AsRef::as_ref).ok_or_else(no_models)
+    }
+
+    fn should_fall_back(&self, error: &AiError) -> bool {
+        if error.is_retryable() {
+            return true;
+        }
+        let vendor_code = match error {
+            AiError::Provider { code, .. } => code.as_deref(),
+            _ => None,
+        };
+        self.codes
+            .iter()
+            .any(|code| code == error.error_code() || Some(code.as_str()) == vendor_code)
+    }
+}
+
+fn no_models() -> AiError {
+    AiError::Config("fallback model has no models".into())
+}
+
+/// Errors of the models tried before the one that served the call.
+#[derive(Default)]
+struct Attempts(Vec<Value>);
+
+impl Attempts {
+    fn record(&mut self, model: &dyn LanguageModel, error: &AiError) {
+        let code = match error {
+            AiError::Provider {
+                code: Some(code), ..
+            } => code.as_str(),
+            _ => error.error_code(),
+        };
+        self.0.push(json!({
+            "provider": model.provider(),
+            "modelId": model.model_id(),
+            "code": code,
+            "message": error.to_string(),
+        }));
+    }
+
+    fn metadata(self, model: &dyn LanguageModel, index: usize) -> Value {
+        json!({
+            "provider": model.provider(),
+            "modelId": model.model_id(),
+            "index": index,
+            "errors": self.0,
+        })
+    }
+}
+
+/// True for parts that carry output, after which a stream can no longer be
+/// replaced by another model's.
+fn is_delta(part: &StreamPart) -> bool {
+    matches!(
+        part,
+        StreamPart::TextDelta { .. }
+            | StreamPart::ReasoningDelta { .. }
+            | StreamPart::ReasoningEnd { .. }
+            | StreamPart::ToolCallStart { .. }
+            | StreamPart::ToolCallDelta { .. }
+            | StreamPart::ToolCallEnd { .. }
+            | StreamPart::Source(_)
+    )
+}
+
+/// The capabilities all of `models` have, so a request that passes
+/// validation can be served by any of them.
+pub(crate) fn common_capabilities<'a>(
+    models: impl Iterator<Item = &'a dyn LanguageModel>,
+) -> ModelCapabilities {
+    let mut capabilities = models.map(|model| model.capabilities());
+    let first = capabilities.next().unwrap_or_default();
+    capabilities.fold(first, |all, next| ModelCapabilities {
+        tool_calling: all.tool_calling && next.tool_calling,
+        structured_outputs: all.structured_outputs && next.structured_outputs,
+        image_input: all.image_input && next.image_input,
+        file_input: all.file_input && next.file_input,
+        reasoning: all.reasoning && next.reasoning,
+        streaming: all.streaming && next.streaming,
+        max_context_tokens: match (all.max_context_tokens, next.max_context_tokens) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            _ => None,
+        },
+    })
+}
+
+#[async_trait]
+impl LanguageModel for FallbackModel {
+    /// The provider of the first model.
+    fn provider(&self) -> &str {
+        self.first().map_or("fallback", |model| model.provider())
+    }
+
+    /// The id of the first model.
+    fn model_id(&self) -> &str {
+        self.first().map_or("", |model| model.model_id())
+    }
+
+    /// The capabilities every wrapped model has.
+    fn capabilities(&self) -> ModelCapabilities {
+        common_capabilities(self.models.iter().map(AsRef::as_ref))
+    }
+
+    /// The model recorded under `"fallback"`, or the first model.
+    fn served_by<'a>(
+        &'a self,
+        provider_metadata: Option<&'a ProviderMetadata>,
+    ) -> (&'a str, &'a str) {
+        let served = provider_metadata
+            .and_then(|metadata| metadata.get(METADATA_KEY)?.get("index")?.as_u64())
+            .and_then(|index| self.models.get(usize::try_from(index).ok()?));
+        match served {
+            Some(model) => model.served_by(provider_metadata),
+            None => (self.provider(), self.model_id()),
+        }
+    }
+
+    async fn do_generate(&self, options: CallOptions) -> Result<ModelResponse> {
+        let mut attempts = Attempts::default();
+        let last = self.models.len().saturating_sub(1);
+
+        for (index, model) in self.models.iter().enumerate() {
+            match model.do_generate(options.clone()).await {
+                Ok(mut response) => {
+                    response
+                        .provider_metadata
+                        .get_or_insert_with(Default::default)
+                        .insert(
+                            METADATA_KEY.to_string(),
+                            attempts.metadata(model.as_ref(), index),
+                        );
+                    return Ok(response);
+                }
+                Err(error) if index < last && self.should_fall_back(&error) => {
+                    attempts.record(model.as_ref(), &error);
+                }
+                Err(error) => return Err(error),
+            }
+        }
+        // Only reached when there are no models; the last one always returns.
+        Err(no_models())
+    }
+
+    async fn do_stream(&self, options: CallOptions) -> Result<ModelStream> {
+        let mut attempts = Attempts::default();
+        let last = self.models.len().saturating_sub(1);
+
+        'models: for (index, model) in self.models.iter().enumerate() {
+            let ModelStream {
+                mut stream,
+                warnings,
+            } = match model.do_stream(options.clone()).await {
+                Ok(stream) => stream,
+                Err(error) if index < last && self.should_fall_back(&error) => {
+                    attempts.record(model.as_ref(), &error);
+                    continue;
+                }
+                Err(error) => return Err(error),
+            };
+
+            // Hold back everything up to the first delta so nothing reaches
+            // the caller from a model that is then abandoned.
+            let mut buffered = Vec::new();
+            while let Some(item) = stream.next().await {
+                match item {
+                    Ok(StreamPart::Error(error))
+                        if index < last && self.should_fall_back(&error) =>
+                    {
+                        attempts.record(model.as_ref(), &error);
+                        continue 'models;
+                    }
+                    Ok(part) => {
+                        let committed =
+                            is_delta(&part) || matches!(part, StreamPart::Finish { .. });
+                        buffered.push(Ok(part));
+                        if committed {
+                            break;
+                        }
+                    }
+                    Err(error) if index < last && self.should_fall_back(&error) => {
+                        attempts.record(model.as_ref(), &error);
+                        continue 'models;
+                    }
+                    Err(error) => {
+                        buffered.push(Err(error));
+                        break;
+                    }
+                }
+            }
+
+            let served = StreamPart::Metadata {
+                provider_metadata: [(
+                    METADATA_KEY.to_string(),
+                    attempts.metadata(model.as_ref(), index),
+                )]
+                .into(),
+            };
+            let prefix = std::iter::once(Ok(served)).chain(buffered);
+            return Ok(ModelStream {
+                stream: stream::iter(prefix).chain(stream).boxed(),
+                warnings,
+            });
+        }
+        // Only reached when there are no models; the last one always returns.
+        Err(no_models())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::StubModel;
+    use ai_core::{
+        FinishReason, GenerateTextRequest, ModelPricing, StaticPricingTable, StreamTextRequest,
+        Usage,
+    };
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn rate_limited() -> AiError {
+        AiError::RateLimit {
+            retry_after: Some(Duration::from_secs(30)),
+        }
+    }
+
+    fn overloaded() -> AiError {
+        AiError::Provider {
+            provider: "anthropic".into(),
+            message: "Overloaded".into(),
+            code: Some("overloaded_error".into()),
+            details: None,
+        }
+    }
+
+    fn text(text: &str) -> Result<StreamPart> {
+        Ok(StreamPart::TextDelta { text: text.into() })
+    }
+
+    fn finish() -> Result<StreamPart> {
+        Ok(StreamPart::Finish {
+            finish_reason: FinishReason::Stop,
+            usage: Usage::default(),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_generate_falls_back_on_retryable_and_configured_errors() {
+        let openai = StubModel {
+            generate: || Err(rate_limited()),
+            ..StubModel::new("openai", "stub")
+        };
+        let anthropic = StubModel {
+            generate: || Err(overloaded()),
+            ..StubModel::new("anthropic", "stub")
+        };
+        let google = StubModel {
+            generate: || {
+                Ok(ModelResponse {
+                    content: vec![ai_core::MessagePart::text("hello")],
+                    ..Default::default()
+                })
+            },
+            ..StubModel::new("google", "stub")
+        };
+        let model = FallbackModel::new(vec![Box::new(openai)])
+            .with_model(Box::new(anthropic))
+            .with_model(Box::new(google))
+            .fallback_on("overloaded_error");
+
+        let result = ai_core::generate_text(GenerateTextRequest::new(model, "hi"))
+            .await
+            .unwrap();
+        assert_eq!(result.text, "hello");
+
+        let served = &result.provider_metadata.unwrap()["fallback"];
+        assert_eq!(served["provider"], "google");
+        assert_eq!(served["index"], 2);
+        assert_eq!(served["errors"][0]["code"], "RATE_LIMIT_ERROR");
+        assert_eq!(served["errors"][1]["code"], "overloaded_error");
+    }
+
+    #[tokio::test]
+    async fn test_generate_returns_other_errors_without_falling_back() {
+        let backup = StubModel::new("anthropic", "stub");
+        let calls = backup.calls.clone();
+        let primary = StubModel {
+            generate: || Err(AiError::Auth("invalid key".into())),
+            ..StubModel::new("openai", "stub")
+        };
+        let model = FallbackModel::new(vec![Box::new(primary), Box::new(backup)]);
+
+        let error = model.do_generate(CallOptions::default()).await.unwrap_err();
+        assert!(matches!(error, AiError::Auth(_)));
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        // The last model's error is returned even if it is retryable.
+        let model = FallbackModel::new(vec![Box::new(StubModel {
+            generate: || Err(rate_limited()),
+            ..StubModel::new("openai", "stub")
+        })]);
+        let error = model.do_generate(CallOptions::default()).await.unwrap_err();
+        assert!(matches!(error, AiError::RateLimit { .. }));
+
+        let model = FallbackModel::new(Vec::new());
+        let error = model.do_generate(CallOptions::default()).await.unwrap_err();
+        assert!(matches!(error, AiError::Config(_)));
+        let error = model.do_stream(CallOptions::default()).await.err().unwrap();
+        assert!(matches!(error, AiError::Config(_)));
+    }
+
+    #[tokio::test]
+    async fn test_stream_falls_back_only_before_first_delta() {
+        let primary = StubModel {
+            stream: || vec![Err(rate_limited())],
+            ..StubModel::new("openai", "stub")
+        };
+        let backup = StubModel {
+            stream: || vec![text("Hel"), Err(rate_limited())],
+            ..StubModel::new("anthropic", "stub")
+        };
+        let last = StubModel {
+            stream: || vec![text("unused"), finish()],
+            ..StubModel::new("google", "stub")
+        };
+        let calls = last.calls.clone();
+        let model = FallbackModel::new(vec![Box::new(primary), Box::new(backup)])
+            .with_model(Box::new(last));
+
+        let handle = ai_core::stream_text(StreamTextRequest::new(model, "hi"))
+            .await
+            .unwrap();
+        let error = handle.result().await.unwrap_err();
+        assert!(matches!(*error, AiError::RateLimit { .. }));
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        let primary = StubModel {
+            stream: || vec![Err(rate_limited())],
+            ..StubModel::new("openai", "stub")
+        };
+        let backup = StubModel {
+            stream: || vec![text("Hello"), finish()],
+            ..StubModel::new("anthropic", "stub")
+        };
+        let model = FallbackModel::new(vec![Box::new(primary), Box::new(backup)]);
+        let handle = ai_core::stream_text(StreamTextRequest::new(model, "hi"))
+            .await
+            .unwrap();
+        let result = handle.result().await.unwrap();
+        assert_eq!(result.text, "Hello");
+        assert_eq!(
+            result.provider_metadata.unwrap()["fallback"]["provider"],
+            "anthropic"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cost_is_estimated_for_the_serving_model() {
+        let pricing = Arc::new(
+            StaticPricingTable::new()
+                .with("openai", "gpt-4.1", ModelPricing::new(2.0, 8.0))
+                .with(
+                    "anthropic",
+                    "claude-sonnet-4-5",
+                    ModelPricing::new(3.0, 15.0),
+                ),
+        );
+        let primary = || StubModel {
+            generate: || Err(rate_limited()),
+            stream: || vec![Err(rate_limited())],
+            ..StubModel::new("openai", "gpt-4.1")
+        };
+        let backup = || StubModel {
+            generate: || {
+                Ok(ModelResponse {
+                    usage: Usage::new(1_000_000, 0),
+                    ..Default::default()
+                })
+            },
+            stream: || {
+                vec![Ok(StreamPart::Finish {
+                    finish_reason: FinishReason::Stop,
+                    usage: Usage::new(1_000_000, 0),
+                })]
+            },
+            ..StubModel::new("anthropic", "claude-sonnet-4-5")
+        };
+
+        let model = FallbackModel::new(vec![Box::new(primary()), Box::new(backup())]);
+        let request = GenerateTextRequest::new(model, "hi").pricing(pricing.clone());
+        let response = ai_core::generate_text(request).await.unwrap();
+        assert_eq!(response.cost.unwrap().total_cost(), 3.0);
+
+        let model = FallbackModel::new(vec![Box::new(primary()), Box::new(backup())]);
+        let request = StreamTextRequest::new(model, "hi").pricing(pricing);
+        let handle = ai_core::stream_text(request).await.unwrap();
+        assert_eq!(
+            handle.result().await.unwrap().cost.unwrap().total_cost(),
+            3.0
+        );
+    }
+}
@@ -1,14 +1,70 @@
-pub fn add(left: usize, right: usize) -> usize {
-    left + right
-}
+//! Provider registry for the AI SDK.
+//!
+//! Lets applications resolve models from configuration strings such as
+//! `"openai:gpt-4.1-mini"` or `"ollama:llama3"`, so switching models is a
+//! config change rather than a rebuild.
+//!
+//! ```no_run
+//! use ai_providers::ProviderRegistry;
+//!
+//! # fn run() -> ai_error::Result<()> {
+//! // Registers every backend enabled through Cargo features.
+//! let registry = ProviderRegistry::from_env();
+//! let model = registry.language_model("openai:gpt-4.1-mini")?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # Features
+//!
+//! Each backend is re-exported under its own module when its feature is
+//! enabled:
+//!
+//! - `provider-openai` (default) — `openai`
+//! - `provider-azure` — `azure`
+//! - `provider-anthropic` — `anthropic`
+//! - `provider-google` — `google`
+//! - `provider-ollama` — `ollama`
+//! - `provider-bedrock` — `bedrock`
+//! - `provider-mistral` — `mistral`
+//!
+//! Build with `default-features = false, features = ["provider-ollama"]` for
+//! a binary that only talks to a local Ollama server.
+//!
+//! # Fallbacks and pools
+//!
+//! [`FallbackModel`] wraps models from several providers and moves on to the
+//! next one when a call hits a rate limit, a network failure or another
+//! configured error.
+//!
+//! [`ModelPool`] spreads calls across equivalent deployments, such as one
+//! per region, and takes members that keep hitting rate limits or network
+//! errors out of rotation for a while.
 
+#![forbid(unsafe_code)]
+#![warn(missing_docs, rust_2018_idioms)]
+
+mod fallback;
+mod pool;
+mod registry;
 #[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
-    }
-}
+mod test_support;
+
+pub use fallback::FallbackModel;
+pub use pool::{ModelPool, PoolStrategy};
+pub use registry::{ProviderFactory, ProviderRegistry};
+
+#[cfg(feature = "provider-anthropic")]
+pub use ai_providers_anthropic as anthropic;
+#[cfg(feature = "provider-azure")]
+pub use ai_providers_azure as azure;
+#[cfg(feature = "provider-bedrock")]
+pub use ai_providers_bedrock as bedrock;
+#[cfg(feature = "provider-google")]
+pub use ai_providers_google as google;
+#[cfg(feature = "provider-mistral")]
+pub use ai_providers_mistral as mistral;
+#[cfg(feature = "provider-ollama")]
+pub use ai_providers_ollama as ollama;
+#[cfg(feature = "provider-openai")]
+pub use ai_providers_openai as openai;
@@ -0,0 +1,520 @@
+//! Load balancing across equivalent deployments.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use futures::stream::{self, StreamExt};
+use serde_json::json;
+
+use crate::fallback::common_capabilities;
+use ai_core::{
+    CallOptions, LanguageModel, ModelCapabilities, ModelResponse, ModelStream, ProviderMetadata,
+    StreamPart,
+};
+use ai_error::{AiError, Result};
+
+/// Key under which the serving member is recorded in the provider metadata.
+const METADATA_KEY: &str = "pool";
+
+/// How a [`ModelPool`] picks the member that serves a call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PoolStrategy {
+    /// Each member in turn.
+    #[default]
+    RoundRobin,
+    /// Each member in proportion to its weight, interleaved so that a heavy
+    /// member does not receive its share in bursts.
+    Weighted,
+    /// The member with the fewest calls in progress, counting streams until
+    /// they are dropped.
+    LeastInFlight,
+}
+
+/// Spreads calls across equivalent models, such as one deployment per
+/// region.
+///
+/// A member is ejected for a while after a number of consecutive
+/// [`AiError::RateLimit`] or [`AiError::Network`] errors (3 and 30 seconds by
+/// default, see [`with_ejection`](Self::with_ejection)), or for the
+/// `retry_after` of the last rate limit if that is longer. Any successful
+/// call resets the count. If every member is ejected, the one that comes
+/// back first is used anyway rather than failing the call.
+///
+/// A failed call is not retried on another member; wrap the pool in a
+/// [`FallbackModel`](crate::FallbackModel) for that. The member that served
+/// the call is recorded in the provider metadata under `"pool"` as
+/// `{ "provider", "modelId", "index" }`, and usage is priced against that
+/// member.
+///
+/// ```no_run
+/// use ai_providers::{ModelPool, PoolStrategy, ProviderRegistry};
+///
+/// # fn run() -> ai_error::Result<()> {
+/// let registry = ProviderRegistry::from_env();
+/// let model = ModelPool::new(PoolStrategy::Weighted)
+///     .with_member(registry.language_model("azure:gpt-4.1-eastus")?, 3)
+///     .with_member(registry.language_model("azure:gpt-4.1-westeurope")?, 1);
+/// # Ok(())
+/// # }
+/// ```
+pub struct ModelPool {
+    strategy: PoolStrategy,
+    members: Vec<Arc<Member>>,
+    next: AtomicUsize,
+    /// Running score of each member for [`PoolStrategy::Weighted`].
+    scores: Mutex<Vec<i64>>,
+    ejection: Ejection,
+}
+
+/// When members are taken out of rotation.
+#[derive(Debug, Clone, Copy)]
+struct Ejection {
+    failures: u32,
+    duration: Duration,
+}
+
+struct Member {
+    model: Box<dyn LanguageModel>,
+    weight: u32,
+    in_flight: AtomicUsize,
+    state: Mutex<MemberState>,
+}
+
+#[derive(Default)]
+struct MemberState {
+    /// Consecutive rate limit and network errors.
+    failures: u32,
+    ejected_until: Option<Instant>,
+}
+
+impl Member {
+    fn state(&self) -> std::sync::MutexGuard<'_, MemberState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn is_available(&self, now: Instant) -> bool {
+        self.state()
+            .ejected_until
+            .map_or(true, |until| until <= now)
+    }
+
+    fn record_success(&self) {
+        let mut state = self.state();
+        state.failures = 0;
+        state.ejected_until = None;
+    }
+
+    fn record_error(&self, error: &AiError, ejection: Ejection) {
+        let retry_after = match error {
+            AiError::RateLimit { retry_after } => *retry_after,
+            AiError::Network(_) => None,
+            _ => return,
+        };
+
+        let mut state = self.state();
+        state.failures += 1;
+        if state.failures >= ejection.failures {
+            let duration = retry_after.map_or(ejection.duration, |d| d.max(ejection.duration));
+            state.ejected_until = Some(Instant::now() + duration);
+            state.failures = 0;
+        }
+    }
+}
+
+/// Counts a call as in flight until dropped.
+struct InFlight(Arc<Member>);
+
+impl InFlight {
+    fn new(member: &Arc<Member>) -> Self {
+        member.in_flight.fetch_add(1, Ordering::SeqCst);
+        Self(member.clone())
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.0.in_flight.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl ModelPool {
+    /// Creates an empty pool that picks members with `strategy`.
+    pub fn new(strategy: PoolStrategy) -> Self {
+        Self {
+            strategy,
+            members: Vec::new(),
+            next: AtomicUsize::new(0),
+            scores: Mutex::default(),
+            ejection: Ejection {
+                failures: 3,
+                duration: Duration::from_secs(30),
+            },
+        }
+    }
+
+    /// Adds `model` to the pool. `weight` is only used by
+    /// [`PoolStrategy::Weighted`], which never picks a member of weight zero.
+    pub fn with_member(mut self, model: Box<dyn LanguageModel>, weight: u32) -> Self {
+        self.members.push(Arc::new(Member {
+            model,
+            weight,
+            in_flight: AtomicUsize::new(0),
+            state: Mutex::default(),
+        }));
+        self.scores
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(0);
+        self
+    }
+
+    /// Ejects a member for `duration` after `failures` consecutive rate
+    /// limit or network errors.
+    pub fn with_ejection(mut self, failures: u32, duration: Duration) -> Self {
+        self.ejection = Ejection {
+            failures: failures.max(1),
+            duration,
+        };
+        self
+    }
+
+    /// Number of members, ejected or not.
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    /// Returns true if the pool has no members.
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// Number of members currently in rotation.
+    pub fn available(&self) -> usize {
+        let now = Instant::now();
+        self.members
+            .iter()
+            .filter(|member| member.is_available(now))
+            .count()
+    }
+
+    fn first(&self) -> Result<&Member> {
+        self.members
+            .first()
+            .map(AsRef::as_ref)
+            .ok_or_else(|| AiError::Config("model pool has no members".into()))
+    }
+
+    /// Picks the member for the next call.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AiError::Config`] if the pool is empty, or if the strategy
+    /// is [`PoolStrategy::Weighted`] and every member has weight zero.
+    fn pick(&self) -> Result<(usize, Arc<Member>)> {
+        self.first()?;
+        let candidates: Vec<usize> = (0..self.members.len())
+            .filter(|&index| {
+                self.strategy != PoolStrategy::Weighted || self.members[index].weight > 0
+            })
+            .collect();
+        if candidates.is_empty() {
+            return Err(AiError::Config(
+                "model pool has no member with a weight above zero".into(),
+            ));
+        }
+
+        let now = Instant::now();
+        let available: Vec<usize> = candidates
+            .iter()
+            .copied()
+            .filter(|&index| self.members[index].is_available(now))
+            .collect();
+
+        let index = if available.is_empty() {
+            candidates
+                .into_iter()
+                .min_by_key(|&index| self.members[index].state().ejected_until)
+                .unwrap_or_default()
+        } else {
+            match self.strategy {
+                PoolStrategy::RoundRobin => {
+                    available[self.next.fetch_add(1, Ordering::Relaxed) % available.len()]
+                }
+                PoolStrategy::Weighted => self.pick_weighted(&available),
+                PoolStrategy::LeastInFlight => {
+                    // Rotate the start so ties do not always go to the
+                    // first member.
+                    let offset = self.next.fetch_add(1, Ordering::Relaxed);
+                    (0..available.len())
+                        .map(|i| available[(offset + i) % available.len()])
+                        .min_by_key(|&index| self.members[index].in_flight.load(Ordering::SeqCst))
+                        .unwrap_or_default()
+                }
+            }
+        };
+        Ok((index, self.members[index].clone()))
+    }
+
+    /// Smooth weighted round-robin: every member gains its weight, the
+    /// highest score is picked and pays back the total.
+    fn pick_weighted(&self, available: &[usize]) -> usize {
+        let mut scores = self.scores.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut best = available[0];
+        let mut total = 0;
+        for &index in available {
+            let weight = i64::from(self.members[index].weight);
+            scores[index] += weight;
+            total += weight;
+            if scores[index] > scores[best] {
+                best = index;
+            }
+        }
+        scores[best] -= total;
+        best
+    }
+
+    fn metadata(member: &Member, index: usize) -> serde_json::Value {
+        json!({
+            "provider": member.model.provider(),
+            "modelId": member.model.model_id(),
+            "index": index,
+        })
+    }
+}
+
+#[async_trait]
+impl LanguageModel for ModelPool {
+    /// The provider of the first member.
+    fn provider(&self) -> &str {
+        self.first()
+            .map_or("pool", |member| member.model.provider())
+    }
+
+    /// The id of the first member.
+    fn model_id(&self) -> &str {
+        self.first().map_or("", |member| member.model.model_id())
+    }
+
+    /// The capabilities every member has.
+    fn capabilities(&self) -> ModelCapabilities {
+        common_capabilities(self.members.iter().map(|member| member.model.as_ref()))
+    }
+
+    /// The member recorded under `"pool"`, or the first member.
+    fn served_by<'a>(
+        &'a self,
+        provider_metadata: Option<&'a ProviderMetadata>,
+    ) -> (&'a str, &'a str) {
+        let served = provider_metadata
+            .and_then(|metadata| metadata.get(METADATA_KEY)?.get("index")?.as_u64())
+            .and_then(|index| self.members.get(usize::try_from(index).ok()?));
+        match served {
+            Some(member) => member.model.served_by(provider_metadata),
+            None => (self.provider(), self.model_id()),
+        }
+    }
+
+    async fn do_generate(&self, options: CallOptions) -> Result<ModelResponse> {
+        let (index, member) = self.pick()?;
+        let _in_flight = InFlight::new(&member);
+
+        match member.model.do_generate(options).await {
+            Ok(mut response) => {
+                member.record_success();
+                response
+                    .provider_metadata
+                    .get_or_insert_with(Default::default)
+                    .insert(METADATA_KEY.to_string(), Self::metadata(&member, index));
+                Ok(response)
+            }
+            Err(error) => {
+                member.record_error(&error, self.ejection);
+                Err(error)
+            }
+        }
+    }
+
+    async fn do_stream(&self, options: CallOptions) -> Result<ModelStream> {
+        let (index, member) = self.pick()?;
+        let in_flight = InFlight::new(&member);
+
+        let ModelStream { stream, warnings } = match member.model.do_stream(options).await {
+            Ok(stream) => stream,
+            Err(error) => {
+                member.record_error(&error, self.ejection);
+                return Err(error);
+            }
+        };
+
+        let served = StreamPart::Metadata {
+            provider_metadata: [(METADATA_KEY.to_string(), Self::metadata(&member, index))].into(),
+        };
+        let ejection = self.ejection;
+        let stream = stream.map(move |item| {
+            let member = &in_flight.0;
+            match &item {
+                Ok(StreamPart::Finish { .. }) => member.record_success(),
+                Ok(StreamPart::Error(error)) => member.record_error(error, ejection),
+                Err(error) => member.record_error(error, ejection),
+                Ok(_) => {}
+            }
+            item
+        });
+
+        Ok(ModelStream {
+            stream: stream::once(async { Ok(served) }).chain(stream).boxed(),
+            warnings,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::StubModel;
+    use ai_core::{GenerateTextRequest, ModelPricing, StaticPricingTable, Usage};
+
+    fn member(model_id: &str, generate: fn() -> Result<ModelResponse>) -> Box<StubModel> {
+        Box::new(StubModel {
+            generate,
+            ..StubModel::new("azure", model_id)
+        })
+    }
+
+    fn ok() -> Result<ModelResponse> {
+        Ok(ModelResponse::default())
+    }
+
+    fn rate_limited() -> Result<ModelResponse> {
+        Err(AiError::RateLimit { retry_after: None })
+    }
+
+    async fn served_model_id(pool: &ModelPool) -> String {
+        let response = pool.do_generate(CallOptions::default()).await.unwrap();
+        response.provider_metadata.unwrap()["pool"]["modelId"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_round_robin_and_weighted_distribution() {
+        let pool = ModelPool::new(PoolStrategy::RoundRobin)
+            .with_member(member("eastus", ok), 1)
+            .with_member(member("westeurope", ok), 1);
+        let mut order = Vec::new();
+        for _ in 0..4 {
+            order.push(served_model_id(&pool).await);
+        }
+        assert_eq!(order, ["eastus", "westeurope", "eastus", "westeurope"]);
+
+        let pool = ModelPool::new(PoolStrategy::Weighted)
+            .with_member(member("eastus", ok), 3)
+            .with_member(member("westeurope", ok), 1);
+        let mut order = Vec::new();
+        for _ in 0..8 {
+            order.push(served_model_id(&pool).await);
+        }
+        assert_eq!(
+            order.iter().filter(|id| *id == "eastus").count(),
+            6,
+            "{order:?}"
+        );
+        // Smooth weighting interleaves the light member.
+        assert_eq!(order[..4], ["eastus", "eastus", "westeurope", "eastus"]);
+    }
+
+    #[tokio::test]
+    async fn test_least_in_flight_avoids_busy_member() {
+        let pool = ModelPool::new(PoolStrategy::LeastInFlight)
+            .with_member(member("eastus", ok), 1)
+            .with_member(member("westeurope", ok), 1);
+
+        // An unfinished stream keeps its member busy until dropped.
+        let stream = pool.do_stream(CallOptions::default()).await.unwrap();
+        let busy = if pool.members[0].in_flight.load(Ordering::SeqCst) == 1 {
+            "eastus"
+        } else {
+            "westeurope"
+        };
+        for _ in 0..3 {
+            assert_ne!(served_model_id(&pool).await, busy);
+        }
+
+        drop(stream);
+        assert!(pool
+            .members
+            .iter()
+            .all(|member| member.in_flight.load(Ordering::SeqCst) == 0));
+    }
+
+    #[tokio::test]
+    async fn test_ejects_member_after_repeated_rate_limits() {
+        let limited = member("eastus", rate_limited);
+        let calls = limited.calls.clone();
+        let pool = ModelPool::new(PoolStrategy::RoundRobin)
+            .with_member(limited, 1)
+            .with_member(member("westeurope", ok), 1)
+            .with_ejection(2, Duration::from_secs(60));
+
+        assert!(pool.do_generate(CallOptions::default()).await.is_err());
+        assert_eq!(served_model_id(&pool).await, "westeurope");
+        assert!(pool.do_generate(CallOptions::default()).await.is_err());
+        assert_eq!(pool.available(), 1);
+
+        for _ in 0..4 {
+            assert_eq!(served_model_id(&pool).await, "westeurope");
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // Other errors do not count towards ejection.
+        let pool = ModelPool::new(PoolStrategy::RoundRobin)
+            .with_member(
+                member("eastus", || Err(AiError::Validation("bad".into()))),
+                1,
+            )
+            .with_ejection(1, Duration::from_secs(60));
+        assert!(pool.do_generate(CallOptions::default()).await.is_err());
+        assert_eq!(pool.available(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_weighted_never_picks_zero_weight_members() {
+        let pool = ModelPool::new(PoolStrategy::Weighted)
+            .with_member(member("eastus", ok), 0)
+            .with_member(member("westeurope", ok), 1);
+        for _ in 0..3 {
+            assert_eq!(served_model_id(&pool).await, "westeurope");
+        }
+
+        let pool = ModelPool::new(PoolStrategy::Weighted).with_member(member("eastus", ok), 0);
+        let error = pool.do_generate(CallOptions::default()).await.unwrap_err();
+        assert!(matches!(error, AiError::Config(_)));
+
+        // Other strategies ignore weights.
+        let pool = ModelPool::new(PoolStrategy::RoundRobin).with_member(member("eastus", ok), 0);
+        assert_eq!(served_model_id(&pool).await, "eastus");
+    }
+
+    #[tokio::test]
+    async fn test_cost_is_estimated_for_the_serving_member() {
+        let pricing = StaticPricingTable::new()
+            .with("azure", "eastus", ModelPricing::new(1.0, 0.0))
+            .with("azure", "westeurope", ModelPricing::new(2.0, 0.0));
+        let million_tokens = || {
+            Ok(ModelResponse {
+                usage: Usage::new(1_000_000, 0),
+                ..Default::default()
+            })
+        };
+        let pool = ModelPool::new(PoolStrategy::Weighted)
+            .with_member(member("eastus", million_tokens), 0)
+            .with_member(member("westeurope", million_tokens), 1);
+
+        let request = GenerateTextRequest::new(pool, "hi").pricing(Arc::new(pricing));
+        let response = ai_core::generate_text(request).await.unwrap();
+        assert_eq!(response.cost.unwrap().total_cost(), 2.0);
+    }
+}
@@ -0,0 +1,290 @@
+//! Registry of providers keyed by name.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use ai_core::{EmbeddingModel, LanguageModel, ModelProvider};
+use ai_error::{AiError, Result};
+
+/// Creates a provider on first use, e.g. from environment variables.
+pub type ProviderFactory = Box<dyn Fn() -> Result<Arc<dyn ModelProvider>> + Send + Sync>;
+
+/// Resolves `"provider:model"` strings to models.
+///
+/// Providers can be registered as ready instances or as factories. A
+/// factory runs the first time one of its models is requested, so an
+/// application can register every provider it knows about while only
+/// configuring credentials for the ones it uses. A failed factory is retried
+/// on the next request.
+///
+/// The model id is everything after the first `:`, so ids that contain
+/// colons themselves (`"ollama:llama3:8b"`) resolve as expected.
+#[derive(Default)]
+pub struct ProviderRegistry {
+    factories: HashMap<String, ProviderFactory>,
+    providers: Mutex<HashMap<String, Arc<dyn ModelProvider>>>,
+}
+
+impl ProviderRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a registry with a factory for every backend enabled through
+    /// Cargo features.
+    ///
+    /// Each factory reads its provider's environment variables on first use,
+    /// so missing credentials only fail once a model of that provider is
+    /// requested.
+    #[allow(unused_mut)]
+    pub fn from_env() -> Self {
+        let mut registry = Self::new();
+        #[cfg(feature = "provider-openai")]
+        registry.register_factory("openai", || {
+            let provider = ai_providers_openai::OpenAiProvider::from_env()?;
+            Ok(Arc::new(provider) as Arc<dyn ModelProvider>)
+        });
+        #[cfg(feature = "provider-azure")]
+        registry.register_factory("azure", || {
+            let provider = ai_providers_azure::AzureOpenAiProvider::from_env()?;
+            Ok(Arc::new(provider) as Arc<dyn ModelProvider>)
+        });
+        #[cfg(feature = "provider-anthropic")]
+        registry.register_factory("anthropic", || {
+            let provider = ai_providers_anthropic::AnthropicProvider::from_env()?;
+            Ok(Arc::new(provider) as Arc<dyn ModelProvider>)
+        });
+        #[cfg(feature = "provider-google")]
+        registry.register_factory("google", || {
+            let provider = ai_providers_google::GoogleProvider::from_env()?;
+            Ok(Arc::new(provider) as Arc<dyn ModelProvider>)
+        });
+        #[cfg(feature = "provider-ollama")]
+        registry.register_factory("ollama", || {
+            let provider = ai_providers_ollama::OllamaProvider::from_env();
+            Ok(Arc::new(provider) as Arc<dyn ModelProvider>)
+        });
+        #[cfg(feature = "provider-bedrock")]
+        registry.register_factory("bedrock", || {
+            let provider = ai_providers_bedrock::BedrockProvider::from_env()?;
+            Ok(Arc::new(provider) as Arc<dyn ModelProvider>)
+        });
+        #[cfg(feature = "provider-mistral")]
+        registry.register_factory("mistral", || {
+            let provider = ai_providers_mistral::MistralProvider::from_env()?;
+            Ok(Arc::new(provider) as Arc<dyn ModelProvider>)
+        });
+        registry
+    }
+
+    /// Registers a provider under its [`ModelProvider::name`], replacing any
+    /// provider or factory with the same name.
+    pub fn register(&mut self, provider: impl ModelProvider + 'static) -> &mut Self {
+        let name = provider.name().to_string();
+        self.factories.remove(&name);
+        self.lock().insert(name, Arc::new(provider));
+        self
+    }
+
+    /// Registers a factory that creates the provider called `name` on first
+    /// use, replacing any provider or factory with the same name.
+    pub fn register_factory<F>(&mut self, name: impl Into<String>, factory: F) -> &mut Self
+    where
+        F: Fn() -> Result<Arc<dyn ModelProvider>> + Send + Sync + 'static,
+    {
+        let name = name.into();
+        self.lock().remove(&name);
+        self.factories.insert(name, Box::new(factory));
+        self
+    }
+
+    /// Builder form of [`register`](Self::register).
+    pub fn with(mut self, provider: impl ModelProvider + 'static) -> Self {
+        self.register(provider);
+        self
+    }
+
+    /// Names of all registered providers, sorted.
+    pub fn provider_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .lock()
+            .keys()
+            .chain(self.factories.keys())
+            .cloned()
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Returns the provider registered as `name`, running its factory if
+    /// needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AiError::Config`] if no provider is registered under `name`,
+    /// and propagates factory errors.
+    pub fn provider(&self, name: &str) -> Result<Arc<dyn ModelProvider>> {
+        if let Some(provider) = self.lock().get(name) {
+            return Ok(Arc::clone(provider));
+        }
+
+        let factory = self.factories.get(name).ok_or_else(|| {
+            AiError::Config(format!(
+                "unknown provider '{name}' (registered: {})",
+                self.provider_names().join(", ")
+            ))
+        })?;
+        let provider = factory()?;
+
+        // Another thread may have won the race; keep the first instance.
+        let mut providers = self.lock();
+        Ok(Arc::clone(
+            providers.entry(name.to_string()).or_insert(provider),
+        ))
+    }
+
+    /// Resolves a `"provider:model"` string to a language model.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AiError::Config`] for malformed ids, unknown providers, or
+    /// models the provider does not offer.
+    pub fn language_model(&self, id: &str) -> Result<Box<dyn LanguageModel>> {
+        let (provider, model_id) = split_id(id)?;
+        self.provider(provider)?.language_model(model_id)
+    }
+
+    /// Resolves a `"provider:model"` string to an embedding model.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AiError::Config`] for malformed ids, unknown providers, or
+    /// models the provider does not offer.
+    pub fn embedding_model(&self, id: &str) -> Result<Box<dyn EmbeddingModel>> {
+        let (provider, model_id) = split_id(id)?;
+        self.provider(provider)?.embedding_model(model_id)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Arc<dyn ModelProvider>>> {
+        self.providers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl fmt::Debug for ProviderRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProviderRegistry")
+            .field("providers", &self.provider_names())
+            .finish()
+    }
+}
+
+/// Splits `"provider:model"` at the first colon.
+fn split_id(id: &str) -> Result<(&str, &str)> {
+    match id.split_once(':') {
+        Some((provider, model_id)) if !provider.is_empty() && !model_id.is_empty() => {
+            Ok((provider, model_id))
+        }
+        _ => Err(AiError::Config(format!(
+            "invalid model id '{id}', expected 'provider:model'"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::StubModel;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Offers language models whose ids start with `known`.
+    struct StubProvider {
+        name: &'static str,
+    }
+
+    impl ModelProvider for StubProvider {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn language_model(&self, model_id: &str) -> Result<Box<dyn LanguageModel>> {
+            if !model_id.starts_with("known") {
+                return Err(AiError::Config(format!("unknown model '{model_id}'")));
+            }
+            Ok(Box::new(StubModel::new(self.name, model_id)))
+        }
+    }
+
+    #[test]
+    fn test_resolves_provider_model_strings() {
+        let registry = ProviderRegistry::new().with(StubProvider { name: "ollama" });
+
+        let model = registry.language_model("ollama:known:8b").unwrap();
+        assert_eq!(model.provider(), "ollama");
+        assert_eq!(model.model_id(), "known:8b");
+
+        for id in [
+            "ollama",
+            "ollama:",
+            ":known",
+            "openai:known",
+            "ollama:other",
+        ] {
+            assert!(
+                matches!(registry.language_model(id), Err(AiError::Config(_))),
+                "{id} should fail"
+            );
+        }
+        assert!(matches!(
+            registry.embedding_model("ollama:known"),
+            Err(AiError::Config(_))
+        ));
+    }
+
+    #[test]
+    fn test_from_env_registers_enabled_backends() {
+        let registry = ProviderRegistry::from_env();
+        let names = registry.provider_names();
+
+        assert_eq!(
+            names.contains(&"openai".to_string()),
+            cfg!(feature = "provider-openai")
+        );
+        assert_eq!(
+            names.contains(&"ollama".to_string()),
+            cfg!(feature = "provider-ollama")
+        );
+        #[cfg(feature = "provider-ollama")]
+        assert_eq!(registry.provider("ollama").unwrap().name(), "ollama");
+    }
+
+    #[test]
+    fn test_factory_runs_once_on_first_use() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let mut registry = ProviderRegistry::new();
+        registry.register_factory("openai", move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ok(Arc::new(StubProvider { name: "openai" }) as Arc<dyn ModelProvider>)
+        });
+        registry.register_factory("broken", || {
+            Err(AiError::Config("BROKEN_API_KEY is not set".into()))
+        });
+
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+        assert_eq!(registry.provider_names(), vec!["broken", "openai"]);
+
+        registry.language_model("openai:known-a").unwrap();
+        registry.language_model("openai:known-b").unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        match registry.language_model("broken:known") {
+            Err(error) => assert!(error.to_string().contains("BROKEN_API_KEY")),
+            Ok(_) => panic!("factory error should propagate"),
+        }
+    }
+}
@@ -0,0 +1,73 @@
+//! Test doubles shared by the ai_providers tests.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::stream::{self, StreamExt};
+
+use ai_core::{
+    CallOptions, FinishReason, LanguageModel, ModelCapabilities, ModelResponse, ModelStream,
+    StreamPart, Usage,
+};
+use ai_error::Result;
+
+/// Replays a fixed outcome and counts how often it was called.
+///
+/// By default calls succeed: generating returns an empty response and
+/// streaming finishes straight away.
+pub(crate) struct StubModel {
+    pub(crate) provider: String,
+    pub(crate) model_id: String,
+    pub(crate) generate: fn() -> Result<ModelResponse>,
+    pub(crate) stream: fn() -> Vec<Result<StreamPart>>,
+    pub(crate) calls: Arc<AtomicUsize>,
+}
+
+impl StubModel {
+    pub(crate) fn new(provider: impl Into<String>, model_id: impl Into<String>) -> Self {
+        Self {
+            provider: provider.into(),
+            model_id: model_id.into(),
+            generate: || Ok(ModelResponse::default()),
+            stream: || {
+                vec![Ok(StreamPart::Finish {
+                    finish_reason: FinishReason::Stop,
+                    usage: Usage::default(),
+                })]
+            },
+            calls: Arc::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl LanguageModel for StubModel {
+    fn provider(&self) -> &str {
+        &self.provider
+    }
+
+    fn model_id(&self) -> &str {
+        &self.model_id
+    }
+
+    fn capabilities(&self) -> ModelCapabilities {
+        ModelCapabilities {
+            streaming: true,
+            ..Default::default()
+        }
+    }
+
+    async fn do_generate(&self, _options: CallOptions) -> Result<ModelResponse> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        (self.generate)()
+    }
+
+    async fn do_stream(&self, _options: CallOptions) -> Result<ModelStream> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        Ok(ModelStream {
+            stream: stream::iter((self.stream)()).boxed(),
+            warnings: Vec::new(),
+        })
+    }
+}
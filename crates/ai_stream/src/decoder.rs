@@ -0,0 +1,154 @@
+//! Stateful decoding of provider event streams.
+//!
+//! Each provider turns its transport events (server-sent events, NDJSON
+//! lines, binary event frames) into output items while tracking state such as
+//! open tool calls. It implements that as an [`EventDecoder`], and
+//! [`decode_events`] drives the decoder over the transport stream.
+
+use std::collections::VecDeque;
+
+use futures::stream::{self, Stream, StreamExt};
+
+/// Queue of items produced by an [`EventDecoder`].
+#[derive(Debug)]
+pub struct Emitter<T, E> {
+    pending: VecDeque<Result<T, E>>,
+    closed: bool,
+}
+
+impl<T, E> Emitter<T, E> {
+    fn new() -> Self {
+        Self {
+            pending: VecDeque::new(),
+            closed: false,
+        }
+    }
+
+    /// Emits an item.
+    pub fn push(&mut self, item: T) {
+        self.pending.push_back(Ok(item));
+    }
+
+    /// Emits an error and ends the stream.
+    pub fn fail(&mut self, error: E) {
+        self.pending.push_back(Err(error));
+        self.closed = true;
+    }
+
+    /// Ends the stream once the items emitted so far have been read.
+    pub fn close(&mut self) {
+        self.closed = true;
+    }
+
+    /// Returns true once the stream has been ended.
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+}
+
+/// Turns transport events into items.
+pub trait EventDecoder {
+    /// Transport event, e.g. an [`SseEvent`](crate::SseEvent).
+    type Event;
+    /// Item produced for the consumer.
+    type Item;
+    /// Error type of the produced stream.
+    type Error;
+
+    /// Handles one event, emitting any items it completes.
+    fn decode(&mut self, event: Self::Event, out: &mut Emitter<Self::Item, Self::Error>);
+
+    /// Handles the end of the transport stream.
+    ///
+    /// Only called if the decoder has not closed the emitter itself. The
+    /// stream ends after the items emitted here.
+    fn end(&mut self, out: &mut Emitter<Self::Item, Self::Error>);
+}
+
+/// Runs `decoder` over `events` and yields everything it emits.
+///
+/// Transport errors are converted into the decoder's error type and end the
+/// stream.
+pub fn decode_events<S, D, E>(
+    events: S,
+    decoder: D,
+) -> impl Stream<Item = Result<D::Item, D::Error>> + Send
+where
+    S: Stream<Item = Result<D::Event, E>> + Send + Unpin,
+    D: EventDecoder + Send,
+    D::Item: Send,
+    D::Error: Send,
+    E: Into<D::Error>,
+{
+    let state = (events, decoder, Emitter::new());
+    stream::unfold(state, |(mut events, mut decoder, mut out)| async move {
+        loop {
+            if let Some(item) = out.pending.pop_front() {
+                return Some((item, (events, decoder, out)));
+            }
+            if out.closed {
+                return None;
+            }
+            match events.next().await {
+                Some(Ok(event)) => decoder.decode(event, &mut out),
+                Some(Err(error)) => out.fail(error.into()),
+                None => {
+                    decoder.end(&mut out);
+                    out.close();
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sums numbers until it sees zero; a stream without zero is truncated.
+    struct Summer {
+        total: u32,
+    }
+
+    impl EventDecoder for Summer {
+        type Event = u32;
+        type Item = u32;
+        type Error = String;
+
+        fn decode(&mut self, event: u32, out: &mut Emitter<u32, String>) {
+            if event == 0 {
+                out.push(self.total);
+                out.close();
+            } else {
+                self.total += event;
+            }
+        }
+
+        fn end(&mut self, out: &mut Emitter<u32, String>) {
+            out.fail(format!("ended at {}", self.total));
+        }
+    }
+
+    async fn run(events: Vec<Result<u32, &'static str>>) -> Vec<Result<u32, String>> {
+        decode_events(stream::iter(events), Summer { total: 0 })
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_decoder_closes_stream() {
+        assert_eq!(run(vec![Ok(1), Ok(2), Ok(0), Ok(5)]).await, vec![Ok(3)]);
+    }
+
+    #[tokio::test]
+    async fn test_end_and_transport_errors() {
+        assert_eq!(
+            run(vec![Ok(1), Ok(2)]).await,
+            vec![Err("ended at 3".to_string())]
+        );
+        assert_eq!(
+            run(vec![Ok(1), Err("reset"), Ok(0)]).await,
+            vec![Err("reset".to_string())]
+        );
+    }
+}
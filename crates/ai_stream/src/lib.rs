@@ -1,14 +1,24 @@
-pub fn add(left: usize, right: usize) -> usize {
-    left + right
-}
+//! Stream primitives for the AI SDK.
+//!
+//! Provider responses arrive as a single async stream, but applications often
+//! need several independent readers of it (an HTTP response, a logger and a
+//! result aggregator). [`Multicast`] turns one stream into any number of
+//! [`Subscriber`]s that each observe every item.
+//!
+//! The [`sse`] module decodes `text/event-stream` bodies, which most providers
+//! use for streaming responses; [`ndjson`] decodes newline-delimited JSON.
+//! The [`decoder`] module drives the provider-specific state machines that
+//! turn those events into output.
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+#![forbid(unsafe_code)]
+#![warn(missing_docs, rust_2018_idioms)]
 
-    #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
-    }
-}
+pub mod decoder;
+pub mod multicast;
+pub mod ndjson;
+pub mod sse;
+
+pub use decoder::{decode_events, Emitter, EventDecoder};
+pub use multicast::{Multicast, Subscriber};
+pub use ndjson::NdjsonDecoder;
+pub use sse::{SseDecoder, SseEvent};
@@ -0,0 +1,187 @@
+//! Replaying fan-out over a single source stream.
+
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::task::{Context, Poll, Wake, Waker};
+
+use futures::stream::{BoxStream, Stream, StreamExt};
+
+/// Shares one source stream between any number of subscribers.
+///
+/// The source is polled lazily by whichever subscriber runs out of buffered
+/// items first. Every item is buffered so each subscriber, including ones
+/// created after the source finished, observes the complete sequence.
+pub struct Multicast<T> {
+    shared: Arc<Mutex<Shared<T>>>,
+}
+
+struct Shared<T> {
+    source: Option<BoxStream<'static, T>>,
+    buffer: Vec<T>,
+    waiters: Arc<Waiters>,
+}
+
+impl<T: Clone + Send + 'static> Multicast<T> {
+    /// Wraps `source` so it can be consumed by several subscribers.
+    pub fn new<S>(source: S) -> Self
+    where
+        S: Stream<Item = T> + Send + 'static,
+    {
+        Self {
+            shared: Arc::new(Mutex::new(Shared {
+                source: Some(source.boxed()),
+                buffer: Vec::new(),
+                waiters: Arc::new(Waiters::default()),
+            })),
+        }
+    }
+
+    /// Returns a new subscriber that starts at the first item of the source.
+    pub fn subscribe(&self) -> Subscriber<T> {
+        Subscriber {
+            shared: Arc::clone(&self.shared),
+            position: 0,
+        }
+    }
+}
+
+impl<T> Clone for Multicast<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+/// An independent reader of a [`Multicast`] stream.
+pub struct Subscriber<T> {
+    shared: Arc<Mutex<Shared<T>>>,
+    position: usize,
+}
+
+impl<T: Clone> Stream for Subscriber<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let this = self.get_mut();
+        let mut shared = lock(&this.shared);
+
+        if let Some(item) = shared.buffer.get(this.position) {
+            this.position += 1;
+            return Poll::Ready(Some(item.clone()));
+        }
+
+        let Shared {
+            source,
+            buffer,
+            waiters,
+        } = &mut *shared;
+        let Some(stream) = source.as_mut() else {
+            return Poll::Ready(None);
+        };
+
+        // Poll the source with a waker that notifies every waiting subscriber,
+        // so progress is never tied to a subscriber that may since have been
+        // dropped.
+        waiters.register(cx.waker());
+        let waker = Waker::from(Arc::clone(waiters));
+        let mut source_cx = Context::from_waker(&waker);
+
+        match stream.poll_next_unpin(&mut source_cx) {
+            Poll::Ready(Some(item)) => {
+                buffer.push(item.clone());
+                this.position += 1;
+                waiters.wake_all();
+                Poll::Ready(Some(item))
+            }
+            Poll::Ready(None) => {
+                *source = None;
+                waiters.wake_all();
+                Poll::Ready(None)
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Waiters {
+    wakers: Mutex<Vec<Waker>>,
+}
+
+impl Waiters {
+    fn register(&self, waker: &Waker) {
+        let mut wakers = lock(&self.wakers);
+        if !wakers.iter().any(|w| w.will_wake(waker)) {
+            wakers.push(waker.clone());
+        }
+    }
+
+    fn wake_all(&self) {
+        for waker in lock(&self.wakers).drain(..) {
+            waker.wake();
+        }
+    }
+}
+
+impl Wake for Waiters {
+    fn wake(self: Arc<Self>) {
+        self.wake_all();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.wake_all();
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::channel::mpsc;
+    use futures::stream;
+
+    #[tokio::test]
+    async fn test_subscribers_see_every_item() {
+        let multicast = Multicast::new(stream::iter(vec![1, 2, 3]));
+        let first = multicast.subscribe();
+        let second = multicast.subscribe();
+
+        assert_eq!(first.collect::<Vec<_>>().await, vec![1, 2, 3]);
+        assert_eq!(second.collect::<Vec<_>>().await, vec![1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_late_subscriber_replays_buffer() {
+        let multicast = Multicast::new(stream::iter(vec!["a", "b"]));
+        multicast.subscribe().collect::<Vec<_>>().await;
+
+        assert_eq!(
+            multicast.subscribe().collect::<Vec<_>>().await,
+            vec!["a", "b"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_subscribers_are_woken() {
+        let (tx, rx) = mpsc::unbounded();
+        let multicast = Multicast::new(rx);
+
+        let readers: Vec<_> = (0..3)
+            .map(|_| tokio::spawn(multicast.subscribe().collect::<Vec<u32>>()))
+            .collect();
+
+        for i in 0..5 {
+            tx.unbounded_send(i).unwrap();
+            tokio::task::yield_now().await;
+        }
+        drop(tx);
+
+        for reader in readers {
+            assert_eq!(reader.await.unwrap(), vec![0, 1, 2, 3, 4]);
+        }
+    }
+}
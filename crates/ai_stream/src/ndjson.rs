@@ -0,0 +1,100 @@
+//! Newline-delimited JSON decoding.
+//!
+//! Some backends (notably Ollama) stream one JSON document per line instead
+//! of server-sent events. [`NdjsonDecoder`] splits arbitrarily chunked bytes
+//! into lines, and [`decode`] applies it to a byte stream such as
+//! `reqwest::Response::bytes_stream`. Parsing each line is left to the
+//! caller.
+
+use std::collections::VecDeque;
+
+use futures::stream::{self, Stream, StreamExt};
+
+/// Incremental splitter for newline-delimited bodies.
+#[derive(Debug, Default)]
+pub struct NdjsonDecoder {
+    buffer: Vec<u8>,
+}
+
+impl NdjsonDecoder {
+    /// Creates an empty decoder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds a chunk of bytes and returns every non-empty line it completes.
+    pub fn feed(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(chunk);
+
+        let mut lines = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            lines.extend(non_empty(&line));
+        }
+        lines
+    }
+
+    /// Flushes a line left unterminated at the end of the stream.
+    pub fn finish(&mut self) -> Option<String> {
+        non_empty(&std::mem::take(&mut self.buffer))
+    }
+}
+
+fn non_empty(line: &[u8]) -> Option<String> {
+    let line = String::from_utf8_lossy(line);
+    let line = line.trim();
+    (!line.is_empty()).then(|| line.to_string())
+}
+
+/// Decodes a byte stream into lines.
+///
+/// Transport errors are passed through and end the stream.
+pub fn decode<S, B, E>(bytes: S) -> impl Stream<Item = Result<String, E>> + Send
+where
+    S: Stream<Item = Result<B, E>> + Send + Unpin,
+    B: AsRef<[u8]>,
+    E: Send,
+{
+    let state = (bytes, NdjsonDecoder::new(), VecDeque::new(), false);
+    stream::unfold(
+        state,
+        |(mut bytes, mut decoder, mut pending, mut done)| async move {
+            loop {
+                if let Some(line) = pending.pop_front() {
+                    return Some((Ok(line), (bytes, decoder, pending, done)));
+                }
+                if done {
+                    return None;
+                }
+                match bytes.next().await {
+                    Some(Ok(chunk)) => pending.extend(decoder.feed(chunk.as_ref())),
+                    Some(Err(error)) => {
+                        return Some((Err(error), (bytes, decoder, pending, true)));
+                    }
+                    None => {
+                        pending.extend(decoder.finish());
+                        done = true;
+                    }
+                }
+            }
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_decode_splits_chunked_lines() {
+        let chunks: Vec<Result<&[u8], ()>> = vec![
+            Ok(b"{\"a\":1}\n{\"a\""),
+            Ok(b":2}\r\n\n"),
+            Ok(b"{\"done\":true}"),
+        ];
+        let lines: Vec<_> = decode(stream::iter(chunks)).collect().await;
+
+        let lines: Vec<_> = lines.into_iter().map(Result::unwrap).collect();
+        assert_eq!(lines, vec!["{\"a\":1}", "{\"a\":2}", "{\"done\":true}"]);
+    }
+}
@@ -0,0 +1,160 @@
+//! Server-sent events decoding.
+//!
+//! Providers stream responses as `text/event-stream`. [`SseDecoder`] turns
+//! arbitrarily chunked bytes into [`SseEvent`]s, and [`decode`] applies it to
+//! a byte stream such as `reqwest::Response::bytes_stream`.
+
+use std::collections::VecDeque;
+
+use futures::stream::{self, Stream, StreamExt};
+
+/// A single server-sent event.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SseEvent {
+    /// Event type from the `event:` field, if present.
+    pub event: Option<String>,
+    /// Payload, with multiple `data:` lines joined by `\n`.
+    pub data: String,
+    /// Last event id from the `id:` field, if present.
+    pub id: Option<String>,
+}
+
+/// Incremental parser for `text/event-stream` bodies.
+#[derive(Debug, Default)]
+pub struct SseDecoder {
+    buffer: Vec<u8>,
+    current: SseEvent,
+    has_data: bool,
+}
+
+impl SseDecoder {
+    /// Creates an empty decoder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds a chunk of bytes and returns every event it completes.
+    pub fn feed(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        self.buffer.extend_from_slice(chunk);
+
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(['\n', '\r']);
+            if let Some(event) = self.process_line(line) {
+                events.push(event);
+            }
+        }
+        events
+    }
+
+    /// Flushes an event left unterminated at the end of the stream.
+    pub fn finish(&mut self) -> Option<SseEvent> {
+        if !self.buffer.is_empty() {
+            let line = String::from_utf8_lossy(&std::mem::take(&mut self.buffer)).into_owned();
+            if let Some(event) = self.process_line(line.trim_end_matches('\r')) {
+                return Some(event);
+            }
+        }
+        self.dispatch()
+    }
+
+    fn process_line(&mut self, line: &str) -> Option<SseEvent> {
+        if line.is_empty() {
+            return self.dispatch();
+        }
+        if line.starts_with(':') {
+            return None;
+        }
+
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+        match field {
+            "data" => {
+                if self.has_data {
+                    self.current.data.push('\n');
+                }
+                self.current.data.push_str(value);
+                self.has_data = true;
+            }
+            "event" => self.current.event = Some(value.to_string()),
+            "id" => self.current.id = Some(value.to_string()),
+            _ => {}
+        }
+        None
+    }
+
+    fn dispatch(&mut self) -> Option<SseEvent> {
+        if !self.has_data {
+            self.current.event = None;
+            return None;
+        }
+        self.has_data = false;
+        Some(std::mem::take(&mut self.current))
+    }
+}
+
+/// Decodes a byte stream into server-sent events.
+///
+/// Transport errors are passed through and end the stream.
+pub fn decode<S, B, E>(bytes: S) -> impl Stream<Item = Result<SseEvent, E>> + Send
+where
+    S: Stream<Item = Result<B, E>> + Send + Unpin,
+    B: AsRef<[u8]>,
+    E: Send,
+{
+    let state = (bytes, SseDecoder::new(), VecDeque::new(), false);
+    stream::unfold(
+        state,
+        |(mut bytes, mut decoder, mut pending, mut done)| async move {
+            loop {
+                if let Some(event) = pending.pop_front() {
+                    return Some((Ok(event), (bytes, decoder, pending, done)));
+                }
+                if done {
+                    return None;
+                }
+                match bytes.next().await {
+                    Some(Ok(chunk)) => pending.extend(decoder.feed(chunk.as_ref())),
+                    Some(Err(error)) => {
+                        return Some((Err(error), (bytes, decoder, pending, true)));
+                    }
+                    None => {
+                        pending.extend(decoder.finish());
+                        done = true;
+                    }
+                }
+            }
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decoder_handles_split_chunks() {
+        let mut decoder = SseDecoder::new();
+        assert!(decoder.feed(b": keep-alive\n\nda").is_empty());
+        assert!(decoder.feed(b"ta: {\"a\":").is_empty());
+
+        let events = decoder.feed(b"1}\r\n\r\nevent: done\ndata: x\ndata: y\n\n");
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].data, "{\"a\":1}");
+        assert_eq!(events[1].event.as_deref(), Some("done"));
+        assert_eq!(events[1].data, "x\ny");
+    }
+
+    #[tokio::test]
+    async fn test_decode_stream_flushes_trailing_event() {
+        let chunks: Vec<Result<&[u8], ()>> = vec![Ok(b"data: one\n\n"), Ok(b"data: two")];
+        let events: Vec<_> = decode(stream::iter(chunks)).collect().await;
+
+        let data: Vec<_> = events.into_iter().map(|e| e.unwrap().data).collect();
+        assert_eq!(data, vec!["one", "two"]);
+    }
+}